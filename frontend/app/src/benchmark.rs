@@ -141,6 +141,7 @@ fn timing_view(timing: &Timing, batch_size: usize) -> Html {
             <tr><td>{ "Radar" }</td><td>{ format!("{:.1}ms", timing.radar * c) }</td><td>{ pct(timing.radar) }</td></tr>
             <tr><td>{ "Radio" }</td><td>{ format!("{:.1}ms", timing.radio * c) }</td><td>{ pct(timing.radio) }</td></tr>
             <tr><td>{ "VM" }</td><td>{ format!("{:.1}ms", timing.vm * c) }</td><td>{ pct(timing.vm) }</td></tr>
+            <tr><td>{ "Debug" }</td><td>{ format!("{:.1}ms", timing.debug * c) }</td><td>{ pct(timing.debug) }</td></tr>
             <tr><td>{ "Ship" }</td><td>{ format!("{:.1}ms", timing.ship * c) }</td><td>{ pct(timing.ship) }</td></tr>
             <tr><td>{ "Bullet" }</td><td>{ format!("{:.1}ms", timing.bullet * c) }</td><td>{ pct(timing.bullet) }</td></tr>
             <tr><td>{ "Scenario" }</td><td>{ format!("{:.1}ms", timing.scenario * c) }</td><td>{ pct(timing.scenario) }</td></tr>
@@ -66,11 +66,21 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "class() → Class" }</code>{ ": Returns the ship class." }</li>
               <li><code>{ "position() → Vec2" }</code>{ ": Get the current position in meters." }</li>
               <li><code>{ "velocity() → Vec2" }</code>{ ": Get the current velocity in m/s." }</li>
+              <li><code>{ "bearing_to(point: Vec2) → f64" }</code>{ ": Get the direction to a point in radians." }</li>
+              <li><code>{ "range_to(point: Vec2) → f64" }</code>{ ": Get the distance to a point in meters." }</li>
+              <li><code>{ "to_local(world_point: Vec2) → Vec2" }</code>{ ": Convert a point to the ship's frame (x forward, y left)." }</li>
+              <li><code>{ "to_world(local_point: Vec2) → Vec2" }</code>{ ": Convert a point from the ship's frame to world coordinates." }</li>
               <li><code>{ "heading() → f64" }</code>{ ": Get the current heading in radians." }</li>
               <li><code>{ "angular_velocity() → f64" }</code>{ ": Get the current angular velocity in radians/s." }</li>
               <li><code>{ "health() → f64" }</code>{ ": Current health." }</li>
+              <li><code>{ "shield() → f64" }</code>{ ": Current shield strength. Absorbs damage before health." }</li>
+              <li><code>{ "is_burning() → bool" }</code>{ ": Whether the ship is on fire and losing health over time." }</li>
               <li><code>{ "fuel() → f64" }</code>{ ": Current fuel (delta-v)." }</li>
+              <li><code>{ "status() → ShipStatus" }</code>{ ": Health, shield, fuel, ammo, reload times, heat and impulse readiness in one struct." }</li>
+              <li><code>{ "thermal_signature() → f64" }</code>{ ": Average acceleration over the last second. Hard burns can make the ship easier to detect on radar." }</li>
               <li><code>{ "accelerate(acceleration: Vec2)" }</code>{ ": Accelerate the ship. Units are m/s²." }</li>
+              <li><code>{ "impulse(direction: Vec2, magnitude: f64)" }</code>{ ": Instantly change velocity by up to 100 m/s (fighters only). Recharges over 10 s." }</li>
+              <li><code>{ "impulse_ready() → bool" }</code>{ ": Returns true if impulse can be used." }</li>
               <li><code>{ "turn(speed: f64)" }</code>{ ": Rotate the ship. Unit is radians/s." }</li>
               <li><code>{ "torque(acceleration: f64)" }</code>{ ": Angular acceleration. Unit is radians/s²." }</li>
               <li><code>{ "max_forward_acceleration() -> f64" }</code>{ ": Maximum forward acceleration." }</li>
@@ -83,17 +93,44 @@ pub fn documentation(props: &DocumentationProps) -> Html {
             <ul>
               <li><code>{ "fire(index: usize)" }</code>{ ": Fire a weapon (gun or missile launcher)." }</li>
               <li><code>{ "aim(index: usize, angle: f64)" }</code>{ ": Aim a weapon (for weapons on a turret)." }</li>
+              <li><code>{ "fire_beam(heading: f64, range: f64)" }</code>{ ": Fire the beam weapon this tick, damaging the first enemy along the ray (ships with a beam only)." }</li>
+              <li><code>{ "beam_energy() → f64" }</code>{ ": Energy left for the beam weapon." }</li>
+              <li><code>{ "ammo(index: usize) → f64" }</code>{ ": Rounds a gun has left to fire, infinite if unlimited." }</li>
+              <li><code>{ "heat(index: usize) → f64" }</code>{ ": A gun's heat from 0 to 1; it jams at 1." }</li>
+              <li><code>{ "is_jammed(index: usize) → bool" }</code>{ ": Whether a gun overheated and is waiting out its cooldown." }</li>
+              <li><code>{ "weapon_cooldown_remaining(index: usize) → u32" }</code>{ ": Ticks before a jammed gun can fire again." }</li>
+              <li><code>{ "gun_muzzle_velocity(index: usize) → f64" }</code>{ ": Speed of a gun's bullets relative to the ship, for computing lead." }</li>
+              <li><code>{ "gun_bullet_ttl(index: usize) → f64" }</code>{ ": Lifetime of a gun's bullets in seconds." }</li>
+              <li><code>{ "fire_if_locked(contact: &ScanResult, cone: f64) → bool" }</code>{ ": Fire gun 0 with lead if the contact is within the cone and in range." }</li>
+              <li><code>{ "station_keep(leader_position: Vec2, leader_velocity: Vec2, offset: Vec2) → Vec2" }</code>{ ": Get the acceleration to hold a position relative to a leader." }</li>
+              <li><code>{ "orbit(center: Vec2, radius: f64, direction: i64) → Vec2" }</code>{ ": Get the acceleration to circle a point, counter-clockwise if direction is non-negative." }</li>
               <li><code>{ "explode()" }</code>{ ": Self-destruct." }</li>
+              <li><code>{ "set_proximity_fuze(radius: f64)" }</code>{ ": Explode automatically when an enemy ship comes within radius meters (0 to disarm)." }</li>
             </ul>
 
             <h2>{ "Radar" }</h2>
             <ul>
               <li><code>{ "set_radar_heading(angle: f64)" }</code>{ ": Point the radar at the given heading." }</li>
               <li><code>{ "radar_heading() -> f64" }</code>{ ": Get current radar heading." }</li>
-              <li><code>{ "set_radar_width(width: f64)" }</code>{ ": Adjust the width of the radar beam (in radians)." }</li>
+              <li><code>{ "radar_applied_heading() -> f64" }</code>{ ": Get the heading the beam is pointed at, which lags while slewing." }</li>
+              <li><code>{ "radar_slew_rate() -> f64" }</code>{ ": Get the maximum radar turn rate (radians/s)." }</li>
+              <li><code>{ "radar_last_beam() -> BeamInfo" }</code>{ ": Get the heading, width, bearings and approximate range of the beam used by the last scan." }</li>
+              <li><code>{ "set_radar_width(width: f64)" }</code>{ ": Adjust the width of the radar beam (in radians). TAU selects omnidirectional mode." }</li>
               <li><code>{ "radar_width() -> f64" }</code>{ ": Get current radar width." }</li>
               <li><code>{ "scan() → Option<ScanResult>" }</code>{ ": Find an enemy ship illuminated by the radar." }</li>
-              <li><code>{ "struct ScanResult { position: Vec2, velocity: Vec2 }" }</code></li>
+              <li><code>{ "struct ScanResult { position: Vec2, velocity: Vec2, rssi: f64, snr: f64 }" }</code></li>
+              <li><code>{ "ScanResult::distance() → f64" }</code>{ ": Distance from your ship to the contact's reported position." }</li>
+              <li><code>{ "closest_contact() → Option<ScanResult>" }</code>{ ": Get the nearest contact in the beam instead of the strongest." }</li>
+              <li><code>{ "scan_all() → Vec<ScanResult>" }</code>{ ": Get every contact in the beam, strongest first." }</li>
+              <li><code>{ "ping() → f64" }</code>{ ": Replace the next scan with an omnidirectional pulse and get the range to the nearest enemy from the last one." }</li>
+              <li><code>{ "radar_energy() → f64" }</code>{ ": Get the energy left in the radar's budget (infinite if unlimited)." }</li>
+              <li><code>{ "radar_jammer_bearing() → Option<f64>" }</code>{ ": Get the bearing to the loudest enemy jammer in the beam." }</li>
+              <li><code>{ "track_radar()" }</code>{ ": Restrict scan() to the current contact until it leaves the beam." }</li>
+              <li><code>{ "untrack_radar()" }</code>{ ": Stop tracking and scan the whole beam again." }</li>
+              <li><code>{ "radar_tracking() → bool" }</code>{ ": Check whether the radar is tracking a contact." }</li>
+              <li><code>{ "radar_tracks() → Vec<RadarTrack>" }</code>{ ": Get filtered contacts seen in the last few ticks, with id, position, velocity and confidence." }</li>
+              <li><code>{ "RadarTrack::uncertainty() → f64" }</code>{ ": How far a lost contact may have moved from its dead-reckoned position." }</li>
+              <li><code>{ "nearest_friendly() → Option<FriendlyContact>" }</code>{ ": Get the class, position and velocity of the closest teammate within 20 km. Unaffected by radar." }</li>
             </ul>
 
             <h2>{ "Advanced Radar" }</h2>
@@ -102,6 +139,9 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "radar_min_distance() -> f64" }</code>{ ": Get current minimum distance filter." }</li>
               <li><code>{ "set_radar_max_distance(dist: f64)" }</code>{ ": Set the maximum distance filter." }</li>
               <li><code>{ "radar_max_distance() -> f64" }</code>{ ": Get current maximum distance filter." }</li>
+              <li><code>{ "set_radar_min_rssi(dbm: f64)" }</code>{ ": Set the radar sensitivity (minimum detectable RSSI)." }</li>
+              <li><code>{ "radar_min_rssi() -> f64" }</code>{ ": Get current radar sensitivity." }</li>
+              <li><code>{ "set_radar_bearing_only_rssi(dbm: Option<f64>)" }</code>{ ": Report contacts between this and the minimum RSSI with only a bearing (ScanResult::bearing_only)." }</li>
               <li><code>{ "set_radar_ecm_mode(mode: EcmMode)" }</code>{ ": Set the Electronic Counter Measures (ECM) mode." }</li>
               <li><code>{ "EcmMode::None" }</code>{ ": No ECM, radar will operate normally." }</li>
               <li><code>{ "EcmMode::Noise" }</code>{ ": Decrease the enemy radar's signal to noise ratio, making it more difficult to detect targets and reducing accuracy of returned contacts." }</li>
@@ -166,10 +206,12 @@ pub fn documentation(props: &DocumentationProps) -> Html {
             <h2>{ "Debugging" }</h2>
             <ul>
               <li><code>{ "debug!(...)" }</code>{ ": Add text to be displayed when the ship is selected by clicking on it. Works just like " }<code>{ "println!" }</code>{ "." }</li>
+              <li><code>{ "debug_set(key: &str, value: f64)" }</code>{ ": Set a named value to be displayed when the ship is selected. Cleared every tick." }</li>
               <li><code>{ "draw_line(v0: Vec2, v1: Vec2, color: u32)" }</code>{ ": Draw a line visible when the ship is selected. Color is 24-bit RGB." }</li>
               <li><code>{ "draw_triangle(center: Vec2, radius: f64, color: u32)" }</code>{ ": Draw a triangle visible when the ship is selected." }</li>
               <li><code>{ "draw_square(center: Vec2, radius: f64, color: u32)" }</code>{ ": Draw a square visible when the ship is selected." }</li>
               <li><code>{ "draw_diamond(center: Vec2, radius: f64, color: u32)" }</code>{ ": Draw a diamond visible when the ship is selected." }</li>
+              <li><code>{ "debug_vector(origin: Vec2, vec: Vec2, color: u32)" }</code>{ ": Draw an arrow along vec, at most 1 km long, visible when the ship is selected." }</li>
               <li><code>{ "draw_polygon(center: Vec2, radius: f64, sides: i32, angle: f64, color: u32)" }</code>{ ": Draw a regular polygon visible when the ship is selected." }</li>
               <li><code>{ "draw_text!(topleft: Vec2, color: u32, ...)" }</code>{ ": Draw text. Works like " }<code>{ "println!" }</code>{ "." }</li>
             </ul>
@@ -179,10 +221,15 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "current_tick() → f64" }</code>{ ": Returns the number of ticks elapsed since the simulation started." }</li>
               <li><code>{ "current_time() → f64" }</code>{ ": Returns the number of seconds elapsed since the simulation started." }</li>
               <li><code>{ "angle_diff(a: f64, b: f64) → f64" }</code>{ ": Returns the shortest (possibly negative) distance between two angles." }</li>
+              <li><code>{ "aim_lead_moving(target_position: Vec2, target_velocity: Vec2, own_velocity: Vec2, muzzle_speed: f64) → Vec2" }</code>{ ": Returns the direction to fire to hit a moving target, with target_position relative to the shooter." }</li>
+              <li><code>{ "closest_point_of_approach(position_a: Vec2, velocity_a: Vec2, position_b: Vec2, velocity_b: Vec2) → (f64, f64)" }</code>{ ": Returns the time until two objects moving at constant velocity are nearest, and their distance then." }</li>
               <li><code>{ "rand(low: f64, high: f64) → f64" }</code>{ ": Get a random number." }</li>
               <li><code>{ "target() → Vec2" }</code>{ ": Used in some scenarios, returns the position of the target." }</li>
               <li><code>{ "target_velocity() → Vec2" }</code>{ ": Used in some scenarios, returns the velocity of the target." }</li>
               <li><code>{ "seed() → u128" }</code>{ ": Returns a seed useful for initializing a random number generator." }</li>
+              <li><code>{ "scenario_name() → &str" }</code>{ ": Returns the name of the current scenario." }</li>
+              <li><code>{ "world_size() → f64" }</code>{ ": Returns the width of the square world in meters." }</li>
+              <li><code>{ "team_count() → usize" }</code>{ ": Returns the number of teams in the scenario." }</li>
             </ul>
 
             <h2>{ "Extra Crates" }</h2>
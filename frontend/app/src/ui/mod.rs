@@ -527,6 +527,17 @@ impl UI {
                 .and_then(|s| s.debug_text.get(&self.picked_ship_id.unwrap()))
                 .cloned()
                 .unwrap_or_default();
+            let debug_values_text: String = self
+                .snapshot
+                .as_ref()
+                .and_then(|s| s.debug_values.get(&self.picked_ship_id.unwrap()))
+                .map(|values| {
+                    values
+                        .iter()
+                        .map(|(key, value)| format!("{key}: {value}\n"))
+                        .collect()
+                })
+                .unwrap_or_default();
             if let Some(elem) = self.picked_ref.cast::<Element>() {
                 let fuel_text = if let Some(fuel) = fuel {
                     format!("Fuel: {:.0}\n", fuel)
@@ -546,7 +557,7 @@ impl UI {
                     "".to_string()
                 };
                 elem.set_text_content(Some(&format!(
-                    "{class:?}\nTeam: {team:?}\nHealth: {health:.0}\n{fuel_text}{active_abilities_text}{debug_text}"
+                    "{class:?}\nTeam: {team:?}\nHealth: {health:.0}\n{fuel_text}{active_abilities_text}{debug_values_text}{debug_text}"
                 )));
             }
         } else if let Some(elem) = self.picked_ref.cast::<Element>() {
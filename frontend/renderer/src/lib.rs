@@ -21,6 +21,7 @@ use grid_renderer::GridRenderer;
 use line_renderer::LineRenderer;
 use nalgebra::{point, vector, Matrix4, Point2};
 use oort_api::Text;
+use oort_simulator::color;
 use oort_simulator::simulation::Line;
 use oort_simulator::snapshot::Snapshot;
 use particle_renderer::ParticleRenderer;
@@ -210,6 +211,24 @@ impl Renderer {
                     }
                 }
             }
+            for ship in snapshot.ships.iter() {
+                if !self.debug && self.picked_ship != Some(ship.id) {
+                    continue;
+                }
+                let Some(target) = ship
+                    .lock_target
+                    .and_then(|id| snapshot.ships.iter().find(|s| s.id == id))
+                else {
+                    continue;
+                };
+                let mut color = color::team(ship.team);
+                color.w = 0.5;
+                lines.push(Line {
+                    a: ship.position,
+                    b: target.position,
+                    color,
+                });
+            }
             self.line_renderer.upload(&self.projection_matrix, &lines)
         };
 
@@ -1,6 +1,7 @@
 use super::{buffer_arena, geometry, glutil};
 use glutil::VertexAttribBuilder;
 use nalgebra::{vector, Matrix4, Vector4};
+use oort_simulator::color;
 use oort_simulator::model;
 use oort_simulator::ship::ShipClass;
 use oort_simulator::snapshot::{ShipSnapshot, Snapshot};
@@ -91,13 +92,7 @@ void main() {
     }
 
     pub fn team_color(team: i32) -> Vector4<f32> {
-        match team {
-            0 => vector![0.99, 0.98, 0.00, 1.00],
-            1 => vector![0.99, 0.00, 0.98, 1.00],
-            2 => vector![0.13, 0.50, 0.73, 1.00],
-            9 => vector![0.40, 0.40, 0.40, 1.00],
-            _ => vector![1.0, 1.0, 1.0, 1.0],
-        }
+        color::team(team)
     }
 
     pub fn upload(
@@ -160,6 +155,9 @@ void main() {
                     let color = if shielded {
                         let frac = (snapshot.time as f32 * 30.0).sin() * 0.2 + 0.5;
                         team_color * (1.0 - frac) + Vector4::new(0.0, 0.0, 1.0, 1.0) * frac
+                    } else if ship.shield_up {
+                        let frac = 0.2;
+                        team_color * (1.0 - frac) + Vector4::new(0.0, 0.0, 1.0, 1.0) * frac
                     } else {
                         team_color
                     };
@@ -142,13 +142,89 @@ pub enum SystemState {
 
     Id,
 
+    RadarContactClosingSpeed,
+    Shield,
+    RadarMinRssi,
+    RadarTrackCount,
+    RadarAppliedHeading,
+    RadarSlewRate,
+    RadarBeamHeading,
+    RadarBeamWidth,
+    RadarBeamRange,
+    Burning,
+    DebugValuesPointer,
+    DebugValuesLength,
+    ThermalSignature,
+    RadarPing,
+    RadarPingRange,
+    ImpulseX,
+    ImpulseY,
+    ImpulseReady,
+    Ammo0,
+    Ammo1,
+    Ammo2,
+    Ammo3,
+    RadarContactAccelerationX,
+    RadarContactAccelerationY,
+    BeamHeading,
+    BeamRange,
+    BeamEnergy,
+    RadarContactCount,
+    RadarJammerBearing,
+    ProximityFuze,
+    RadarTrackContact,
+    RadarEnergy,
+    RadarBearingOnlyRssi,
+    RadarContactBearingOnly,
+    JamTicks0,
+    JamTicks1,
+    JamTicks2,
+    JamTicks3,
+    Heat0,
+    Heat1,
+    Heat2,
+    Heat3,
+
     Size,
-    MaxSize = 128,
+    MaxSize = 256,
 }
 
 #[allow(missing_docs)]
 pub const MAX_ENVIRONMENT_SIZE: usize = 1024;
 
+#[allow(missing_docs)]
+pub const MAX_RADAR_TRACKS: usize = 16;
+
+/// Maximum number of contacts returned by [`scan_all`](prelude::scan_all).
+pub const MAX_RADAR_CONTACTS: usize = 16;
+
+/// Acceleration (in m/s²) a lost contact is assumed to be capable of when
+/// estimating how far it may have strayed from its dead-reckoned position.
+pub const TRACK_MANEUVER_ACCELERATION: f64 = 60.0;
+
+/// Number of f64 values per entry in the radar track buffer:
+/// id, class, position x/y, velocity x/y, confidence, ticks since seen.
+#[allow(missing_docs)]
+pub const RADAR_TRACK_SIZE: usize = 8;
+
+/// Number of f64 values in the closest contact buffer: found flag, class,
+/// position x/y, velocity x/y, rssi, snr, closing speed.
+#[allow(missing_docs)]
+pub const RADAR_CONTACT_SIZE: usize = 9;
+
+/// Number of f64 values in the nearest friendly buffer: found flag, class,
+/// position x/y, velocity x/y.
+#[allow(missing_docs)]
+pub const FRIENDLY_CONTACT_SIZE: usize = 6;
+
+/// Number of weapons with an entry in the muzzle velocity buffer.
+#[allow(missing_docs)]
+pub const MAX_GUNS: usize = 4;
+
+/// Maximum distance (in meters) at which [`nearest_friendly`](prelude::nearest_friendly)
+/// reports a teammate.
+pub const DATALINK_RANGE: f64 = 20e3;
+
 /// Identifiers for each class of ship.
 #[allow(missing_docs)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -279,13 +355,25 @@ pub struct Text {
     pub text: [u8; 11],
 }
 
+#[doc(hidden)]
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugValue {
+    pub value: f64,
+    pub length: u8,
+    pub key: [u8; 15],
+}
+
 /// Message sent and received on the radio.
 pub type Message = [f64; 4];
 
 // Public for fuzzer.
 #[doc(hidden)]
 pub mod sys {
-    use crate::MAX_ENVIRONMENT_SIZE;
+    use crate::{
+        FRIENDLY_CONTACT_SIZE, MAX_ENVIRONMENT_SIZE, MAX_GUNS, MAX_RADAR_CONTACTS,
+        MAX_RADAR_TRACKS, RADAR_CONTACT_SIZE, RADAR_TRACK_SIZE,
+    };
 
     use super::SystemState;
 
@@ -325,6 +413,63 @@ pub mod sys {
         std::str::from_utf8(&environment[..n]).expect("Failed to convert environment to string")
     }
 
+    // Written by the simulator before each tick, see SystemState::RadarTrackCount.
+    #[no_mangle]
+    pub static mut RADAR_TRACKS: [[f64; RADAR_TRACK_SIZE]; MAX_RADAR_TRACKS] =
+        [[0.0; RADAR_TRACK_SIZE]; MAX_RADAR_TRACKS];
+
+    pub fn read_radar_tracks() -> &'static [[f64; RADAR_TRACK_SIZE]] {
+        let n = (read_system_state(SystemState::RadarTrackCount) as usize).min(MAX_RADAR_TRACKS);
+        unsafe { &RADAR_TRACKS[..n] }
+    }
+
+    // Written by the simulator before each tick. The first value is non-zero
+    // if a contact was found.
+    #[no_mangle]
+    pub static mut RADAR_CLOSEST_CONTACT: [f64; RADAR_CONTACT_SIZE] = [0.0; RADAR_CONTACT_SIZE];
+
+    pub fn read_radar_closest_contact() -> &'static [f64; RADAR_CONTACT_SIZE] {
+        unsafe { &RADAR_CLOSEST_CONTACT }
+    }
+
+    // Written by the simulator before each tick, see SystemState::RadarContactCount.
+    #[no_mangle]
+    pub static mut RADAR_CONTACTS: [[f64; RADAR_CONTACT_SIZE]; MAX_RADAR_CONTACTS] =
+        [[0.0; RADAR_CONTACT_SIZE]; MAX_RADAR_CONTACTS];
+
+    pub fn read_radar_contacts() -> &'static [[f64; RADAR_CONTACT_SIZE]] {
+        let n =
+            (read_system_state(SystemState::RadarContactCount) as usize).min(MAX_RADAR_CONTACTS);
+        unsafe { &RADAR_CONTACTS[..n] }
+    }
+
+    // Written by the simulator before each tick. The first value is non-zero
+    // if a teammate is in datalink range.
+    #[no_mangle]
+    pub static mut FRIENDLY_CONTACT: [f64; FRIENDLY_CONTACT_SIZE] = [0.0; FRIENDLY_CONTACT_SIZE];
+
+    pub fn read_friendly_contact() -> &'static [f64; FRIENDLY_CONTACT_SIZE] {
+        unsafe { &FRIENDLY_CONTACT }
+    }
+
+    // Written by the simulator before each tick. Zero for missile launchers
+    // and missing weapons.
+    #[no_mangle]
+    pub static mut GUN_MUZZLE_VELOCITIES: [f64; MAX_GUNS] = [0.0; MAX_GUNS];
+
+    pub fn read_gun_muzzle_velocities() -> &'static [f64; MAX_GUNS] {
+        unsafe { &GUN_MUZZLE_VELOCITIES }
+    }
+
+    // Written by the simulator before each tick. Zero for missile launchers
+    // and missing weapons.
+    #[no_mangle]
+    pub static mut GUN_BULLET_TTLS: [f64; MAX_GUNS] = [0.0; MAX_GUNS];
+
+    pub fn read_gun_bullet_ttls() -> &'static [f64; MAX_GUNS] {
+        unsafe { &GUN_BULLET_TTLS }
+    }
+
     pub fn getenv(key: &str) -> Option<&'static str> {
         let environment = read_environment();
         for line in environment.lines() {
@@ -340,6 +485,7 @@ pub mod sys {
 }
 
 mod math {
+    use super::vec::{Vec2, Vec2Extras};
     pub use std::f64::consts::{PI, TAU};
 
     /// Returns the smallest rotation between angles `a` and `b`.
@@ -353,6 +499,65 @@ mod math {
             c
         }
     }
+
+    /// Returns the unit vector to fire along to hit a moving target while
+    /// moving yourself.
+    ///
+    /// `target_position` is relative to the shooter. Bullets inherit the
+    /// shooter's velocity, so the intercept is solved for a bullet moving at
+    /// `own_velocity` plus `muzzle_speed` in the returned direction. If the
+    /// bullet can never catch the target the result points straight at it.
+    pub fn aim_lead_moving(
+        target_position: Vec2,
+        target_velocity: Vec2,
+        own_velocity: Vec2,
+        muzzle_speed: f64,
+    ) -> Vec2 {
+        // Solve |target_position + dv * t| = muzzle_speed * t for the
+        // earliest positive t.
+        let dv = target_velocity - own_velocity;
+        let a = dv.dot(dv) - muzzle_speed * muzzle_speed;
+        let b = 2.0 * target_position.dot(dv);
+        let c = target_position.dot(target_position);
+        let roots = if a.abs() < 1e-9 {
+            [-c / b, f64::NAN]
+        } else {
+            let sqrt = (b * b - 4.0 * a * c).sqrt();
+            [(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+        };
+        let t = roots
+            .into_iter()
+            .filter(|&t| t > 0.0)
+            .fold(f64::INFINITY, f64::min);
+        if t.is_finite() {
+            (target_position + dv * t).normalize()
+        } else {
+            target_position.normalize()
+        }
+    }
+
+    /// Returns the time (in seconds) until two objects moving at constant
+    /// velocities are nearest to each other, and the distance between them
+    /// at that point.
+    ///
+    /// The time is zero if they are already moving apart. Works with radar
+    /// contacts as well as the ship's own position and velocity.
+    pub fn closest_point_of_approach(
+        position_a: Vec2,
+        velocity_a: Vec2,
+        position_b: Vec2,
+        velocity_b: Vec2,
+    ) -> (f64, f64) {
+        let dp = position_b - position_a;
+        let dv = velocity_b - velocity_a;
+        let speed_squared = dv.dot(dv);
+        let time = if speed_squared > 0.0 {
+            (-dp.dot(dv) / speed_squared).max(0.0)
+        } else {
+            0.0
+        };
+        (time, (dp + dv * time).length())
+    }
 }
 
 mod rng {
@@ -362,6 +567,10 @@ mod rng {
     }
 
     /// Returns a random number between `low` and `high`.
+    ///
+    /// Each ship has its own stream seeded from [`seed`](crate::prelude::seed),
+    /// so replays of a match see the same numbers. Every call advances the
+    /// stream, including multiple calls in one tick.
     pub fn rand(low: f64, high: f64) -> f64 {
         rng().rand_float() * (high - low) + low
     }
@@ -431,6 +640,14 @@ mod api {
             .unwrap_or(0.0)
     }
 
+    /// Returns the number of teams in the scenario.
+    pub fn team_count() -> usize {
+        super::sys::getenv("TEAM_COUNT")
+            .unwrap_or("0")
+            .parse()
+            .unwrap_or(0)
+    }
+
     /// Returns the current position (in meters).
     pub fn position() -> Vec2 {
         vec2(
@@ -447,16 +664,63 @@ mod api {
         )
     }
 
+    /// Returns the direction from the ship to a point (in radians).
+    ///
+    /// The result is relative to the world x-axis and in the range [0, 2π),
+    /// like [`heading`].
+    pub fn bearing_to(point: Vec2) -> f64 {
+        (point - position()).angle()
+    }
+
+    /// Returns the distance from the ship to a point (in meters).
+    pub fn range_to(point: Vec2) -> f64 {
+        (point - position()).length()
+    }
+
     /// Returns the current heading (in radians).
     pub fn heading() -> f64 {
         read_system_state(SystemState::Heading)
     }
 
+    /// Converts a point from world coordinates to the ship's frame.
+    ///
+    /// In the ship's frame the ship is at the origin, the positive x-axis
+    /// points out of its nose and the positive y-axis to its left.
+    pub fn to_local(world_point: Vec2) -> Vec2 {
+        (world_point - position()).rotate(-heading())
+    }
+
+    /// Converts a point from the ship's frame to world coordinates.
+    ///
+    /// This is the inverse of [`to_local`].
+    pub fn to_world(local_point: Vec2) -> Vec2 {
+        local_point.rotate(heading()) + position()
+    }
+
     /// Returns the current angular velocity (in radians/s).
     pub fn angular_velocity() -> f64 {
         read_system_state(SystemState::AngularVelocity)
     }
 
+    /// Instantly changes velocity by `magnitude` (in m/s) along `direction`.
+    ///
+    /// Fighters can change their velocity by up to 100 m/s at a time, using
+    /// the same fuel as thrusting. Another impulse is then unavailable for 10
+    /// seconds, see [`impulse_ready`].
+    pub fn impulse(direction: Vec2, magnitude: f64) {
+        if direction.length() == 0.0 {
+            return;
+        }
+        let delta_v = direction.normalize() * magnitude;
+        write_system_state(SystemState::ImpulseX, delta_v.x);
+        write_system_state(SystemState::ImpulseY, delta_v.y);
+    }
+
+    /// Returns true if [`impulse`] can be used.
+    pub fn impulse_ready() -> bool {
+        read_system_state(SystemState::ImpulseReady) > 0.0
+    }
+
     /// Sets the linear acceleration for the next tick (in m/s²).
     pub fn accelerate(mut acceleration: Vec2) {
         acceleration = acceleration.rotate(-heading());
@@ -517,6 +781,22 @@ mod api {
         write_system_state(state_index, 1.0);
     }
 
+    /// Fires the ship's beam weapon for this tick.
+    ///
+    /// The beam is instantaneous and damages the first enemy along `heading`
+    /// (in radians, like [`heading`]) within `range` meters, but drains
+    /// energy while held on. Does nothing on ships without a beam.
+    pub fn fire_beam(heading: f64, range: f64) {
+        write_system_state(SystemState::BeamHeading, heading);
+        write_system_state(SystemState::BeamRange, range);
+    }
+
+    /// Returns the energy left for the beam weapon, zero on ships without
+    /// one.
+    pub fn beam_energy() -> f64 {
+        read_system_state(SystemState::BeamEnergy)
+    }
+
     /// Returns the number of ticks until a weapon is ready to fire.
     ///
     /// `index` selects the weapon. Returns 0 if the weapon is ready.
@@ -531,6 +811,81 @@ mod api {
         read_system_state(state_index) as u32
     }
 
+    /// Returns the number of rounds a gun has left. Each shot uses one round
+    /// per bullet in the gun's burst.
+    ///
+    /// `index` selects the weapon. Returns infinity if the gun's ammunition is
+    /// unlimited, which is the case in most scenarios.
+    pub fn ammo(index: usize) -> f64 {
+        let state_index = match index {
+            0 => SystemState::Ammo0,
+            1 => SystemState::Ammo1,
+            2 => SystemState::Ammo2,
+            3 => SystemState::Ammo3,
+            _ => return 0.0,
+        };
+        read_system_state(state_index)
+    }
+
+    /// Returns true if a gun overheated and can't fire until its cooldown,
+    /// see [`weapon_cooldown_remaining`], has passed.
+    ///
+    /// `index` selects the weapon.
+    pub fn is_jammed(index: usize) -> bool {
+        weapon_cooldown_remaining(index) > 0
+    }
+
+    /// Returns the number of ticks before a jammed gun can fire again, which
+    /// is 0 if it isn't jammed. Letting the gun cool down doesn't shorten it.
+    ///
+    /// `index` selects the weapon.
+    pub fn weapon_cooldown_remaining(index: usize) -> u32 {
+        let state_index = match index {
+            0 => SystemState::JamTicks0,
+            1 => SystemState::JamTicks1,
+            2 => SystemState::JamTicks2,
+            3 => SystemState::JamTicks3,
+            _ => return 0,
+        };
+        read_system_state(state_index) as u32
+    }
+
+    /// Returns a gun's heat, from 0 to 1. Each shot adds heat, which drains
+    /// away over time, and the gun jams when it reaches 1, see [`is_jammed`].
+    ///
+    /// `index` selects the weapon. Returns 0 for guns that don't overheat.
+    pub fn heat(index: usize) -> f64 {
+        let state_index = match index {
+            0 => SystemState::Heat0,
+            1 => SystemState::Heat1,
+            2 => SystemState::Heat2,
+            3 => SystemState::Heat3,
+            _ => return 0.0,
+        };
+        read_system_state(state_index)
+    }
+
+    /// Returns the speed (in m/s) of bullets fired by a gun, relative to the
+    /// ship.
+    ///
+    /// `index` selects the weapon. Returns 0 if the weapon isn't a gun.
+    pub fn gun_muzzle_velocity(index: usize) -> f64 {
+        crate::sys::read_gun_muzzle_velocities()
+            .get(index)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Returns the lifetime (in seconds) of bullets fired by a gun.
+    ///
+    /// `index` selects the weapon. Returns 0 if the weapon isn't a gun.
+    pub fn gun_bullet_ttl(index: usize) -> f64 {
+        crate::sys::read_gun_bullet_ttls()
+            .get(index)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
     /// Self-destructs, producing a damaging explosion.
     ///
     /// This is commonly used by missiles.
@@ -538,28 +893,111 @@ mod api {
         write_system_state(SystemState::Explode, 1.0);
     }
 
+    /// Arms a proximity fuze that calls [`explode`] as soon as an enemy ship
+    /// comes within `radius` meters.
+    ///
+    /// The fuze is checked every tick, even when the ship isn't running its
+    /// `tick` function. Pass zero to disarm it.
+    pub fn set_proximity_fuze(radius: f64) {
+        write_system_state(SystemState::ProximityFuze, radius.max(0.0));
+    }
+
     /// Returns the current health.
     pub fn health() -> f64 {
         read_system_state(SystemState::Health)
     }
 
+    /// Returns the current shield strength.
+    ///
+    /// Shields absorb damage before health and recharge after a few seconds
+    /// without being hit.
+    pub fn shield() -> f64 {
+        read_system_state(SystemState::Shield)
+    }
+
+    /// Returns true if the ship is on fire and losing health every tick.
+    pub fn is_burning() -> bool {
+        read_system_state(SystemState::Burning) > 0.0
+    }
+
+    /// Returns the engine heat signature, a running average of the ship's
+    /// acceleration over about the last second (in m/s²).
+    ///
+    /// In some scenarios this makes the ship easier to see on radar.
+    pub fn thermal_signature() -> f64 {
+        read_system_state(SystemState::ThermalSignature)
+    }
+
     /// Returns the current fuel (delta-v).
     pub fn fuel() -> f64 {
         read_system_state(SystemState::Fuel)
     }
 
-    /// Returns the heading the radar is pointed at.
+    /// The ship's remaining resources, see [`status`].
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ShipStatus {
+        /// See [`health`].
+        pub health: f64,
+        /// See [`shield`].
+        pub shield: f64,
+        /// See [`fuel`].
+        pub fuel: f64,
+        /// See [`ammo`], indexed by weapon.
+        pub ammo: [f64; crate::MAX_GUNS],
+        /// See [`reload_ticks`], indexed by weapon.
+        pub reload_ticks: [u32; crate::MAX_GUNS],
+        /// See [`heat`], indexed by weapon.
+        pub heat: [f64; crate::MAX_GUNS],
+        /// See [`thermal_signature`].
+        pub thermal_signature: f64,
+        /// See [`is_burning`].
+        pub burning: bool,
+        /// See [`impulse_ready`].
+        pub impulse_ready: bool,
+    }
+
+    /// Returns health, fuel, ammunition and other resources in one call.
+    pub fn status() -> ShipStatus {
+        ShipStatus {
+            health: health(),
+            shield: shield(),
+            fuel: fuel(),
+            ammo: std::array::from_fn(ammo),
+            reload_ticks: std::array::from_fn(reload_ticks),
+            heat: std::array::from_fn(heat),
+            thermal_signature: thermal_signature(),
+            burning: is_burning(),
+            impulse_ready: impulse_ready(),
+        }
+    }
+
+    /// Returns the heading the radar was commanded to point at.
     pub fn radar_heading() -> f64 {
         read_system_state(SystemState::RadarHeading)
     }
 
     /// Sets the heading to point the radar at.
     ///
-    /// It takes effect next tick.
+    /// It takes effect next tick. If the radar has a limited slew rate the
+    /// beam turns towards the new heading over several ticks.
     pub fn set_radar_heading(heading: f64) {
         write_system_state(SystemState::RadarHeading, heading);
     }
 
+    /// Returns the heading the radar beam is actually pointed at.
+    ///
+    /// This lags behind [`radar_heading`] while the beam is slewing.
+    pub fn radar_applied_heading() -> f64 {
+        read_system_state(SystemState::RadarAppliedHeading)
+    }
+
+    /// Returns the maximum rate the radar beam can turn (in radians/s).
+    ///
+    /// This is infinite if the beam can move to any heading in one tick.
+    pub fn radar_slew_rate() -> f64 {
+        read_system_state(SystemState::RadarSlewRate)
+    }
+
     /// Returns the current radar width (in radians).
     ///
     /// This is the field of view of the radar.
@@ -571,6 +1009,9 @@ mod api {
     ///
     /// This is the field of view of the radar.
     /// It takes effect next tick.
+    ///
+    /// A width of `TAU` switches to omnidirectional mode, which sees in every
+    /// direction at a much shorter range.
     pub fn set_radar_width(width: f64) {
         write_system_state(SystemState::RadarWidth, width);
     }
@@ -599,6 +1040,46 @@ mod api {
         write_system_state(SystemState::RadarMaxDistance, dist);
     }
 
+    /// Returns the radar sensitivity (minimum detectable RSSI, in dBm).
+    pub fn radar_min_rssi() -> f64 {
+        10.0 * read_system_state(SystemState::RadarMinRssi).log10() + 30.0
+    }
+
+    /// Sets the radar sensitivity (minimum detectable RSSI, in dBm).
+    ///
+    /// Lower values extend detection range but let through more unreliable
+    /// contacts. Clamped to [-130, -60] dBm. The default is -100 dBm.
+    ///
+    /// It takes effect next tick.
+    pub fn set_radar_min_rssi(min_rssi: f64) {
+        write_system_state(
+            SystemState::RadarMinRssi,
+            10.0_f64.powf((min_rssi - 30.0) / 10.0),
+        );
+    }
+
+    /// Returns the threshold for bearing-only contacts (in dBm), or `None`
+    /// if they are disabled.
+    pub fn radar_bearing_only_rssi() -> Option<f64> {
+        let rssi = read_system_state(SystemState::RadarBearingOnlyRssi);
+        (rssi > 0.0).then(|| 10.0 * rssi.log10() + 30.0)
+    }
+
+    /// Sets the threshold for bearing-only contacts (in dBm).
+    ///
+    /// Contacts between this and [`radar_min_rssi`] are returned by [`scan`]
+    /// with [`ScanResult::bearing_only`] set: only their direction is known.
+    /// This lets a ship turn towards a faint contact until it gets a full
+    /// return. `None` disables bearing-only contacts, which is the default.
+    ///
+    /// It takes effect next tick.
+    pub fn set_radar_bearing_only_rssi(rssi: Option<f64>) {
+        write_system_state(
+            SystemState::RadarBearingOnlyRssi,
+            rssi.map_or(0.0, |rssi| 10.0_f64.powf((rssi - 30.0) / 10.0)),
+        );
+    }
+
     /// Gets the Electronic Counter Measures (ECM) mode.
     pub fn radar_ecm_mode() -> EcmMode {
         read_system_state(SystemState::RadarEcmMode).into()
@@ -609,10 +1090,68 @@ mod api {
         write_system_state(SystemState::RadarEcmMode, mode as u32 as f64);
     }
 
+    /// Replaces the next radar scan with an omnidirectional ranging pulse.
+    ///
+    /// Returns the distance to the nearest enemy found by the pulse sent on
+    /// the previous tick, or infinity if there was none or it found nothing.
+    /// The pulse ignores the radar heading and width but gives no bearing,
+    /// and its power is spread over the full circle so it doesn't reach as
+    /// far as a beam.
+    pub fn ping() -> f64 {
+        write_system_state(SystemState::RadarPing, 1.0);
+        read_system_state(SystemState::RadarPingRange)
+    }
+
+    /// Returns the energy (in joules) left for the radar.
+    ///
+    /// In scenarios that limit it, every scan costs the radar's power times
+    /// the tick length and energy recharges at a fixed rate. A radar without
+    /// enough energy transmits at reduced power, so it sees less far.
+    /// Infinity when energy is unlimited.
+    pub fn radar_energy() -> f64 {
+        read_system_state(SystemState::RadarEnergy)
+    }
+
+    /// Returns the approximate bearing (in radians) to the strongest enemy
+    /// jammer in the radar beam on the previous tick.
+    ///
+    /// `None` if no jammer in the beam was louder than the background noise.
+    /// A jammer can hide itself from [`scan`], but not its bearing.
+    pub fn radar_jammer_bearing() -> Option<f64> {
+        let bearing = read_system_state(SystemState::RadarJammerBearing);
+        (!bearing.is_nan()).then_some(bearing)
+    }
+
+    /// Restricts the radar to the contact returned by the latest [`scan`].
+    ///
+    /// While tracking, [`scan`] only reports that ship, even if a stronger
+    /// contact is in the beam. Tracking stops when the ship leaves the beam
+    /// or its return drops below the minimum RSSI, and also when jamming or
+    /// calling [`ping`]. Keep the beam pointed at the contact to hold it.
+    pub fn track_radar() {
+        write_system_state(SystemState::RadarTrackContact, 1.0);
+    }
+
+    /// Stops the tracking started by [`track_radar`].
+    pub fn untrack_radar() {
+        write_system_state(SystemState::RadarTrackContact, 0.0);
+    }
+
+    /// Returns true if the radar is tracking a contact, see [`track_radar`].
+    ///
+    /// Calling [`track_radar`] or [`untrack_radar`] changes this right away,
+    /// before the radar has acted on it. From the next tick it reports
+    /// whether a contact is actually being tracked.
+    pub fn radar_tracking() -> bool {
+        read_system_state(SystemState::RadarTrackContact) > 0.0
+    }
+
     /// A radar contact.
     #[derive(Clone, Debug)]
     pub struct ScanResult {
         /// The contact's class.
+        ///
+        /// Scenarios can make weak returns report [`Class::Unknown`].
         pub class: Class,
         /// The contact's approximate position.
         pub position: Vec2,
@@ -622,6 +1161,34 @@ mod api {
         pub rssi: f64,
         /// The signal-to-noise ratio measured in dB.
         pub snr: f64,
+        /// The rate at which the distance to the contact is decreasing (in m/s).
+        ///
+        /// Negative if the contact is moving away.
+        pub closing_speed: f64,
+        /// The contact's approximate acceleration, estimated from successive
+        /// scans of the same contact.
+        ///
+        /// Zero until the contact has been seen twice, and always zero for
+        /// [`closest_contact`] and [`scan_all`].
+        pub acceleration: Vec2,
+        /// True if the return was only strong enough for a bearing, see
+        /// [`set_radar_bearing_only_rssi`].
+        ///
+        /// The position is then one meter from the ship in the contact's
+        /// direction, and the velocity is zero. Always false for
+        /// [`closest_contact`] and [`scan_all`].
+        pub bearing_only: bool,
+    }
+
+    impl ScanResult {
+        /// Returns the distance from the ship to the contact's reported
+        /// position (in meters).
+        ///
+        /// The reported position is noisy, so this is only as accurate as the
+        /// return is strong.
+        pub fn distance(&self) -> f64 {
+            range_to(self.position)
+        }
     }
 
     /// Returns the radar contact with the highest signal strength.
@@ -641,9 +1208,240 @@ mod api {
             ),
             rssi: read_system_state(SystemState::RadarContactRssi),
             snr: read_system_state(SystemState::RadarContactSnr),
+            closing_speed: read_system_state(SystemState::RadarContactClosingSpeed),
+            acceleration: vec2(
+                read_system_state(SystemState::RadarContactAccelerationX),
+                read_system_state(SystemState::RadarContactAccelerationY),
+            ),
+            bearing_only: read_system_state(SystemState::RadarContactBearingOnly) > 0.0,
+        })
+    }
+
+    /// Returns the nearest radar contact in the beam.
+    ///
+    /// Unlike [`scan`], which picks the contact with the strongest return,
+    /// this prefers distance, so a small nearby ship wins over a large one
+    /// further away.
+    pub fn closest_contact() -> Option<ScanResult> {
+        let c = crate::sys::read_radar_closest_contact();
+        if c[0] == 0.0 {
+            return None;
+        }
+        Some(scan_result_from_fields(c))
+    }
+
+    /// Returns every radar contact in the beam, strongest first.
+    ///
+    /// Each contact has independent noise, so the first entry may differ
+    /// slightly from [`scan`]. At most
+    /// [`MAX_RADAR_CONTACTS`](crate::MAX_RADAR_CONTACTS) are returned.
+    pub fn scan_all() -> Vec<ScanResult> {
+        crate::sys::read_radar_contacts()
+            .iter()
+            .map(scan_result_from_fields)
+            .collect()
+    }
+
+    fn scan_result_from_fields(c: &[f64; crate::RADAR_CONTACT_SIZE]) -> ScanResult {
+        ScanResult {
+            class: Class::from_f64(c[1]),
+            position: vec2(c[2], c[3]),
+            velocity: vec2(c[4], c[5]),
+            rssi: c[6],
+            snr: c[7],
+            closing_speed: c[8],
+            acceleration: vec2(0.0, 0.0),
+            bearing_only: false,
+        }
+    }
+
+    /// A radar contact maintained across ticks by the track file.
+    #[derive(Clone, Debug)]
+    pub struct RadarTrack {
+        /// Identifier that stays the same for as long as the track exists.
+        pub id: u64,
+        /// The contact's class.
+        pub class: Class,
+        /// The smoothed position estimate.
+        pub position: Vec2,
+        /// The smoothed velocity estimate.
+        pub velocity: Vec2,
+        /// 1.0 if the contact was seen this tick, decaying while it goes unseen.
+        pub confidence: f64,
+        /// Number of ticks since the radar last saw the contact.
+        pub ticks_since_seen: u32,
+    }
+
+    impl RadarTrack {
+        /// Returns how far (in meters) the contact may be from `position` if
+        /// it maneuvered since it was last seen.
+        ///
+        /// Zero while the contact is being painted, growing with the square
+        /// of the time since it was lost.
+        pub fn uncertainty(&self) -> f64 {
+            let t = self.ticks_since_seen as f64 * TICK_LENGTH;
+            0.5 * crate::TRACK_MANEUVER_ACCELERATION * t * t
+        }
+    }
+
+    /// Returns the contacts seen by the radar in the last few ticks.
+    ///
+    /// Positions and velocities are filtered across scans and extrapolated
+    /// while the beam is pointed elsewhere.
+    pub fn radar_tracks() -> Vec<RadarTrack> {
+        crate::sys::read_radar_tracks()
+            .iter()
+            .map(|t| RadarTrack {
+                id: t[0] as u64,
+                class: Class::from_f64(t[1]),
+                position: vec2(t[2], t[3]),
+                velocity: vec2(t[4], t[5]),
+                confidence: t[6],
+                ticks_since_seen: t[7] as u32,
+            })
+            .collect()
+    }
+
+    /// A teammate reported by the datalink.
+    #[derive(Clone, Debug)]
+    pub struct FriendlyContact {
+        /// The teammate's class.
+        pub class: Class,
+        /// The teammate's exact position.
+        pub position: Vec2,
+        /// The teammate's exact velocity.
+        pub velocity: Vec2,
+    }
+
+    /// Returns the closest ship on your team, if one is within
+    /// [`DATALINK_RANGE`](crate::DATALINK_RANGE).
+    ///
+    /// Radar never reports teammates. This uses their own telemetry instead,
+    /// so it's noise-free and doesn't depend on where the radar is pointed.
+    pub fn nearest_friendly() -> Option<FriendlyContact> {
+        let c = crate::sys::read_friendly_contact();
+        if c[0] == 0.0 {
+            return None;
+        }
+        Some(FriendlyContact {
+            class: Class::from_f64(c[1]),
+            position: vec2(c[2], c[3]),
+            velocity: vec2(c[4], c[5]),
         })
     }
 
+    /// The radar beam used by the most recent scan.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BeamInfo {
+        /// Heading of the center of the beam.
+        pub heading: f64,
+        /// Width of the beam after clamping to the radar's limits.
+        pub width: f64,
+        /// Bearing of the clockwise edge of the beam.
+        pub start_bearing: f64,
+        /// Bearing of the counter-clockwise edge of the beam.
+        pub end_bearing: f64,
+        /// Approximate range at which a cruiser-sized target can be detected.
+        pub range: f64,
+    }
+
+    /// Returns the beam the radar actually emitted last tick.
+    ///
+    /// This can differ from what was requested since the width is clamped
+    /// and the heading may still be slewing.
+    pub fn radar_last_beam() -> BeamInfo {
+        let heading = read_system_state(SystemState::RadarBeamHeading);
+        let width = read_system_state(SystemState::RadarBeamWidth);
+        BeamInfo {
+            heading,
+            width,
+            start_bearing: heading - 0.5 * width,
+            end_bearing: heading + 0.5 * width,
+            range: read_system_state(SystemState::RadarBeamRange),
+        }
+    }
+
+    /// Fires gun 0 at a radar contact if a lead-corrected shot is possible.
+    ///
+    /// The shot is only taken if the gun is loaded, the lead direction is
+    /// within `cone` radians of the current heading, and the bullet would
+    /// reach the contact before expiring. Returns true if the gun was fired.
+    pub fn fire_if_locked(contact: &ScanResult, cone: f64) -> bool {
+        let bullet_ttl = gun_bullet_ttl(0);
+        let bullet_speed = gun_muzzle_velocity(0);
+        if bullet_ttl <= 0.0 || bullet_speed <= 0.0 || reload_ticks(0) > 0 {
+            return false;
+        }
+
+        let dp = contact.position - position();
+        let dv = contact.velocity - velocity();
+        let mut t = dp.length() / bullet_speed;
+        for _ in 0..3 {
+            t = (dp + dv * t).length() / bullet_speed;
+        }
+        if t > bullet_ttl {
+            return false;
+        }
+
+        let lead = (dp + dv * t).angle();
+        if crate::math::angle_diff(heading(), lead).abs() > cone {
+            return false;
+        }
+
+        aim(0, lead);
+        fire(0);
+        true
+    }
+
+    /// Returns the acceleration needed to hold station relative to a leader.
+    ///
+    /// `offset` is the desired position relative to `leader_position`, in world
+    /// coordinates. The leader's position and velocity would typically be
+    /// received over the radio. Pass the result to [`accelerate`].
+    pub fn station_keep(leader_position: Vec2, leader_velocity: Vec2, offset: Vec2) -> Vec2 {
+        let dp = leader_position + offset - position();
+        let dv = leader_velocity - velocity();
+        let distance = dp.length();
+        if distance < 1e-9 {
+            return dv * 4.0;
+        }
+        // Approach no faster than we could stop using the weakest thruster,
+        // and slow to a 1 s time constant when close.
+        let braking = 0.5 * max_lateral_acceleration().min(max_forward_acceleration());
+        let approach_speed = (2.0 * braking * distance).sqrt().min(distance);
+        let desired_velocity = dp * (approach_speed / distance);
+        (desired_velocity + dv) * 4.0
+    }
+
+    /// Returns the acceleration needed to enter and hold a circular orbit.
+    ///
+    /// The orbit has the given `radius` around the world point `center`. A
+    /// non-negative `direction` circles counter-clockwise, a negative one
+    /// clockwise. The orbital speed leaves half of the weakest thruster free
+    /// for corrections. Pass the result to [`accelerate`].
+    pub fn orbit(center: Vec2, radius: f64, direction: i64) -> Vec2 {
+        let dp = position() - center;
+        let distance = dp.length();
+        if distance < 1e-9 {
+            return -velocity() * 4.0;
+        }
+        let radial = dp / distance;
+        let tangent = if direction >= 0 {
+            vec2(-radial.y, radial.x)
+        } else {
+            vec2(radial.y, -radial.x)
+        };
+        let braking = 0.5 * max_lateral_acceleration().min(max_forward_acceleration());
+        let orbital_speed = (braking * radius).sqrt();
+        // Close the radial error like station_keep, then add the centripetal
+        // acceleration the circle needs.
+        let error = radius - distance;
+        let radial_speed = (2.0 * braking * error.abs()).sqrt().min(error.abs()) * error.signum();
+        let desired_velocity = tangent * orbital_speed + radial * radial_speed;
+        let centripetal = -radial * (orbital_speed * orbital_speed / distance);
+        (desired_velocity - velocity()) * 4.0 + centripetal
+    }
+
     #[doc(hidden)]
     pub mod radio_internal {
         use super::SystemState;
@@ -661,8 +1459,9 @@ mod api {
             assert!(sel < MAX_RADIOS);
             let stride = 7;
             let offset = stride * sel;
-            let add_offset =
-                |x| unsafe { ::std::mem::transmute::<u8, SystemState>((x as u8) + offset as u8) };
+            let add_offset = |x| unsafe {
+                ::std::mem::transmute::<u16, SystemState>((x as u16) + offset as u16)
+            };
             RadioIndices {
                 channel: add_offset(SystemState::Radio0Channel),
                 send: add_offset(SystemState::Radio0Send),
@@ -856,14 +1655,15 @@ mod api {
 #[doc(hidden)]
 #[macro_use]
 pub mod dbg {
-    use super::{Line, Text};
+    use super::{DebugValue, Line, Text};
     use crate::sys::write_system_state;
     use crate::vec::*;
     use std::f64::consts::TAU;
 
     static mut TEXT_BUFFER: String = String::new();
-    static mut LINE_BUFFER: Vec<Line> = Vec::new();
+    pub(crate) static mut LINE_BUFFER: Vec<Line> = Vec::new();
     static mut DRAWN_TEXT_BUFFER: Vec<Text> = Vec::new();
+    static mut DEBUG_VALUE_BUFFER: Vec<DebugValue> = Vec::new();
 
     /// Adds text to be displayed when the ship is selected by clicking on it.
     ///
@@ -982,6 +1782,28 @@ pub mod dbg {
         draw_diamond(center, radius, color)
     }
 
+    /// Longest arrow drawn by [`debug_vector`], in meters.
+    pub const MAX_VECTOR_LENGTH: f64 = 1000.0;
+
+    /// Draws an arrow visible in debug mode, e.g. for a commanded
+    /// acceleration.
+    ///
+    /// The arrow starts at `origin` in world coordinates and points along
+    /// `vec`, shortened to at most [`MAX_VECTOR_LENGTH`].
+    /// `color` is 24-bit RGB.
+    pub fn debug_vector(origin: Vec2, vec: Vec2, color: u32) {
+        let length = vec.length();
+        if length < 1e-9 {
+            return;
+        }
+        let vec = vec * (length.min(MAX_VECTOR_LENGTH) / length);
+        let tip = origin + vec;
+        let barb = -vec * 0.2;
+        draw_line(origin, tip, color);
+        draw_line(tip, tip + barb.rotate(TAU / 12.0), color);
+        draw_line(tip, tip + barb.rotate(-TAU / 12.0), color);
+    }
+
     /// Adds text to be drawn in the world, visible in debug mode.
     ///
     /// Works like [println!]. Up to 128 strings can be drawn per ship, per tick.
@@ -1014,6 +1836,33 @@ pub mod dbg {
         });
     }
 
+    /// Records a named value for this tick, shown when the ship is selected.
+    ///
+    /// Setting the same key again in a tick replaces the value. Keys are
+    /// truncated to 15 bytes and up to 128 values can be set per ship, per
+    /// tick.
+    pub fn debug_set(key: &str, value: f64) {
+        let mut key_buf = [0u8; 15];
+        key_buf
+            .iter_mut()
+            .zip(key.bytes())
+            .for_each(|(d, s)| *d = s);
+        let length = key.len().min(key_buf.len()) as u8;
+        let buf = unsafe { &mut DEBUG_VALUE_BUFFER };
+        if let Some(existing) = buf
+            .iter_mut()
+            .find(|v| v.length == length && v.key == key_buf)
+        {
+            existing.value = value;
+        } else if buf.len() < 128 {
+            buf.push(DebugValue {
+                value,
+                length,
+                key: key_buf,
+            });
+        }
+    }
+
     #[doc(hidden)]
     pub fn update() {
         {
@@ -1049,6 +1898,17 @@ pub mod dbg {
                 slice.len() as u32 as f64,
             );
         }
+        {
+            let slice = unsafe { &mut DEBUG_VALUE_BUFFER }.as_slice();
+            write_system_state(
+                super::SystemState::DebugValuesPointer,
+                slice.as_ptr() as u32 as f64,
+            );
+            write_system_state(
+                super::SystemState::DebugValuesLength,
+                slice.len() as u32 as f64,
+            );
+        }
     }
 
     #[doc(hidden)]
@@ -1057,6 +1917,7 @@ pub mod dbg {
             TEXT_BUFFER.clear();
             LINE_BUFFER.clear();
             DRAWN_TEXT_BUFFER.clear();
+            DEBUG_VALUE_BUFFER.clear();
         }
     }
 }
@@ -1125,3 +1986,460 @@ pub mod prelude {
     pub use byteorder;
     pub use maths_rs;
 }
+
+#[cfg(test)]
+mod test {
+    use super::prelude::*;
+    use super::sys::{read_system_state, write_system_state};
+    use super::SystemState;
+    use std::sync::Mutex;
+
+    // The system state is global, so tests that use it must not run concurrently.
+    static SYSTEM_STATE_LOCK: Mutex<()> = Mutex::new(());
+
+    fn contact(position: Vec2) -> ScanResult {
+        ScanResult {
+            class: Class::Fighter,
+            position,
+            velocity: vec2(0.0, 0.0),
+            rssi: 0.0,
+            snr: 0.0,
+            closing_speed: 0.0,
+            acceleration: vec2(0.0, 0.0),
+            bearing_only: false,
+        }
+    }
+
+    #[test]
+    fn test_fire_if_locked() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        for state in [
+            SystemState::PositionX,
+            SystemState::PositionY,
+            SystemState::VelocityX,
+            SystemState::VelocityY,
+        ] {
+            write_system_state(state, 0.0);
+        }
+        write_system_state(SystemState::Class, Class::Fighter as u32 as f64);
+        write_system_state(SystemState::Heading, 0.0);
+        unsafe {
+            super::sys::GUN_MUZZLE_VELOCITIES = [1000.0, 0.0, 0.0, 0.0];
+            super::sys::GUN_BULLET_TTLS = [10.0, 0.0, 0.0, 0.0];
+        }
+
+        write_system_state(SystemState::Fire0, 0.0);
+        assert!(fire_if_locked(&contact(vec2(1000.0, 10.0)), 0.1));
+        assert_eq!(read_system_state(SystemState::Fire0), 1.0);
+
+        write_system_state(SystemState::Fire0, 0.0);
+        assert!(!fire_if_locked(&contact(vec2(0.0, 1000.0)), 0.1));
+        assert_eq!(read_system_state(SystemState::Fire0), 0.0);
+
+        // Out of range.
+        assert!(!fire_if_locked(&contact(vec2(1e6, 0.0)), 0.1));
+    }
+
+    #[test]
+    fn test_bearing_and_range() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::PositionX, 100.0);
+        write_system_state(SystemState::PositionY, -50.0);
+        let origin = vec2(100.0, -50.0);
+        let check = |offset: Vec2, bearing: f64| {
+            let eps = 1e-9;
+            assert!(
+                (bearing_to(origin + offset) - bearing).abs() < eps,
+                "offset={offset:?}"
+            );
+            assert!((range_to(origin + offset) - offset.length()).abs() < eps);
+        };
+
+        check(vec2(1.0, 1.0), PI / 4.0);
+        check(vec2(-1.0, 1.0), 3.0 * PI / 4.0);
+        check(vec2(-1.0, -1.0), 5.0 * PI / 4.0);
+        check(vec2(1.0, -1.0), 7.0 * PI / 4.0);
+
+        // Continuous across ±π.
+        check(vec2(-10.0, 0.0), PI);
+        check(vec2(-10.0, 1e-3), PI - 1e-4);
+        check(vec2(-10.0, -1e-3), PI + 1e-4);
+    }
+
+    #[test]
+    fn test_local_world_conversion() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::PositionX, 100.0);
+        write_system_state(SystemState::PositionY, -50.0);
+        write_system_state(SystemState::Heading, PI / 2.0);
+        let eps = 1e-9;
+
+        // Ten meters ahead of the nose is straight up in world coordinates.
+        let ahead = to_world(vec2(10.0, 0.0));
+        assert!((ahead - vec2(100.0, -40.0)).length() < eps, "{ahead:?}");
+
+        // A point on the world's positive x side is to the ship's right.
+        let right = to_local(vec2(110.0, -50.0));
+        assert!((right - vec2(0.0, -10.0)).length() < eps, "{right:?}");
+
+        for p in [vec2(0.0, 0.0), vec2(123.0, -456.0), vec2(-1e4, 3e3)] {
+            let round_trip = to_world(to_local(p));
+            assert!((round_trip - p).length() < 1e-6, "{p:?} {round_trip:?}");
+        }
+    }
+
+    #[test]
+    fn test_station_keep() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::MaxForwardAcceleration, 60.0);
+        write_system_state(SystemState::MaxLateralAcceleration, 30.0);
+        let dt = 1.0 / 60.0;
+        let mut p = vec2(-2000.0, 500.0);
+        let mut v = vec2(100.0, 0.0);
+        let mut leader_p = vec2(0.0, 0.0);
+        let leader_v = vec2(50.0, 20.0);
+        let offset = vec2(-100.0, 50.0);
+        for tick in 0..(60 * 60) {
+            write_system_state(SystemState::PositionX, p.x);
+            write_system_state(SystemState::PositionY, p.y);
+            write_system_state(SystemState::VelocityX, v.x);
+            write_system_state(SystemState::VelocityY, v.y);
+            let mut a = station_keep(leader_p, leader_v, offset);
+            if a.length() > 30.0 {
+                a = a * (30.0 / a.length());
+            }
+            v += a * dt;
+            p += v * dt;
+            leader_p += leader_v * dt;
+            // Once converged the follower holds the offset.
+            if tick >= 60 * 30 {
+                let error = (p - (leader_p + offset)).length();
+                assert!(error < 1.0, "tick={tick} error={error}");
+                assert!((v - leader_v).length() < 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_orbit() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::MaxForwardAcceleration, 60.0);
+        write_system_state(SystemState::MaxLateralAcceleration, 30.0);
+        let dt = 1.0 / 60.0;
+        let center = vec2(500.0, -200.0);
+        for (direction, mut p, mut v) in [
+            (1, center + vec2(1050.0, 0.0), vec2(0.0, 0.0)),
+            (-1, center + vec2(300.0, -200.0), vec2(50.0, 0.0)),
+            (1, center + vec2(3000.0, 1000.0), vec2(-100.0, 0.0)),
+        ] {
+            for tick in 0..(60 * 60) {
+                write_system_state(SystemState::PositionX, p.x);
+                write_system_state(SystemState::PositionY, p.y);
+                write_system_state(SystemState::VelocityX, v.x);
+                write_system_state(SystemState::VelocityY, v.y);
+                let mut a = orbit(center, 1000.0, direction);
+                if a.length() > 30.0 {
+                    a = a * (30.0 / a.length());
+                }
+                v += a * dt;
+                p += v * dt;
+                if tick >= 60 * 30 {
+                    let error = ((p - center).length() - 1000.0).abs();
+                    assert!(error < 1.0, "tick={tick} error={error}");
+                    let dp = p - center;
+                    let angular_momentum = dp.x * v.y - dp.y * v.x;
+                    assert_eq!(angular_momentum.signum(), direction as f64);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_radar_tracks() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        unsafe {
+            super::sys::RADAR_TRACKS[0] = [
+                7.0,
+                Class::Frigate as u32 as f64,
+                1.0,
+                2.0,
+                3.0,
+                4.0,
+                0.5,
+                3.0,
+            ];
+        }
+        write_system_state(SystemState::RadarTrackCount, 1.0);
+        let tracks = radar_tracks();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, 7);
+        assert_eq!(tracks[0].class, Class::Frigate);
+        assert_eq!(tracks[0].position, vec2(1.0, 2.0));
+        assert_eq!(tracks[0].velocity, vec2(3.0, 4.0));
+        assert_eq!(tracks[0].confidence, 0.5);
+        assert_eq!(tracks[0].ticks_since_seen, 3);
+
+        write_system_state(SystemState::RadarTrackCount, 0.0);
+        assert!(radar_tracks().is_empty());
+    }
+
+    #[test]
+    fn test_radar_last_beam() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::RadarBeamHeading, 1.0);
+        write_system_state(SystemState::RadarBeamWidth, 0.5);
+        write_system_state(SystemState::RadarBeamRange, 1e4);
+        assert_eq!(
+            radar_last_beam(),
+            BeamInfo {
+                heading: 1.0,
+                width: 0.5,
+                start_bearing: 0.75,
+                end_bearing: 1.25,
+                range: 1e4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_closest_contact() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        unsafe {
+            super::sys::RADAR_CLOSEST_CONTACT = [0.0; super::RADAR_CONTACT_SIZE];
+        }
+        assert!(closest_contact().is_none());
+        unsafe {
+            super::sys::RADAR_CLOSEST_CONTACT = [
+                1.0,
+                Class::Missile as u32 as f64,
+                1.0,
+                2.0,
+                3.0,
+                4.0,
+                -90.0,
+                10.0,
+                5.0,
+            ];
+        }
+        let contact = closest_contact().unwrap();
+        assert_eq!(contact.class, Class::Missile);
+        assert_eq!(contact.position, vec2(1.0, 2.0));
+        assert_eq!(contact.velocity, vec2(3.0, 4.0));
+        assert_eq!(contact.rssi, -90.0);
+        assert_eq!(contact.snr, 10.0);
+        assert_eq!(contact.closing_speed, 5.0);
+
+        write_system_state(SystemState::PositionX, 4.0);
+        write_system_state(SystemState::PositionY, 6.0);
+        assert_eq!(contact.distance(), 5.0);
+    }
+
+    #[test]
+    fn test_radar_jammer_bearing() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::RadarJammerBearing, f64::NAN);
+        assert_eq!(radar_jammer_bearing(), None);
+        write_system_state(SystemState::RadarJammerBearing, 1.5);
+        assert_eq!(radar_jammer_bearing(), Some(1.5));
+    }
+
+    #[test]
+    fn test_radar_track() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        untrack_radar();
+        assert!(!radar_tracking());
+        track_radar();
+        assert!(radar_tracking());
+        untrack_radar();
+        assert!(!radar_tracking());
+    }
+
+    #[test]
+    fn test_scan_all() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::RadarContactCount, 0.0);
+        assert!(scan_all().is_empty());
+
+        unsafe {
+            super::sys::RADAR_CONTACTS[0][1] = Class::Cruiser as u32 as f64;
+            super::sys::RADAR_CONTACTS[0][2] = 1.0;
+            super::sys::RADAR_CONTACTS[0][3] = 2.0;
+            super::sys::RADAR_CONTACTS[1][1] = Class::Fighter as u32 as f64;
+            super::sys::RADAR_CONTACTS[1][6] = -90.0;
+        }
+        write_system_state(SystemState::RadarContactCount, 2.0);
+        let contacts = scan_all();
+        assert_eq!(contacts.len(), 2);
+        assert_eq!(contacts[0].class, Class::Cruiser);
+        assert_eq!(contacts[0].position, vec2(1.0, 2.0));
+        assert_eq!(contacts[1].class, Class::Fighter);
+        assert_eq!(contacts[1].rssi, -90.0);
+        write_system_state(SystemState::RadarContactCount, 0.0);
+    }
+
+    #[test]
+    fn test_nearest_friendly() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        unsafe {
+            super::sys::FRIENDLY_CONTACT = [0.0; super::FRIENDLY_CONTACT_SIZE];
+        }
+        assert!(nearest_friendly().is_none());
+        unsafe {
+            super::sys::FRIENDLY_CONTACT = [1.0, Class::Frigate as u32 as f64, 1.0, 2.0, 3.0, 4.0];
+        }
+        let friendly = nearest_friendly().unwrap();
+        assert_eq!(friendly.class, Class::Frigate);
+        assert_eq!(friendly.position, vec2(1.0, 2.0));
+        assert_eq!(friendly.velocity, vec2(3.0, 4.0));
+    }
+
+    #[test]
+    fn test_closest_point_of_approach() {
+        let eps = 1e-9;
+        let (time, distance) = closest_point_of_approach(
+            vec2(0.0, 0.0),
+            vec2(100.0, 0.0),
+            vec2(1000.0, -1000.0),
+            vec2(0.0, 100.0),
+        );
+        assert!((time - 10.0).abs() < eps, "{time}");
+        assert!(distance < eps, "{distance}");
+
+        let (time, distance) = closest_point_of_approach(
+            vec2(0.0, 0.0),
+            vec2(100.0, 0.0),
+            vec2(0.0, 500.0),
+            vec2(100.0, 0.0),
+        );
+        assert_eq!((time, distance), (0.0, 500.0));
+
+        let (time, distance) = closest_point_of_approach(
+            vec2(0.0, 0.0),
+            vec2(0.0, 0.0),
+            vec2(300.0, 400.0),
+            vec2(30.0, 40.0),
+        );
+        assert_eq!(time, 0.0);
+        assert!((distance - 500.0).abs() < eps);
+    }
+
+    #[test]
+    fn test_aim_lead_moving() {
+        let eps = 1e-9;
+        let target_position = vec2(1000.0, 0.0);
+        let target_velocity = vec2(0.0, 100.0);
+
+        // A stationary shooter leads the target, one matching its velocity
+        // fires straight at it.
+        let stationary = aim_lead_moving(target_position, target_velocity, vec2(0.0, 0.0), 1000.0);
+        assert!(stationary.y > 0.05, "{stationary:?}");
+        let moving = aim_lead_moving(target_position, target_velocity, target_velocity, 1000.0);
+        assert!((moving - vec2(1.0, 0.0)).length() < eps, "{moving:?}");
+
+        // The bullet and target meet.
+        let own_velocity = vec2(-200.0, 300.0);
+        let direction = aim_lead_moving(target_position, target_velocity, own_velocity, 1000.0);
+        assert!((direction.length() - 1.0).abs() < eps);
+        let bullet_velocity = own_velocity + direction * 1000.0;
+        let dv = bullet_velocity - target_velocity;
+        let t = target_position.dot(dv) / dv.dot(dv);
+        let miss = (target_position + target_velocity * t - bullet_velocity * t).length();
+        assert!(t > 0.0 && miss < 1e-6, "t={t} miss={miss}");
+
+        // A target outrunning the bullet is aimed at directly.
+        let fleeing = aim_lead_moving(target_position, vec2(2000.0, 0.0), vec2(0.0, 0.0), 1000.0);
+        assert!((fleeing - vec2(1.0, 0.0)).length() < eps, "{fleeing:?}");
+    }
+
+    #[test]
+    fn test_debug_vector() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        let lines = || unsafe { std::mem::take(&mut super::dbg::LINE_BUFFER) };
+        let origin = vec2(100.0, 50.0);
+        lines();
+
+        debug_vector(origin, vec2(0.0, 200.0), 0xff0000);
+        let arrow = lines();
+        assert_eq!(arrow.len(), 3);
+        let tip = vec2(100.0, 250.0);
+        let start = |line: &super::Line| vec2(line.x0, line.y0);
+        let end = |line: &super::Line| vec2(line.x1, line.y1);
+        assert_eq!((start(&arrow[0]), end(&arrow[0])), (origin, tip));
+        for barb in &arrow[1..] {
+            assert_eq!(start(barb), tip);
+            assert!((end(barb).distance(tip) - 40.0).abs() < 1e-9);
+            assert!(end(barb).y < tip.y);
+            assert_eq!(barb.color, 0xff0000);
+        }
+        assert!((end(&arrow[1]).x - 100.0) * (end(&arrow[2]).x - 100.0) < 0.0);
+
+        // Long vectors are clamped, zero ones draw nothing.
+        debug_vector(origin, vec2(1e6, 0.0), 0xff0000);
+        let arrow = lines();
+        assert!((end(&arrow[0]).distance(origin) - MAX_VECTOR_LENGTH).abs() < 1e-9);
+        debug_vector(origin, vec2(0.0, 0.0), 0xff0000);
+        assert!(lines().is_empty());
+    }
+
+    #[test]
+    fn test_scenario_parameters() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        let set_environment = |environment: &str| unsafe {
+            super::sys::ENVIRONMENT = [0; super::MAX_ENVIRONMENT_SIZE];
+            super::sys::ENVIRONMENT[..environment.len()].copy_from_slice(environment.as_bytes());
+        };
+
+        set_environment("SCENARIO_NAME=tutorial_frigate\nWORLD_SIZE=40000\nTEAM_COUNT=3");
+        assert_eq!(scenario_name(), "tutorial_frigate");
+        assert_eq!(world_size(), 40000.0);
+        assert_eq!(team_count(), 3);
+
+        set_environment("");
+        assert_eq!(scenario_name(), "unknown");
+        assert_eq!(team_count(), 0);
+    }
+
+    #[test]
+    fn test_status() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::Health, 80.0);
+        write_system_state(SystemState::Shield, 5.0);
+        write_system_state(SystemState::Fuel, 1200.0);
+        write_system_state(SystemState::Ammo0, 17.0);
+        write_system_state(SystemState::Ammo1, f64::INFINITY);
+        write_system_state(SystemState::ReloadTicks0, 3.0);
+        write_system_state(SystemState::Heat0, 0.25);
+        write_system_state(SystemState::ThermalSignature, 42.0);
+        write_system_state(SystemState::Burning, 0.0);
+        write_system_state(SystemState::ImpulseReady, 1.0);
+
+        let status = status();
+        assert_eq!(status.health, health());
+        assert_eq!(status.shield, shield());
+        assert_eq!(status.fuel, fuel());
+        for i in 0..super::MAX_GUNS {
+            assert_eq!(status.ammo[i], ammo(i));
+            assert_eq!(status.reload_ticks[i], reload_ticks(i));
+            assert_eq!(status.heat[i], heat(i));
+        }
+        assert_eq!(status.heat[0], 0.25);
+        assert_eq!(status.ammo[0], 17.0);
+        assert!(status.ammo[1].is_infinite());
+        assert_eq!(status.reload_ticks[0], 3);
+        assert_eq!(status.thermal_signature, thermal_signature());
+        assert!(!status.burning);
+        assert!(status.impulse_ready);
+    }
+
+    #[test]
+    fn test_weapon_jam() {
+        let _guard = SYSTEM_STATE_LOCK.lock().unwrap();
+        write_system_state(SystemState::JamTicks0, 0.0);
+        write_system_state(SystemState::JamTicks1, 45.0);
+
+        assert!(!is_jammed(0));
+        assert!(is_jammed(1));
+        assert_eq!(weapon_cooldown_remaining(1), 45);
+        assert_eq!(weapon_cooldown_remaining(super::MAX_GUNS), 0);
+    }
+}
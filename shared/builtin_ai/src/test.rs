@@ -1,11 +1,13 @@
 #![allow(clippy::empty_loop)]
 use oort_api::prelude::*;
 
-pub struct Ship {}
+pub struct Ship {
+    ticks: u32,
+}
 
 impl Ship {
     pub fn new() -> Ship {
-        Ship {}
+        Ship { ticks: 0 }
     }
 
     pub fn tick(&mut self) {
@@ -14,9 +16,17 @@ impl Ship {
             "scenario_name" => debug!("Scenario: {}", scenario_name()),
             "world_size" => debug!("World size: {}", world_size()),
             "id" => debug!("ID: {}", id()),
+            "debug_values" => {
+                // Only set on the first tick to check the values are cleared.
+                if self.ticks == 0 {
+                    debug_set("answer", 42.0);
+                    debug_set("speed", velocity().length());
+                }
+            }
             "panic" => panic!("Panic!"),
             "infinite_loop" => loop {},
             _ => debug!("Unknown testcase: {:?}", testcase),
         }
+        self.ticks += 1;
     }
 }
@@ -1,6 +1,33 @@
 use anyhow::{bail, Result};
 use std::path::{Path, PathBuf};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A compiler message about the user's code.
+///
+/// Lines and columns are 1-based and refer to the source passed to the
+/// compiler. Both are zero for failures that rustc didn't attribute to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: u32,
+    pub col: u32,
+    pub severity: Severity,
+    pub message: String,
+}
+
 pub struct Compiler {
     #[allow(dead_code)]
     tmp_dir: Option<tempdir::TempDir>,
@@ -102,6 +129,29 @@ impl Compiler {
         self.compile_fast(code)
     }
 
+    /// Like [`compile`](Self::compile), but breaks a failure down into the
+    /// messages rustc reported against `code`.
+    pub fn compile_with_diagnostics(&mut self, code: &str) -> Result<Vec<u8>, Vec<Diagnostic>> {
+        self.compile(code).map_err(|e| {
+            let message = e.to_string();
+            // Drop the "rustc failed: " prefix in front of the compiler output.
+            let output = message
+                .split_once(" failed: ")
+                .map_or(&message[..], |(_, x)| x);
+            let diagnostics = parse_diagnostics(output);
+            if diagnostics.is_empty() {
+                vec![Diagnostic {
+                    line: 0,
+                    col: 0,
+                    severity: Severity::Error,
+                    message,
+                }]
+            } else {
+                diagnostics
+            }
+        })
+    }
+
     pub fn compile_fast(&mut self, code: &str) -> Result<Vec<u8> /* wasm */> {
         let tmp_path = &self.dir;
         std::fs::write(tmp_path.join("ai/src/user.rs"), code.as_bytes())?;
@@ -166,6 +216,62 @@ impl Compiler {
     }
 }
 
+/// Extracts the errors and warnings that point into the user's code from
+/// rustc's human readable output, e.g.:
+///
+/// ```text
+/// error[E0425]: cannot find value `x` in this scope
+///  --> /tmp/oort-ai/ai/src/user.rs:3:5
+/// ```
+fn parse_diagnostics(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut pending: Option<(Severity, &str)> = None;
+    for line in output.lines() {
+        if let Some(header) = parse_diagnostic_header(line) {
+            pending = Some(header);
+            continue;
+        }
+        let Some(location) = line.trim_start().strip_prefix("--> ") else {
+            continue;
+        };
+        let Some((severity, message)) = pending.take() else {
+            continue;
+        };
+        let mut parts = location.rsplitn(3, ':');
+        let (Some(col), Some(line), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if !path.ends_with("ai/src/user.rs") {
+            continue;
+        }
+        if let (Ok(line), Ok(col)) = (line.parse(), col.parse()) {
+            diagnostics.push(Diagnostic {
+                line,
+                col,
+                severity,
+                message: message.to_string(),
+            });
+        }
+    }
+    diagnostics
+}
+
+fn parse_diagnostic_header(line: &str) -> Option<(Severity, &str)> {
+    let (severity, rest) = if let Some(rest) = line.strip_prefix("error") {
+        (Severity::Error, rest)
+    } else if let Some(rest) = line.strip_prefix("warning") {
+        (Severity::Warning, rest)
+    } else {
+        return None;
+    };
+    // Skip the error code, e.g. "[E0425]".
+    let rest = match rest.strip_prefix('[') {
+        Some(rest) => rest.split_once(']')?.1,
+        None => rest,
+    };
+    rest.strip_prefix(": ").map(|message| (severity, message))
+}
+
 fn find_rlib(tmp_path: &Path, crate_name: &str) -> PathBuf {
     if let Some(path) = glob::glob(
         tmp_path
@@ -202,3 +308,62 @@ fn find_rustc() -> String {
         "rustc".to_string()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{parse_diagnostics, Diagnostic, Severity};
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let output = r#"
+   Compiling oort_ai v0.1.0 (/tmp/oort-ai/ai)
+warning: unused variable: `x`
+ --> /tmp/oort-ai/ai/src/user.rs:5:13
+  |
+5 |         let x = 1;
+  |             ^ help: if this is intentional, prefix it with an underscore: `_x`
+  |
+  = note: `#[warn(unused_variables)]` on by default
+
+error[E0425]: cannot find value `y` in this scope
+  --> /tmp/oort-ai/ai/src/user.rs:12:9
+   |
+12 |         y
+   |         ^ not found in this scope
+
+warning: unused import: `std::fmt`
+ --> /tmp/oort-ai/ai/src/lib.rs:1:5
+
+error: aborting due to previous error; 2 warnings emitted
+"#;
+        assert_eq!(
+            parse_diagnostics(output),
+            vec![
+                Diagnostic {
+                    line: 5,
+                    col: 13,
+                    severity: Severity::Warning,
+                    message: "unused variable: `x`".to_string(),
+                },
+                Diagnostic {
+                    line: 12,
+                    col: 9,
+                    severity: Severity::Error,
+                    message: "cannot find value `y` in this scope".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_diagnostics_ignores_other_output() {
+        let output = "\
+   Compiling oort_api v0.64.0
+    Finished release [optimized] target(s) in 1.23s
+ --> /tmp/oort-ai/ai/src/user.rs:1:1
+error: linking with `rust-lld` failed
+ --> not a location
+";
+        assert_eq!(parse_diagnostics(output), vec![]);
+    }
+}
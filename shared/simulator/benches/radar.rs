@@ -0,0 +1,59 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra::vector;
+use oort_simulator::ship::fighter;
+use oort_simulator::simulation::{Code, Simulation};
+use oort_simulator::{rng, ship};
+use rand::Rng;
+use std::time::Duration;
+
+// Every ship has a radar and is a reflector for the other team, so scan cost
+// grows with the square of the count without the spatial index.
+fn fleet(count: usize, spatial_index: bool) -> Box<Simulation> {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    sim.set_radar_spatial_index(spatial_index);
+    let mut rng = rng::new_rng(0);
+    for i in 0..count {
+        let p = vector![rng.gen_range(-15e3..15e3), rng.gen_range(-15e3..15e3)];
+        let heading = rng.gen_range(0.0..std::f64::consts::TAU);
+        ship::create(
+            &mut sim,
+            p,
+            vector![0.0, 0.0],
+            heading,
+            fighter((i % 2) as i32),
+        );
+    }
+    sim
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("radar_tick");
+    for count in [50, 200, 800] {
+        for (name, spatial_index) in [("spatial_index", true), ("linear", false)] {
+            group.bench_with_input(BenchmarkId::new(name, count), &count, |b, &count| {
+                let mut sim = fleet(count, spatial_index);
+                // Only the radar's share of each step is measured.
+                b.iter_custom(|iters| {
+                    let mut total = Duration::ZERO;
+                    for _ in 0..iters {
+                        sim.step();
+                        total += Duration::from_secs_f64(sim.timing().radar);
+                    }
+                    total
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+pub fn criterion_config() -> Criterion {
+    Criterion::default()
+        .sample_size(10)
+        .measurement_time(core::time::Duration::from_secs(10))
+}
+
+criterion_group!(name = benches;
+                 config = criterion_config();
+                 targets = criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, target};
+use oort_simulator::simulation::{Code, Simulation};
+use rand::Rng;
+use std::f64::consts::TAU;
+
+fn hundred_ships() -> Box<Simulation> {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut rng = oort_simulator::rng::new_rng(0);
+    for i in 0..100 {
+        let p = vector![rng.gen_range(-10e3..10e3), rng.gen_range(-10e3..10e3)];
+        let v = vector![rng.gen_range(-100.0..100.0), rng.gen_range(-100.0..100.0)];
+        ship::create(&mut sim, p, v, rng.gen_range(0.0..TAU), fighter(i % 2));
+    }
+    sim
+}
+
+fn radar_targets(spatial_index: bool) -> Box<Simulation> {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    sim.set_radar_spatial_index(spatial_index);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.ship_mut(ship0).radar_mut().unwrap().width = TAU / 4.0;
+    let mut rng = oort_simulator::rng::new_rng(0);
+    for _ in 0..100 {
+        let p = vector![rng.gen_range(-20e3..20e3), rng.gen_range(-20e3..20e3)];
+        ship::create(&mut sim, p, vector![0.0, 0.0], 0.0, target(1));
+    }
+    sim
+}
+
+fn bullet_contacts(pooling: bool) -> Box<Simulation> {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    sim.set_bullet_pooling(pooling);
+    for i in 0..50 {
+        let y = i as f64 * 50.0;
+        ship::create(
+            &mut sim,
+            vector![0.0, y],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![300.0, y],
+            vector![0.0, 0.0],
+            0.0,
+            ship::ShipData {
+                health: 1e9,
+                ..target(1)
+            },
+        );
+    }
+    sim
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    c.bench_function("step_100_ships", |b| {
+        let mut sim = hundred_ships();
+        b.iter(|| sim.step());
+    });
+
+    let mut group = c.benchmark_group("radar_scan_100_targets");
+    for (name, spatial_index) in [("spatial_index", true), ("linear", false)] {
+        group.bench_function(name, |b| {
+            let mut sim = radar_targets(spatial_index);
+            let mut timing = 0.0;
+            let mut c = 0;
+            b.iter(|| {
+                c += 1;
+                sim.step();
+                timing += sim.timing().radar;
+            });
+            if c > 0 {
+                println!("\nRadar: {:.3}ms", timing * (1e3 / c as f64));
+            }
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("bullet_contacts");
+    for (name, pooling) in [("fresh", false), ("pooled", true)] {
+        group.bench_function(name, |b| {
+            let mut sim = bullet_contacts(pooling);
+            b.iter(|| {
+                for handle in sim.ships.iter().copied().collect::<Vec<_>>() {
+                    if sim.ship(handle).data().team == 0 {
+                        sim.ship_mut(handle).fire_gun(0);
+                    }
+                }
+                sim.step();
+            });
+        });
+    }
+    group.finish();
+}
+
+pub fn criterion_config() -> Criterion {
+    Criterion::default()
+        .sample_size(10)
+        .measurement_time(core::time::Duration::from_secs(10))
+}
+
+criterion_group!(name = benches;
+                 config = criterion_config();
+                 targets = criterion_benchmark);
+criterion_main!(benches);
@@ -1,16 +1,18 @@
 use std::collections::HashMap;
 
 use super::index_set::{HasIndex, Index};
-use crate::simulation::{Simulation, MAX_WORLD_SIZE, PHYSICS_TICK_LENGTH};
-use crate::{collision, simulation};
+use crate::ship::{ShipHandle, StatusEffect};
+use crate::simulation::{Simulation, MAX_WORLD_SIZE};
+use crate::{collision, scenario, simulation};
 use bitvec::vec::BitVec;
-use nalgebra::Vector2;
+use nalgebra::{Rotation2, Vector2};
 use rapier2d_f64::prelude::*;
+use rapier2d_f64::parry::bounding_volume::BoundingVolume;
 use static_aabb2d_index::*;
 
 const COLOR_COLLIDERS: bool = false;
 
-#[derive(Hash, PartialEq, Eq, Copy, Clone, PartialOrd, Ord)]
+#[derive(Hash, PartialEq, Eq, Copy, Clone, Debug, PartialOrd, Ord)]
 pub struct BulletHandle(pub Index);
 
 impl HasIndex for BulletHandle {
@@ -25,12 +27,122 @@ impl From<BulletHandle> for RigidBodyHandle {
     }
 }
 
-#[derive(Default, Clone)]
+/// Collider radius of bullets that don't specify one.
+pub const DEFAULT_RADIUS: f64 = 1.0;
+
+#[derive(Clone)]
 pub struct BulletData {
     pub mass: f32,
     pub team: i32,
     pub ttl: f32,
     pub color: u32,
+    /// Ship that fired this bullet, used for kill attribution.
+    pub source: Option<ShipHandle>,
+    pub damage_type: DamageType,
+    pub falloff: DamageFalloff,
+    /// Spawn position, set by [`create`]. Used for damage falloff.
+    pub origin: Vector2<f64>,
+    /// Effect applied to the ship this bullet hits.
+    pub status_effect: Option<StatusEffect>,
+    /// Makes the bullet home in on a ship.
+    pub guidance: Option<BulletGuidance>,
+    /// Times the bullet bounces off walls before being destroyed. Zero
+    /// destroys it on the first wall hit.
+    pub max_bounces: u32,
+    pub bounces: u32,
+    /// Radius of the bullet's collider (in meters).
+    pub radius: f64,
+    pub kind: BulletKind,
+}
+
+impl Default for BulletData {
+    fn default() -> Self {
+        Self {
+            mass: 0.0,
+            team: 0,
+            ttl: 0.0,
+            color: 0,
+            source: None,
+            damage_type: Default::default(),
+            falloff: Default::default(),
+            origin: Default::default(),
+            status_effect: None,
+            guidance: None,
+            max_bounces: 0,
+            bounces: 0,
+            radius: DEFAULT_RADIUS,
+            kind: BulletKind::Kinetic,
+        }
+    }
+}
+
+/// Steers a bullet towards `target` each tick, keeping its speed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BulletGuidance {
+    pub target: ShipHandle,
+    /// Maximum rate the velocity vector turns (in radians/s).
+    pub turn_rate: f64,
+}
+
+/// What happens when a bullet hits an enemy ship.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum BulletKind {
+    /// Damages the ship and is destroyed, unless the hit destroys the ship.
+    #[default]
+    Kinetic,
+    /// Damages the ship and keeps going, so it can hit several ships in a
+    /// line.
+    Piercing,
+    /// Damages every enemy ship within `radius` meters of the impact point,
+    /// each as if hit directly, and is destroyed.
+    Explosive { radius: f64 },
+}
+
+/// Kind of damage dealt by a bullet, scaled by the target's
+/// [`DamageResistance`](crate::ship::DamageResistance).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageType {
+    #[default]
+    Kinetic,
+    Energy,
+}
+
+/// How bullet damage decreases with distance from the firing point.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum DamageFalloff {
+    #[default]
+    None,
+    /// Damage decreases linearly, reaching zero at `range` meters.
+    Linear { range: f64 },
+    /// Damage decreases with the square of distance, reaching zero at `range` meters.
+    Quadratic { range: f64 },
+}
+
+impl DamageFalloff {
+    pub fn multiplier(&self, distance: f64) -> f64 {
+        match *self {
+            DamageFalloff::None => 1.0,
+            DamageFalloff::Linear { range } => (1.0 - distance / range).max(0.0),
+            DamageFalloff::Quadratic { range } => (1.0 - (distance / range).powi(2)).max(0.0),
+        }
+    }
+}
+
+/// Bodies of destroyed bullets kept for reuse. See
+/// [`Simulation::set_bullet_pooling`].
+#[derive(Clone, Default)]
+pub(crate) struct BulletPool {
+    pub(crate) enabled: bool,
+    free: Vec<BulletHandle>,
+    // Destroyed this tick. Not reused until the next tick because collision
+    // events being handled may still refer to them.
+    pending: Vec<BulletHandle>,
+}
+
+impl BulletPool {
+    pub(crate) fn recycle_pending(&mut self) {
+        self.free.append(&mut self.pending);
+    }
 }
 
 pub fn body(sim: &Simulation, handle: BulletHandle) -> &RigidBody {
@@ -49,23 +161,65 @@ pub fn data_mut(sim: &mut Simulation, handle: BulletHandle) -> &mut BulletData {
     sim.bullet_data.get_mut(handle.index()).unwrap()
 }
 
+/// Estimates how many seconds until the bullet reaches `ship`, assuming both
+/// keep their current velocities.
+///
+/// The ship is treated as a circle of its bounding radius. Returns `None` if
+/// the bullet will miss, is moving away, or expires first, and zero if it is
+/// already touching the ship.
+pub fn time_to_impact(sim: &Simulation, handle: BulletHandle, ship: ShipHandle) -> Option<f64> {
+    let bullet_body = body(sim, handle);
+    let ship = sim.ship(ship);
+    let dp = bullet_body.translation() - ship.position().vector;
+    let dv = bullet_body.linvel() - ship.velocity();
+    let radius = ship.radius() + data(sim, handle).radius;
+
+    // Solve |dp + dv * t| = radius for the earliest t.
+    let c = dp.dot(&dp) - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let a = dv.dot(&dv);
+    let b = 2.0 * dp.dot(&dv);
+    let discriminant = b * b - 4.0 * a * c;
+    if a < 1e-9 || b >= 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    if t > data(sim, handle).ttl as f64 {
+        return None;
+    }
+    Some(t)
+}
+
 pub fn create(
     sim: &mut Simulation,
     position: Vector2<f64>,
     velocity: Vector2<f64>,
     mut data: BulletData,
 ) -> BulletHandle {
+    if COLOR_COLLIDERS {
+        data.color = 0xff0000ff;
+    }
+    data.origin = position;
+    if let Some(handle) = sim.bullet_pool.free.pop() {
+        let body = sim.bodies.get_mut(handle.into()).unwrap();
+        body.set_enabled(true);
+        body.set_translation(position, true);
+        body.set_linvel(velocity, true);
+        sim.bullet_data.insert(handle.index(), data);
+        sim.bullets.insert(handle);
+        return handle;
+    }
     let rigid_body = RigidBodyBuilder::dynamic()
         .translation(position)
         .linvel(velocity)
+        .linear_damping(sim.linear_damping)
         .ccd_enabled(true)
         .additional_mass(0.1)
         .build();
     let body_handle = sim.bodies.insert(rigid_body);
     let handle = BulletHandle(body_handle.0);
-    if COLOR_COLLIDERS {
-        data.color = 0xff0000ff;
-    }
     sim.bullet_data.insert(handle.index(), data);
     sim.bullets.insert(handle);
     handle
@@ -75,6 +229,15 @@ pub fn destroy(sim: &mut Simulation, handle: BulletHandle) {
     sim.bullet_data
         .remove(handle.index(), BulletData::default());
     sim.bullets.remove(handle);
+    if sim.bullet_pool.enabled {
+        remove_collider(sim, handle);
+        sim.bodies
+            .get_mut(handle.into())
+            .unwrap()
+            .set_enabled(false);
+        sim.bullet_pool.pending.push(handle);
+        return;
+    }
     sim.bodies.remove(
         RigidBodyHandle(handle.index()),
         &mut sim.island_manager,
@@ -86,10 +249,9 @@ pub fn destroy(sim: &mut Simulation, handle: BulletHandle) {
 }
 
 pub fn tick(sim: &mut Simulation) {
-    let dt = PHYSICS_TICK_LENGTH;
+    let dt = sim.dt();
     let (indices_by_team, coarse_grids_by_team) = build_indices(sim, dt);
     let mut stack = Vec::new();
-    let shape = rapier2d_f64::geometry::Ball { radius: 1.0 };
     let bullets: Vec<BulletHandle> = sim.bullets.iter().cloned().collect();
     for handle in bullets {
         let team = {
@@ -102,27 +264,44 @@ pub fn tick(sim: &mut Simulation) {
             data.team
         };
 
+        if let Some(guidance) = data(sim, handle).guidance {
+            guide(sim, handle, &guidance, dt);
+        }
+
         let has_collider;
         let coarse_grid_hit;
-        let mut needs_collider = false;
+        // Enemy bullets, walls and debris could be anywhere, so bullet
+        // collisions, ricochets, interior walls and debris need a collider at
+        // all times.
+        let mut needs_collider = sim.bullet_collisions
+            || sim.polygon_walls
+            || !sim.debris.is_empty()
+            || data(sim, handle).max_bounces > 0;
+        let shape = rapier2d_f64::geometry::Ball {
+            radius: data(sim, handle).radius,
+        };
         {
             let world_size = sim.world_size();
-            let body = sim.bodies.get_mut(RigidBodyHandle(handle.index())).unwrap();
+            let walls = sim.boundary() == scenario::Boundary::Walls;
+            let arena_radius = sim.arena_radius();
+            let body = sim.bodies.get(RigidBodyHandle(handle.index())).unwrap();
             has_collider = !body.colliders().is_empty();
 
             let position = *body.translation();
-            if position.x < -world_size / 2.0
-                || position.x > world_size / 2.0
-                || position.y < -world_size / 2.0
-                || position.y > world_size / 2.0
+            if (walls
+                && (position.x < -world_size / 2.0
+                    || position.x > world_size / 2.0
+                    || position.y < -world_size / 2.0
+                    || position.y > world_size / 2.0))
+                || arena_radius.map_or(false, |r| position.norm_squared() > r * r)
             {
                 destroy(sim, handle);
                 continue;
             }
 
-            coarse_grid_hit = coarse_grids_by_team
-                .iter()
-                .any(|(other_team, grid)| *other_team != team && grid.lookup(position));
+            coarse_grid_hit = coarse_grids_by_team.iter().any(|(other_team, grid)| {
+                sim.are_hostile(*other_team, team) && grid.lookup(position)
+            });
             if coarse_grid_hit {
                 let aabb = shape.compute_swept_aabb(
                     body.position(),
@@ -130,7 +309,7 @@ pub fn tick(sim: &mut Simulation) {
                 );
 
                 for (other_team, index) in indices_by_team.iter() {
-                    if team != *other_team {
+                    if sim.are_hostile(team, *other_team) {
                         needs_collider = needs_collider
                             || index
                                 .query_iter_with_stack(
@@ -165,6 +344,58 @@ pub fn tick(sim: &mut Simulation) {
     }
 }
 
+/// Turns the bullet's velocity towards the target by at most one tick of
+/// `turn_rate`. Does nothing once the target is gone.
+fn guide(sim: &mut Simulation, handle: BulletHandle, guidance: &BulletGuidance, dt: f64) {
+    if !sim.ships.contains(guidance.target) {
+        return;
+    }
+    let target_position = sim.ship(guidance.target).position().vector;
+    let body = body_mut(sim, handle);
+    let velocity = *body.linvel();
+    let dp = target_position - body.translation();
+    if velocity.magnitude_squared() == 0.0 || dp.magnitude_squared() == 0.0 {
+        return;
+    }
+    let error = velocity.angle(&dp) * velocity.perp(&dp).signum();
+    let max_step = guidance.turn_rate * dt;
+    let rotation = Rotation2::new(error.clamp(-max_step, max_step));
+    body.set_linvel(rotation * velocity, true);
+}
+
+/// Bounces the bullet off `wall` if it has bounces left, otherwise destroys
+/// it.
+pub(crate) fn ricochet(sim: &mut Simulation, handle: BulletHandle, wall: ColliderHandle) {
+    let data = data_mut(sim, handle);
+    if data.bounces >= data.max_bounces {
+        destroy(sim, handle);
+        return;
+    }
+    data.bounces += 1;
+    let wall = sim.colliders.get(wall).unwrap();
+    let body = sim.bodies.get(handle.into()).unwrap();
+    let position = *body.translation();
+    let velocity = *body.linvel();
+    let projection = wall
+        .shape()
+        .project_point(wall.position(), &position.into(), false);
+    let offset = position - projection.point.coords;
+    // Far from the origin the offset can be tiny but still points the right
+    // way, so only fall back when the bullet is exactly on the surface.
+    let mut normal = offset.try_normalize(0.0).unwrap_or(-velocity.normalize());
+    // Face the side the bullet came from, even if it's already past the
+    // surface.
+    if normal.dot(&velocity) > 0.0 {
+        normal = -normal;
+    }
+    let depth = offset.dot(&normal);
+    let body = body_mut(sim, handle);
+    body.set_linvel(velocity - 2.0 * velocity.dot(&normal) * normal, true);
+    if depth < 0.0 {
+        body.set_translation(position - 2.0 * depth * normal, true);
+    }
+}
+
 fn build_indices(
     sim: &Simulation,
     dt: f64,
@@ -200,9 +431,12 @@ fn build_indices(
 
 fn add_collider(sim: &mut Simulation, handle: BulletHandle) {
     let team = data(sim, handle).team;
-    let collider = ColliderBuilder::ball(1.0)
+    let collider = ColliderBuilder::ball(data(sim, handle).radius)
         .restitution(1.0)
-        .collision_groups(collision::bullet_interaction_groups(team))
+        .collision_groups(collision::bullet_interaction_groups(
+            team,
+            sim.bullet_collisions,
+        ))
         .active_events(ActiveEvents::COLLISION_EVENTS)
         .sensor(true)
         .build();
@@ -229,6 +463,9 @@ fn remove_collider(sim: &mut Simulation, handle: BulletHandle) {
 
 struct CoarseGrid {
     cells: BitVec,
+    /// Inflated AABBs reaching past the cells, which only cover
+    /// `MAX_WORLD_SIZE`. Ships in open worlds can fly beyond it.
+    outside: Vec<Aabb>,
 }
 
 impl CoarseGrid {
@@ -243,13 +480,29 @@ impl CoarseGrid {
         (Self::WIDTH * y + x) as usize
     }
 
+    fn bounds() -> Aabb {
+        Aabb::from_half_extents(
+            point![0.0, 0.0],
+            vector![MAX_WORLD_SIZE / 2.0, MAX_WORLD_SIZE / 2.0],
+        )
+    }
+
     pub fn new() -> Self {
         let mut cells = BitVec::new();
         cells.resize((Self::WIDTH * Self::WIDTH) as usize, false);
-        Self { cells }
+        Self {
+            cells,
+            outside: Vec::new(),
+        }
     }
 
     pub fn lookup(&self, p: Vector2<f64>) -> bool {
+        if !Self::bounds().contains_local_point(&p.into()) {
+            return self
+                .outside
+                .iter()
+                .any(|aabb| aabb.contains_local_point(&p.into()));
+        }
         let index: usize = Self::to_cell(p);
         if index < self.cells.len() {
             self.cells[index]
@@ -261,10 +514,11 @@ impl CoarseGrid {
     pub fn insert(&mut self, mut aabb: Aabb) {
         aabb.mins -= vector![Self::CELL_SIZE, Self::CELL_SIZE];
         aabb.maxs += vector![Self::CELL_SIZE, Self::CELL_SIZE];
-        if let Some(aabb) = aabb.intersection(&Aabb::from_half_extents(
-            point![0.0, 0.0],
-            vector![MAX_WORLD_SIZE / 2.0, MAX_WORLD_SIZE / 2.0],
-        )) {
+        let bounds = Self::bounds();
+        if !bounds.contains(&aabb) {
+            self.outside.push(aabb);
+        }
+        if let Some(aabb) = aabb.intersection(&bounds) {
             let w = ((aabb.maxs.x - aabb.mins.x) * Self::RECIP_CELL_SIZE).ceil() as i32;
             let h = ((aabb.maxs.y - aabb.mins.y) * Self::RECIP_CELL_SIZE).ceil() as i32;
             let min_index = Self::to_cell(aabb.mins.coords);
@@ -1,18 +1,26 @@
-use crate::bullet::{self, BulletHandle};
+use crate::bullet::{self, BulletHandle, BulletKind};
+use crate::debris;
 use crate::index_set::HasIndex;
+use crate::mine;
+use crate::repair;
 use crate::ship::{ShipClass, ShipHandle};
-use crate::simulation::{Particle, Simulation, PHYSICS_TICK_LENGTH};
-use nalgebra::{Rotation2, UnitComplex};
+use crate::simulation::{ExplosionEffect, Particle, SimEvent, Simulation};
+use nalgebra::{Point2, Rotation2, UnitComplex, Vector2};
 use oort_api::Ability;
 use rand::Rng;
 use rapier2d_f64::prelude::*;
+use std::collections::BTreeMap;
 use std::f64::consts::TAU;
 
-const DAMAGE_FACTOR: f64 = 0.00014;
+pub(crate) const DAMAGE_FACTOR: f64 = 0.00014;
 const WALL_COLLISION_GROUP: Group = Group::GROUP_1;
 const SHIP_COLLISION_GROUP: Group = Group::GROUP_2;
 const PLANET_COLLISION_GROUP: Group = Group::GROUP_3;
-const BULLET_GROUPS: &[Group] = &[
+const ZONE_COLLISION_GROUP: Group = Group::GROUP_22;
+const DEBRIS_COLLISION_GROUP: Group = Group::GROUP_23;
+/// Number of teams, each with its own bullet collision group.
+pub const MAX_TEAMS: i32 = 10;
+const BULLET_GROUPS: [Group; MAX_TEAMS as usize] = [
     Group::GROUP_4,
     Group::GROUP_5,
     Group::GROUP_6,
@@ -24,6 +32,27 @@ const BULLET_GROUPS: &[Group] = &[
     Group::GROUP_12,
     Group::GROUP_13,
 ];
+const SHIP_LAYER_GROUPS: &[Group] = &[
+    Group::GROUP_14,
+    Group::GROUP_15,
+    Group::GROUP_16,
+    Group::GROUP_17,
+    Group::GROUP_18,
+    Group::GROUP_19,
+    Group::GROUP_20,
+    Group::GROUP_21,
+];
+
+/// Ships collide with each other only if their collision masks share a bit.
+pub const DEFAULT_SHIP_COLLISION_MASK: u8 = 1;
+
+/// Ticks before a pair of ships in contact can damage each other again, see
+/// [`Simulation::set_ram_damage`].
+pub const COLLISION_DAMAGE_COOLDOWN_TICKS: u32 = 30;
+
+/// Relative speed (in m/s) below which colliding ships don't damage each
+/// other, see [`Simulation::set_ram_damage`].
+pub const RAM_DAMAGE_MIN_SPEED: f64 = 20.0;
 
 fn bullet_group(team: i32) -> Group {
     BULLET_GROUPS[team as usize]
@@ -35,11 +64,15 @@ fn all_bullet_groups() -> Group {
     r
 }
 
-pub fn bullet_interaction_groups(team: i32) -> InteractionGroups {
-    InteractionGroups::new(
-        bullet_group(team),
-        WALL_COLLISION_GROUP | SHIP_COLLISION_GROUP | PLANET_COLLISION_GROUP,
-    )
+pub fn bullet_interaction_groups(team: i32, bullet_collisions: bool) -> InteractionGroups {
+    let mut filter = WALL_COLLISION_GROUP
+        | SHIP_COLLISION_GROUP
+        | PLANET_COLLISION_GROUP
+        | DEBRIS_COLLISION_GROUP;
+    if bullet_collisions {
+        filter |= all_bullet_groups() ^ bullet_group(team);
+    }
+    InteractionGroups::new(bullet_group(team), filter)
 }
 
 pub fn wall_interaction_groups() -> InteractionGroups {
@@ -49,14 +82,39 @@ pub fn wall_interaction_groups() -> InteractionGroups {
     )
 }
 
-pub fn ship_interaction_groups(team: i32) -> InteractionGroups {
+fn ship_layer_groups(mask: u8) -> Group {
+    let mut r = Group::empty();
+    r.extend(
+        SHIP_LAYER_GROUPS
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask & (1 << i) != 0)
+            .map(|(_, &group)| group),
+    );
+    r
+}
+
+pub fn ship_interaction_groups(team: i32, mask: u8) -> InteractionGroups {
     let bullet_groups = all_bullet_groups() ^ bullet_group(team);
+    let layer_groups = ship_layer_groups(mask);
     InteractionGroups::new(
-        SHIP_COLLISION_GROUP,
-        WALL_COLLISION_GROUP | SHIP_COLLISION_GROUP | PLANET_COLLISION_GROUP | bullet_groups,
+        SHIP_COLLISION_GROUP | layer_groups,
+        WALL_COLLISION_GROUP
+            | PLANET_COLLISION_GROUP
+            | ZONE_COLLISION_GROUP
+            | layer_groups
+            | bullet_groups,
     )
 }
 
+pub fn zone_interaction_groups() -> InteractionGroups {
+    InteractionGroups::new(ZONE_COLLISION_GROUP, SHIP_COLLISION_GROUP)
+}
+
+pub fn debris_interaction_groups() -> InteractionGroups {
+    InteractionGroups::new(DEBRIS_COLLISION_GROUP, all_bullet_groups())
+}
+
 pub fn planet_interaction_groups() -> InteractionGroups {
     let bullet_groups = all_bullet_groups();
     InteractionGroups::new(
@@ -65,8 +123,31 @@ pub fn planet_interaction_groups() -> InteractionGroups {
     )
 }
 
+/// Sorts collision events by the colliders involved.
+///
+/// Hits in the same tick then resolve in an order that depends only on the
+/// simulation state, not on the order the physics pipeline reported them.
+/// The sort is stable, so a pair that started and stopped touching in the
+/// same tick keeps its events in order.
+pub(crate) fn sort_collision_events(events: &mut [CollisionEvent]) {
+    events.sort_by_key(|event| {
+        let h1 = event.collider1().into_raw_parts();
+        let h2 = event.collider2().into_raw_parts();
+        (h1.min(h2), h1.max(h2))
+    });
+}
+
 pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
     for event in events {
+        if repair::handle_collision_event(sim, event) {
+            continue;
+        }
+        if debris::handle_collision_event(sim, event) {
+            continue;
+        }
+        if mine::handle_collision_event(sim, event) {
+            continue;
+        }
         if let CollisionEvent::Started(h1, h2, _flags) = event {
             let get_index = |h| sim.colliders.get(h).and_then(|x| x.parent()).map(|x| x.0);
             let handle_hit = |sim: &mut Simulation, ship, bullet: BulletHandle| {
@@ -78,62 +159,40 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                     let dp = bullet_position - sim.ship(ship).position().vector;
                     let normal = dp.normalize();
                     let new_bullet_velocity = normal * bullet_velocity.magnitude();
+                    let dt = sim.dt();
                     {
                         let body = bullet::body_mut(sim, bullet);
                         body.set_linvel(new_bullet_velocity, false);
-                        body.set_translation(
-                            bullet_position + new_bullet_velocity * PHYSICS_TICK_LENGTH,
-                            false,
-                        );
+                        body.set_translation(bullet_position + new_bullet_velocity * dt, false);
                     }
                     bullet::data_mut(sim, bullet).team = sim.ship(ship).data().team;
                     return;
                 }
-                if bullet::data(sim, bullet).team == sim.ship(ship).data().team {
+                if !sim.are_hostile(bullet::data(sim, bullet).team, sim.ship(ship).data().team) {
                     bullet::destroy(sim, bullet);
                     return;
                 }
-                let dv = bullet_velocity - sim.ship(ship).velocity();
-                let energy = 0.5 * bullet::data(sim, bullet).mass as f64 * dv.magnitude_squared();
-                let damage = energy * DAMAGE_FACTOR;
-                for _ in 0..((damage as i32 / 10).clamp(1, 20)) {
-                    let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
-                    let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..1000.0), 0.0]);
-                    let p = bullet_position + v * sim.rng.gen_range(0.0..0.1);
-                    sim.events.particles.push(Particle {
-                        position: p,
-                        velocity: v,
-                        color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
-                        lifetime: (PHYSICS_TICK_LENGTH * 30.0) as f32,
-                    });
-                }
-                let ship_destroyed = {
-                    let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
-                    ship_data.health -= damage;
-                    ship_data.health <= 0.0
-                };
-                if ship_destroyed {
-                    for _ in 0..10 {
-                        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
-                        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..200.0), 0.0]);
-                        let p = sim.ship(ship).body().position().translation.vector
-                            + v * sim.rng.gen_range(0.0..0.1);
-                        let lifetime = (sim.ship_data.get(ship.index()).unwrap().mass.log2()
-                            * PHYSICS_TICK_LENGTH) as f32;
-                        sim.events.particles.push(Particle {
-                            position: p,
-                            velocity: v,
-                            color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
-                            lifetime,
+                match bullet::data(sim, bullet).kind {
+                    BulletKind::Kinetic => {
+                        if damage_ship(sim, ship, bullet, bullet_position, bullet_velocity) {
+                            bullet::data_mut(sim, bullet).mass *= 0.5;
+                            let rotation = UnitComplex::new(sim.rng.gen_range(-0.1..0.1));
+                            let new_bullet_velocity = rotation.transform_vector(&bullet_velocity);
+                            bullet::body_mut(sim, bullet).set_linvel(new_bullet_velocity, false);
+                        } else {
+                            bullet::destroy(sim, bullet);
+                        }
+                    }
+                    BulletKind::Piercing => {
+                        damage_ship(sim, ship, bullet, bullet_position, bullet_velocity);
+                    }
+                    BulletKind::Explosive { radius } => {
+                        let team = bullet::data(sim, bullet).team;
+                        explode(sim, bullet_position, radius, team, |sim, target| {
+                            damage_ship(sim, target, bullet, bullet_position, bullet_velocity);
                         });
+                        bullet::destroy(sim, bullet);
                     }
-                    sim.ship_mut(ship).data_mut().destroyed = true;
-                    bullet::data_mut(sim, bullet).mass *= 0.5;
-                    let rotation = UnitComplex::new(sim.rng.gen_range(-0.1..0.1));
-                    let new_bullet_velocity = rotation.transform_vector(&bullet_velocity);
-                    bullet::body_mut(sim, bullet).set_linvel(new_bullet_velocity, false);
-                } else {
-                    bullet::destroy(sim, bullet);
                 }
             };
             if let (Some(idx1), Some(idx2)) = (get_index(*h1), get_index(*h2)) {
@@ -158,11 +217,25 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                     [Collider::Bullet(b), Collider::Ship(s)] => {
                         handle_hit(sim, s, b);
                     }
+                    [Collider::Bullet(b1), Collider::Bullet(b2)] => {
+                        if sim.are_hostile(bullet::data(sim, b1).team, bullet::data(sim, b2).team) {
+                            bullet::destroy(sim, b1);
+                            bullet::destroy(sim, b2);
+                        }
+                    }
                     [Collider::Bullet(b), Collider::Wall] => {
-                        bullet::destroy(sim, b);
+                        let wall = if idx1 == b.index() { *h2 } else { *h1 };
+                        bullet::ricochet(sim, b, wall);
                     }
                     [Collider::Ship(s1), Collider::Ship(s2)] => {
-                        if sim.ship(s1).data().team != sim.ship(s2).data().team {
+                        if sim.are_hostile(sim.ship(s1).data().team, sim.ship(s2).data().team) {
+                            let tick = sim.tick();
+                            let position = sim.ship(s1).position().vector;
+                            sim.log_event(SimEvent::ShipCollision {
+                                tick,
+                                ships: [s1, s2],
+                                position,
+                            });
                             sim.ship_mut(s1).handle_collision();
                             sim.ship_mut(s2).handle_collision();
                         }
@@ -179,6 +252,156 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
     }
 }
 
+/// Applies `damage` to every live ship hostile to `team` whose hull is within
+/// `radius` of `position`, then logs and draws the explosion.
+pub(crate) fn explode(
+    sim: &mut Simulation,
+    position: Vector2<f64>,
+    radius: f64,
+    team: i32,
+    mut damage: impl FnMut(&mut Simulation, ShipHandle),
+) {
+    let victims: Vec<ShipHandle> = sim
+        .ships_within(position, radius)
+        .into_iter()
+        .filter(|&handle| sim.are_hostile(sim.ship(handle).data().team, team))
+        .collect();
+    for handle in victims {
+        damage(sim, handle);
+    }
+    let tick = sim.tick();
+    sim.log_event(SimEvent::Explosion {
+        tick,
+        ship: None,
+        position,
+    });
+    sim.events.explosions.push(ExplosionEffect {
+        position,
+        radius,
+        tick,
+    });
+}
+
+/// Applies the damage and status effect of `bullet` to `ship`, which is
+/// marked destroyed if its health runs out. Returns whether it was destroyed.
+fn damage_ship(
+    sim: &mut Simulation,
+    ship: ShipHandle,
+    bullet: BulletHandle,
+    bullet_position: Vector2<f64>,
+    bullet_velocity: Vector2<f64>,
+) -> bool {
+    let dv = bullet_velocity - sim.ship(ship).velocity();
+    let energy = 0.5 * bullet::data(sim, bullet).mass as f64 * dv.magnitude_squared();
+    let damage = {
+        let data = bullet::data(sim, bullet);
+        let distance = (bullet_position - data.origin).magnitude();
+        energy
+            * DAMAGE_FACTOR
+            * data.falloff.multiplier(distance)
+            * sim
+                .ship(ship)
+                .data()
+                .resistance
+                .multiplier(data.damage_type)
+    };
+    let tick = sim.tick();
+    let dt = sim.dt();
+    sim.log_event(SimEvent::BulletHit {
+        tick,
+        bullet,
+        ship,
+        position: bullet_position,
+        damage: damage * sim.damage_multiplier,
+    });
+    for _ in 0..((damage as i32 / 10).clamp(1, 20)) {
+        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..1000.0), 0.0]);
+        let p = bullet_position + v * sim.rng.gen_range(0.0..0.1);
+        sim.events.particles.push(Particle {
+            position: p,
+            velocity: v,
+            color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
+            lifetime: (dt * 30.0) as f32,
+        });
+    }
+    let data = bullet::data(sim, bullet);
+    let (source, status_effect) = (data.source, data.status_effect);
+    let ship_destroyed = sim.ship_mut(ship).damage(damage, source);
+    if let Some(effect) = status_effect {
+        sim.ship_mut(ship)
+            .data_mut()
+            .apply_status_effect(effect.with_source(source));
+    }
+    ship_destroyed
+}
+
+/// Returns the velocity of each ship if ram damage is enabled, to be taken
+/// before the physics step resolves any impacts.
+pub(crate) fn ram_velocities(sim: &Simulation) -> BTreeMap<ShipHandle, Vector2<f64>> {
+    if sim.ram_damage <= 0.0 {
+        return BTreeMap::new();
+    }
+    sim.ships
+        .iter()
+        .map(|&handle| (handle, sim.ship(handle).velocity()))
+        .collect()
+}
+
+/// Damages both ships of each touching pair in proportion to the kinetic
+/// energy of their relative velocity before the impact, at most once per
+/// pair every [`COLLISION_DAMAGE_COOLDOWN_TICKS`].
+pub(crate) fn apply_ram_damage(
+    sim: &mut Simulation,
+    velocities: &BTreeMap<ShipHandle, Vector2<f64>>,
+) {
+    if sim.ram_damage <= 0.0 {
+        return;
+    }
+    let tick = sim.tick();
+    sim.collision_cooldowns.retain(|_, &mut until| until > tick);
+
+    for pair in touching_ship_pairs(sim) {
+        let (a, b) = pair;
+        if sim.collision_cooldowns.contains_key(&pair) {
+            continue;
+        }
+        let (Some(va), Some(vb)) = (velocities.get(&a), velocities.get(&b)) else {
+            continue;
+        };
+        let speed = (va - vb).magnitude();
+        if speed < RAM_DAMAGE_MIN_SPEED {
+            continue;
+        }
+        sim.collision_cooldowns
+            .insert(pair, tick + COLLISION_DAMAGE_COOLDOWN_TICKS);
+        let damage = sim.ram_damage * 0.5 * speed * speed;
+        sim.ship_mut(a).damage(damage, Some(b));
+        sim.ship_mut(b).damage(damage, Some(a));
+    }
+}
+
+/// Returns each pair of ships in contact once, ordered by handle.
+fn touching_ship_pairs(sim: &Simulation) -> Vec<(ShipHandle, ShipHandle)> {
+    let ship = |h| {
+        sim.colliders
+            .get(h)
+            .and_then(|collider| collider.parent())
+            .map(|body| ShipHandle(body.0))
+            .filter(|&handle| sim.ships.contains(handle))
+    };
+    let mut pairs: Vec<(ShipHandle, ShipHandle)> = sim
+        .narrow_phase
+        .contact_pairs()
+        .filter(|pair| pair.has_any_active_contact)
+        .filter_map(|pair| Some((ship(pair.collider1)?, ship(pair.collider2)?)))
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect();
+    pairs.sort();
+    pairs.dedup();
+    pairs
+}
+
 pub fn add_walls(sim: &mut Simulation) {
     let world_size = sim.world_size();
     let mut make_edge = |x: f64, y: f64, a: f64| {
@@ -201,3 +424,119 @@ pub fn add_walls(sim: &mut Simulation) {
     make_edge(world_size / 2.0, 0.0, std::f64::consts::PI / 2.0);
     make_edge(-world_size / 2.0, 0.0, 3.0 * std::f64::consts::PI / 2.0);
 }
+
+/// Adds a circular wall centered on the origin.
+///
+/// The wall is a closed polyline inscribed in the circle, so nothing inside it
+/// can get further than `radius` from the origin. Bullets outside the circle
+/// are destroyed. Should be no larger than half the world size, since the
+/// square walls from [`add_walls`] are still present.
+pub fn add_circular_boundary(sim: &mut Simulation, radius: f64) {
+    let segments = ((TAU * radius / 100.0).ceil() as u32).clamp(32, 1024);
+    let vertices: Vec<Point<Real>> = (0..segments)
+        .map(|i| {
+            let angle = TAU * i as f64 / segments as f64;
+            point![radius * angle.cos(), radius * angle.sin()]
+        })
+        .collect();
+    let indices: Vec<[u32; 2]> = (0..segments).map(|i| [i, (i + 1) % segments]).collect();
+    let rigid_body = RigidBodyBuilder::fixed().build();
+    let body_handle = sim.bodies.insert(rigid_body);
+    let collider = ColliderBuilder::polyline(vertices, Some(indices))
+        .restitution(1.0)
+        .collision_groups(wall_interaction_groups())
+        .build();
+    sim.colliders
+        .insert_with_parent(collider, body_handle, &mut sim.bodies);
+    sim.arena_radius = Some(radius);
+}
+
+/// Adds a wall along the closed polygon through `vertices`.
+///
+/// Ships bounce off it and bullets ricochet or are destroyed, the same as at
+/// the world edge. Once a scenario has any polygon walls every bullet keeps a
+/// collider, which makes bullets more expensive to simulate.
+///
+/// A wall with fewer than two vertices is ignored with a warning.
+pub fn add_polygon_wall(sim: &mut Simulation, vertices: &[Point2<f64>]) {
+    if vertices.len() < 2 {
+        log::warn!("Ignoring wall with {} vertices", vertices.len());
+        return;
+    }
+    let n = vertices.len() as u32;
+    let indices: Vec<[u32; 2]> = (0..n).map(|i| [i, (i + 1) % n]).collect();
+    let rigid_body = RigidBodyBuilder::fixed().build();
+    let body_handle = sim.bodies.insert(rigid_body);
+    let collider = ColliderBuilder::polyline(vertices.to_vec(), Some(indices))
+        .restitution(1.0)
+        .collision_groups(wall_interaction_groups())
+        .build();
+    sim.colliders
+        .insert_with_parent(collider, body_handle, &mut sim.bodies);
+    sim.polygon_walls = true;
+}
+
+#[cfg(test)]
+mod test {
+    use super::sort_collision_events;
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    use rapier2d_f64::prelude::*;
+    use test_log::test;
+
+    type Key = (bool, (u32, u32), (u32, u32));
+
+    // In either collider order, since the pipeline may report both.
+    fn keys(events: &[CollisionEvent]) -> Vec<Key> {
+        events
+            .iter()
+            .map(|event| {
+                let h1 = event.collider1().into_raw_parts();
+                let h2 = event.collider2().into_raw_parts();
+                (event.started(), h1.min(h2), h1.max(h2))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_collision_events() {
+        let handle = |i| ColliderHandle::from_raw_parts(i, 0);
+        let flags = CollisionEventFlags::empty();
+        let mut events = vec![];
+        for i in 0..10 {
+            for j in (i + 1)..10 {
+                events.push(CollisionEvent::Started(handle(i), handle(j), flags));
+            }
+        }
+        // A pair that started and stopped touching in the same tick.
+        events.push(CollisionEvent::Started(handle(20), handle(21), flags));
+        events.push(CollisionEvent::Stopped(handle(21), handle(20), flags));
+        let mut expected = events.clone();
+        sort_collision_events(&mut expected);
+
+        let mut rng = crate::rng::new_rng(0);
+        for _ in 0..20 {
+            let mut shuffled: Vec<CollisionEvent> = events
+                .iter()
+                .map(|&event| match event {
+                    CollisionEvent::Started(h1, h2, flags) if rng.gen_bool(0.5) => {
+                        CollisionEvent::Started(h2, h1, flags)
+                    }
+                    event => event,
+                })
+                .collect();
+            shuffled.shuffle(&mut rng);
+            // Events for one pair still arrive in the order they happened.
+            let started = shuffled
+                .iter()
+                .position(|event| event.started() && event.collider1().into_raw_parts().0 >= 20)
+                .unwrap();
+            let stopped = shuffled.iter().position(|event| event.stopped()).unwrap();
+            if stopped < started {
+                shuffled.swap(started, stopped);
+            }
+            sort_collision_events(&mut shuffled);
+            assert_eq!(keys(&shuffled), keys(&expected));
+        }
+    }
+}
@@ -1,5 +1,16 @@
 use nalgebra::{vector, Vector4};
 
+/// Color used to draw ships and debug lines belonging to `team`.
+pub fn team(team: i32) -> Vector4<f32> {
+    match team {
+        0 => vector![0.99, 0.98, 0.00, 1.00],
+        1 => vector![0.99, 0.00, 0.98, 1.00],
+        2 => vector![0.13, 0.50, 0.73, 1.00],
+        9 => vector![0.40, 0.40, 0.40, 1.00],
+        _ => vector![1.0, 1.0, 1.0, 1.0],
+    }
+}
+
 pub fn to_u32(c: Vector4<f32>) -> u32 {
     let convert = |x| (x * 255.0) as u32;
     convert(c.x) << 24 | convert(c.y) << 16 | convert(c.z) << 8 | convert(c.w)
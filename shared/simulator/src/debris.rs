@@ -0,0 +1,94 @@
+use crate::bullet::{self, BulletHandle};
+use crate::collision;
+use crate::simulation::Simulation;
+use nalgebra::Vector2;
+use rapier2d_f64::prelude::*;
+
+/// Destructible cover that stops bullets but not ships.
+#[derive(Clone)]
+pub struct Debris {
+    pub position: Vector2<f64>,
+    pub radius: f64,
+    pub health: f64,
+    body: RigidBodyHandle,
+    collider: ColliderHandle,
+}
+
+/// Adds a piece of debris.
+///
+/// Bullets that hit it are destroyed and chip away at its health, and it is
+/// removed once that reaches zero. Ships fly through it unaffected.
+pub fn add_debris(sim: &mut Simulation, position: Vector2<f64>, radius: f64, health: f64) {
+    let rigid_body = RigidBodyBuilder::fixed().translation(position).build();
+    let body = sim.bodies.insert(rigid_body);
+    let collider = ColliderBuilder::ball(radius)
+        .collision_groups(collision::debris_interaction_groups())
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .build();
+    let collider = sim
+        .colliders
+        .insert_with_parent(collider, body, &mut sim.bodies);
+    sim.debris.push(Debris {
+        position,
+        radius,
+        health,
+        body,
+        collider,
+    });
+}
+
+/// Applies bullet hits to debris.
+///
+/// Returns true if the event involved debris and needs no further handling.
+pub(crate) fn handle_collision_event(sim: &mut Simulation, event: &CollisionEvent) -> bool {
+    let (h1, h2) = (event.collider1(), event.collider2());
+    let Some(index) = sim
+        .debris
+        .iter()
+        .position(|debris| debris.collider == h1 || debris.collider == h2)
+    else {
+        return false;
+    };
+    if !event.started() {
+        return true;
+    }
+    let other = if sim.debris[index].collider == h1 {
+        h2
+    } else {
+        h1
+    };
+    let bullet = sim
+        .colliders
+        .get(other)
+        .and_then(|collider| collider.parent())
+        .map(|body| BulletHandle(body.0))
+        .filter(|&handle| sim.bullets.contains(handle));
+    let Some(bullet) = bullet else {
+        return true;
+    };
+    let damage = {
+        let body = bullet::body(sim, bullet);
+        let (position, velocity) = (body.position().translation.vector, *body.linvel());
+        let data = bullet::data(sim, bullet);
+        let distance = (position - data.origin).magnitude();
+        0.5 * data.mass as f64
+            * velocity.magnitude_squared()
+            * collision::DAMAGE_FACTOR
+            * sim.damage_multiplier
+            * data.falloff.multiplier(distance)
+    };
+    bullet::destroy(sim, bullet);
+    sim.debris[index].health -= damage;
+    if sim.debris[index].health <= 0.0 {
+        let debris = sim.debris.remove(index);
+        sim.bodies.remove(
+            debris.body,
+            &mut sim.island_manager,
+            &mut sim.colliders,
+            &mut sim.impulse_joints,
+            &mut sim.multibody_joints,
+            /*remove_attached_colliders=*/ true,
+        );
+    }
+    true
+}
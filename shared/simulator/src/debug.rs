@@ -1,3 +1,4 @@
+use crate::color;
 use crate::ship::ShipHandle;
 use crate::simulation::Simulation;
 use nalgebra::{vector, Point2, UnitComplex, Vector4};
@@ -32,6 +33,19 @@ pub fn emit_ship(sim: &mut Simulation, handle: ShipHandle) {
             .transform_vector(&sim.ship(handle).data().acceleration),
         color: vector![0.0, 1.0, 0.2, 1.0],
     });
+    if sim.thrust_debug {
+        // The force applied by the previous tick, which is what physics just
+        // integrated. One metre of line per m/s² of acceleration.
+        let ship = sim.ship(handle);
+        let data = ship.data();
+        if data.last_acceleration != vector![0.0, 0.0] {
+            lines.push(Line {
+                a: p,
+                b: p + data.last_acceleration,
+                color: color::team(data.team),
+            });
+        }
+    }
     for gun in sim.ship(handle).data().guns.iter() {
         if gun.min_angle == gun.max_angle {
             continue;
@@ -1,14 +1,20 @@
 pub mod bullet;
 pub mod collision;
 pub mod color;
+pub mod debris;
 pub mod debug;
 pub mod index_set;
+pub mod mine;
 pub mod model;
 pub mod radar;
 pub mod radio;
+pub mod repair;
+pub mod replay;
 pub mod rng;
+pub mod runner;
 pub mod scenario;
 pub mod ship;
+pub mod ship_grid;
 pub mod simulation;
 pub mod snapshot;
 pub mod vm;
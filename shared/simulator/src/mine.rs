@@ -0,0 +1,119 @@
+use crate::collision;
+use crate::ship::ShipHandle;
+use crate::simulation::{Particle, Simulation};
+use nalgebra::{vector, Rotation2, Vector2};
+use rand::Rng;
+use rapier2d_f64::prelude::*;
+use std::f64::consts::TAU;
+
+/// A stationary mine that detonates when an enemy ship comes close.
+#[derive(Clone)]
+pub struct Mine {
+    pub position: Vector2<f64>,
+    pub team: i32,
+    pub damage: f64,
+    pub trigger_radius: f64,
+    body: RigidBodyHandle,
+    collider: ColliderHandle,
+}
+
+/// Adds a mine owned by `team`.
+///
+/// When a ship of another team enters `trigger_radius` the mine explodes,
+/// dealing `damage` to every enemy ship within that radius, and is removed.
+/// Friendly ships pass over it safely.
+pub fn add_mine(
+    sim: &mut Simulation,
+    position: Vector2<f64>,
+    team: i32,
+    damage: f64,
+    trigger_radius: f64,
+) {
+    let rigid_body = RigidBodyBuilder::fixed().translation(position).build();
+    let body = sim.bodies.insert(rigid_body);
+    let collider = ColliderBuilder::ball(trigger_radius)
+        .sensor(true)
+        .collision_groups(collision::zone_interaction_groups())
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .build();
+    let collider = sim
+        .colliders
+        .insert_with_parent(collider, body, &mut sim.bodies);
+    sim.mines.push(Mine {
+        position,
+        team,
+        damage,
+        trigger_radius,
+        body,
+        collider,
+    });
+}
+
+/// Detonates mines entered by enemy ships.
+///
+/// Returns true if the event involved a mine and needs no further handling.
+pub(crate) fn handle_collision_event(sim: &mut Simulation, event: &CollisionEvent) -> bool {
+    let (h1, h2) = (event.collider1(), event.collider2());
+    let Some(index) = sim
+        .mines
+        .iter()
+        .position(|mine| mine.collider == h1 || mine.collider == h2)
+    else {
+        return false;
+    };
+    if !event.started() {
+        return true;
+    }
+    let other = if sim.mines[index].collider == h1 {
+        h2
+    } else {
+        h1
+    };
+    let team = sim.mines[index].team;
+    let triggered = sim
+        .colliders
+        .get(other)
+        .and_then(|collider| collider.parent())
+        .map(|body| ShipHandle(body.0))
+        .filter(|&handle| sim.ships.contains(handle))
+        .map_or(false, |handle| {
+            sim.are_hostile(sim.ship(handle).data().team, team)
+        });
+    if triggered {
+        let mine = sim.mines.remove(index);
+        detonate(sim, &mine);
+        sim.bodies.remove(
+            mine.body,
+            &mut sim.island_manager,
+            &mut sim.colliders,
+            &mut sim.impulse_joints,
+            &mut sim.multibody_joints,
+            /*remove_attached_colliders=*/ true,
+        );
+    }
+    true
+}
+
+fn detonate(sim: &mut Simulation, mine: &Mine) {
+    collision::explode(
+        sim,
+        mine.position,
+        mine.trigger_radius,
+        mine.team,
+        |sim, handle| {
+            sim.ship_mut(handle).damage(mine.damage, None);
+        },
+    );
+
+    let dt = sim.dt();
+    for _ in 0..20 {
+        let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+        let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..500.0), 0.0]);
+        sim.events.particles.push(Particle {
+            position: mine.position,
+            velocity: v,
+            color: vector![1.0, sim.rng.gen_range(0.3..0.7), 0.2, 1.0],
+            lifetime: (dt * 30.0) as f32,
+        });
+    }
+}
@@ -1,6 +1,7 @@
-use crate::ship::{self, ShipClass, ShipHandle};
+use crate::scenario::Boundary;
+use crate::ship::{self, ShipAccessor, ShipClass, ShipHandle};
 use crate::simulation::{Line, Simulation};
-use crate::{model, rng, simulation};
+use crate::{rng, simulation};
 use nalgebra::Rotation2;
 use nalgebra::{vector, Point2, Vector2};
 use oort_api::{Ability, EcmMode};
@@ -8,8 +9,8 @@ use rand::Rng;
 use rand_distr::StandardNormal;
 use rapier2d_f64::parry;
 use rapier2d_f64::prelude::*;
-use std::collections::HashMap;
-use std::f64::consts::TAU;
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::{PI, TAU};
 use std::ops::Range;
 use wide::{f32x4, CmpGt, CmpLt};
 
@@ -19,10 +20,33 @@ const JAMMER_COEFF: f64 = 1e-9; // Account for frequency hopping and pulse lengt
 const BEARING_NOISE_FACTOR: f64 = 1e1 * (TAU / 360.0);
 const DISTANCE_NOISE_FACTOR: f64 = 1e4;
 const VELOCITY_NOISE_FACTOR: f64 = 1e2;
+// Standard deviation of the bearing to a jammer (radians).
+const JAMMER_BEARING_NOISE: f64 = 2.0 * (TAU / 360.0);
+const MIN_RSSI_DBM: f64 = -130.0;
+const MAX_RSSI_DBM: f64 = -60.0;
+// Relative RSSI difference below which contacts are ordered by handle.
+const RSSI_TIE_EPSILON: f64 = 1e-9;
+const TRACK_ALPHA: f64 = 0.5;
+const TRACK_BETA: f64 = 0.2;
+// Smoothing applied to the velocity differences behind the acceleration estimate.
+const TRACK_ACCELERATION_GAIN: f64 = 0.1;
+const TRACK_CONFIDENCE_DECAY: f64 = 0.8;
+const TRACK_MAX_MISSED_TICKS: u32 = 10;
+const MAX_TRACKS: usize = oort_api::MAX_RADAR_TRACKS;
+const MAX_CONTACTS: usize = oort_api::MAX_RADAR_CONTACTS;
+/// Consecutive ticks a contact must be painted before the radar has a lock.
+pub const LOCK_PAINT_TICKS: u32 = 30;
+/// Tracks below this confidence have no [`TrackEstimate`].
+pub const TRACK_MIN_CONFIDENCE: f64 = 0.2;
+/// With radar classification enabled, contacts weaker than this multiple of
+/// the radar's minimum RSSI are reported without a class.
+pub const CLASSIFY_RSSI_FACTOR: f64 = 2.0;
 
 #[derive(Clone, Debug)]
 pub struct Radar {
     pub heading: f64,
+    pub target_heading: f64,
+    pub slew_rate: Option<f64>,
     pub width: f64,
     pub min_width: f64,
     pub max_width: f64,
@@ -32,14 +56,51 @@ pub struct Radar {
     pub rx_cross_section: f64,
     pub reliable_rssi: f64,
     pub min_rssi: f64,
+    /// Contacts weaker than `min_rssi` but at least this strong are reported
+    /// with only a bearing, see [`ScanResult::bearing_only`]. `None` disables
+    /// bearing-only contacts.
+    pub bearing_only_rssi: Option<f64>,
+    /// Scales the position and velocity noise of contacts. 1.0 is the
+    /// standard radar and 0.0 reports exact positions.
+    pub noise_factor: f64,
     pub ecm_mode: EcmMode,
     pub result: Option<ScanResult>,
+    pub closest: Option<ScanResult>,
+    pub contacts: Vec<ScanResult>,
+    pub tracks: Vec<RadarTrack>,
+    pub next_track_id: u64,
+    pub last_beam: Option<RadarBeam>,
+    /// Replace the next scan with a ranging pulse, see
+    /// [`request_ping`](Self::request_ping).
+    pub ping: bool,
+    pub ping_range: Option<f64>,
+    pub jammer_bearing: Option<f64>,
+    /// Ship that scans are restricted to, see
+    /// [`track_contact`](Self::track_contact).
+    pub tracked_contact: Option<ShipHandle>,
+    /// Energy pool that scans draw from. `None` means unlimited energy.
+    pub energy: Option<RadarEnergy>,
+}
+
+/// Energy stored for a [`Radar`].
+///
+/// Each scan, ping or tick of jamming uses `power * dt`. If there isn't
+/// enough, the radar transmits with whatever power is left, which shortens
+/// its range or weakens its jamming.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadarEnergy {
+    pub energy: f64,
+    pub max_energy: f64,
+    /// Energy recovered per second, whether or not the radar is in use.
+    pub recharge_per_second: f64,
 }
 
 impl Default for Radar {
     fn default() -> Self {
         Radar {
             heading: 0.0,
+            target_heading: 0.0,
+            slew_rate: None,
             width: TAU / 16.0,
             min_width: TAU / 360.0,
             max_width: TAU / 4.0,
@@ -49,8 +110,20 @@ impl Default for Radar {
             rx_cross_section: 10.0,
             reliable_rssi: from_dbm(-90.0),
             min_rssi: from_dbm(-100.0),
+            bearing_only_rssi: None,
+            noise_factor: 1.0,
             ecm_mode: EcmMode::None,
             result: None,
+            closest: None,
+            contacts: Vec::new(),
+            tracks: Vec::new(),
+            next_track_id: 1,
+            last_beam: None,
+            ping: false,
+            ping_range: None,
+            jammer_bearing: None,
+            tracked_contact: None,
+            energy: None,
         }
     }
 }
@@ -60,16 +133,81 @@ impl Radar {
         self.heading
     }
 
+    /// Sets the commanded heading.
+    ///
+    /// Without a slew rate the beam snaps to it immediately, otherwise it
+    /// turns towards it each tick.
     pub fn set_heading(&mut self, heading: f64) {
-        self.heading = heading.rem_euclid(TAU);
+        self.target_heading = heading.rem_euclid(TAU);
+        if self.slew_rate.is_none() {
+            self.heading = self.target_heading;
+        }
+    }
+
+    pub fn get_target_heading(&self) -> f64 {
+        self.target_heading
+    }
+
+    pub fn get_slew_rate(&self) -> Option<f64> {
+        self.slew_rate
+    }
+
+    /// Limits how fast the beam turns (in radians per second). `None` removes
+    /// the limit.
+    pub fn set_slew_rate(&mut self, slew_rate: Option<f64>) {
+        self.slew_rate = slew_rate.map(|x| x.max(0.0));
+        if self.slew_rate.is_none() {
+            self.heading = self.target_heading;
+        }
+    }
+
+    /// Returns the energy left, or `None` if the radar has no energy limit.
+    pub fn get_energy(&self) -> Option<f64> {
+        self.energy.as_ref().map(|energy| energy.energy)
+    }
+
+    /// Recharges the energy pool and pays for this tick's transmission,
+    /// returning the power actually transmitted.
+    fn draw_power(&mut self, dt: f64) -> f64 {
+        let Some(energy) = self.energy.as_mut() else {
+            return self.power;
+        };
+        energy.energy = (energy.energy + energy.recharge_per_second * dt).min(energy.max_energy);
+        let power = self.power.min(energy.energy / dt);
+        energy.energy -= power * dt;
+        power
+    }
+
+    /// Turns the beam towards the commanded heading by at most one tick's
+    /// worth of slew.
+    fn slew(&mut self, dt: f64) {
+        let Some(slew_rate) = self.slew_rate else {
+            return;
+        };
+        let max_step = slew_rate * dt;
+        let diff = (self.target_heading - self.heading + PI).rem_euclid(TAU) - PI;
+        self.heading = (self.heading + diff.clamp(-max_step, max_step)).rem_euclid(TAU);
     }
 
     pub fn get_width(&self) -> f64 {
         self.width
     }
 
+    /// Sets the beam width, clamped to `min_width..max_width`.
+    ///
+    /// A width of `TAU` or more selects omnidirectional mode, which sees
+    /// contacts at every bearing. Spreading the power over the full circle
+    /// gives it much shorter range than a focused beam.
     pub fn set_width(&mut self, width: f64) {
-        self.width = width.clamp(self.min_width, self.max_width);
+        if width >= TAU {
+            self.width = TAU;
+        } else {
+            self.width = width.clamp(self.min_width, self.max_width);
+        }
+    }
+
+    pub fn is_omnidirectional(&self) -> bool {
+        self.width >= TAU
     }
 
     pub fn get_min_distance(&self) -> f64 {
@@ -88,6 +226,33 @@ impl Radar {
         self.max_distance = dist.clamp(0.0, simulation::MAX_WORLD_SIZE * 2.0);
     }
 
+    pub fn get_min_rssi(&self) -> f64 {
+        self.min_rssi
+    }
+
+    /// Sets the detection threshold (in watts). Lower values detect weaker
+    /// contacts at the cost of more unreliable returns.
+    pub fn set_min_rssi(&mut self, min_rssi: f64) {
+        self.min_rssi = min_rssi.clamp(from_dbm(MIN_RSSI_DBM), from_dbm(MAX_RSSI_DBM));
+    }
+
+    pub fn get_bearing_only_rssi(&self) -> Option<f64> {
+        self.bearing_only_rssi
+    }
+
+    /// Sets the threshold (in watts) for bearing-only contacts, or disables
+    /// them with `None`.
+    pub fn set_bearing_only_rssi(&mut self, rssi: Option<f64>) {
+        self.bearing_only_rssi =
+            rssi.map(|rssi| rssi.clamp(from_dbm(MIN_RSSI_DBM), from_dbm(MAX_RSSI_DBM)));
+    }
+
+    /// Returns the weakest RSSI that produces any contact.
+    fn detection_rssi(&self) -> f64 {
+        self.bearing_only_rssi
+            .map_or(self.min_rssi, |rssi| rssi.min(self.min_rssi))
+    }
+
     pub fn set_ecm_mode(&mut self, mode: EcmMode) {
         self.ecm_mode = mode;
     }
@@ -95,11 +260,237 @@ impl Radar {
     pub fn scan(&self) -> Option<ScanResult> {
         self.result
     }
+
+    /// Returns the nearest contact in the beam, rather than the strongest
+    /// one returned by [`scan`](Self::scan).
+    pub fn closest_contact(&self) -> Option<ScanResult> {
+        self.closest
+    }
+
+    /// Returns every contact in the beam above the minimum RSSI, strongest
+    /// first.
+    ///
+    /// Each contact has its own noise. The strongest one is not necessarily
+    /// the one returned by [`scan`](Self::scan), which can also miss
+    /// unreliable contacts that appear here.
+    pub fn scan_all(&self) -> &[ScanResult] {
+        &self.contacts
+    }
+
+    /// Replaces the next scan with an omnidirectional ranging pulse.
+    ///
+    /// The pulse ignores the beam's heading and width and only measures the
+    /// distance to the nearest enemy, see [`ping_range`](Self::ping_range).
+    /// Its power is spread over the full circle, so it reaches less far than
+    /// a narrow beam.
+    pub fn request_ping(&mut self) {
+        self.ping = true;
+    }
+
+    /// Returns the distance to the nearest enemy found by a ranging pulse in
+    /// the most recent radar tick, or `None` if there was no pulse or it
+    /// found nothing.
+    pub fn ping_range(&self) -> Option<f64> {
+        self.ping_range
+    }
+
+    /// Returns the approximate bearing to the strongest enemy jammer in the
+    /// beam during the most recent scan, if its noise drowned out the
+    /// background.
+    ///
+    /// This is the only thing a jammer gives away: it may still hide
+    /// itself and other contacts from [`scan`](Self::scan).
+    pub fn jammer_bearing(&self) -> Option<f64> {
+        self.jammer_bearing
+    }
+
+    /// Returns the beam used by the most recent scan, after clamping and
+    /// slewing were applied.
+    pub fn last_beam(&self) -> Option<RadarBeam> {
+        self.last_beam
+    }
+
+    /// Returns the contacts seen in the last `TRACK_MAX_MISSED_TICKS` ticks.
+    pub fn tracks(&self) -> &[RadarTrack] {
+        &self.tracks
+    }
+
+    /// Returns true if the track with the given id has been painted for at
+    /// least [`LOCK_PAINT_TICKS`] consecutive ticks, up to and including the
+    /// latest scan.
+    pub fn has_lock(&self, contact_id: u64) -> bool {
+        self.tracks
+            .iter()
+            .any(|t| t.id == contact_id && t.has_lock())
+    }
+
+    /// Restricts future scans to the ship returned by the latest one.
+    ///
+    /// While tracking, [`scan`](Self::scan) only reports that ship, even if a
+    /// stronger contact is in the beam. Tracking stops once the ship is out
+    /// of the beam or below the minimum RSSI, or when the radar jams or pings
+    /// instead of scanning. Does nothing if the latest scan found nothing.
+    /// Unrelated to the lock from painting a contact, see
+    /// [`has_lock`](Self::has_lock).
+    pub fn track_contact(&mut self) {
+        self.tracked_contact = self
+            .tracks
+            .iter()
+            .find(|t| t.ticks_since_seen == 0)
+            .map(|t| t.handle);
+    }
+
+    pub fn untrack_contact(&mut self) {
+        self.tracked_contact = None;
+    }
+
+    pub fn tracked_contact(&self) -> Option<ShipHandle> {
+        self.tracked_contact
+    }
+
+    /// Returns the ship the radar currently has a lock on, if any.
+    pub fn lock_target(&self) -> Option<ShipHandle> {
+        self.tracks.iter().find(|t| t.has_lock()).map(|t| t.handle)
+    }
+
+    /// Returns the estimated position of the track with the given id, or None
+    /// once its confidence has fallen below [`TRACK_MIN_CONFIDENCE`].
+    pub fn estimate_track(&self, contact_id: u64) -> Option<TrackEstimate> {
+        self.tracks
+            .iter()
+            .find(|t| t.id == contact_id && t.confidence >= TRACK_MIN_CONFIDENCE)
+            .map(RadarTrack::estimate)
+    }
+
+    /// Advances the track file by one tick and folds in the latest contact.
+    ///
+    /// Tracks coast on their estimated velocity while unseen and are dropped
+    /// after `TRACK_MAX_MISSED_TICKS` consecutive misses.
+    fn update_tracks(&mut self, dt: f64, contact: Option<(ShipHandle, &ScanResult)>) {
+        for track in self.tracks.iter_mut() {
+            track.position += track.velocity * dt;
+            track.ticks_since_seen += 1;
+            track.time_since_seen = track.ticks_since_seen as f64 * dt;
+            track.confidence *= TRACK_CONFIDENCE_DECAY;
+        }
+
+        if let Some((handle, contact)) = contact {
+            if let Some(track) = self.tracks.iter_mut().find(|t| t.handle == handle) {
+                let elapsed = track.ticks_since_seen as f64 * dt;
+                let residual = contact.position - track.position;
+                track.position += residual * TRACK_ALPHA;
+                track.velocity += residual * (TRACK_BETA / elapsed);
+                let measured_acceleration = (contact.velocity - track.last_velocity) / elapsed;
+                track.acceleration +=
+                    (measured_acceleration - track.acceleration) * TRACK_ACCELERATION_GAIN;
+                track.last_velocity = contact.velocity;
+                track.class = contact.class.or(track.class);
+                track.confidence = 1.0;
+                // Only an unbroken run of scans counts towards a lock.
+                track.paint_ticks = if track.ticks_since_seen == 1 {
+                    track.paint_ticks + 1
+                } else {
+                    1
+                };
+                track.ticks_since_seen = 0;
+                track.time_since_seen = 0.0;
+            } else {
+                self.tracks.push(RadarTrack {
+                    id: self.next_track_id,
+                    handle,
+                    class: contact.class,
+                    position: contact.position,
+                    velocity: contact.velocity,
+                    acceleration: Vector2::zeros(),
+                    last_velocity: contact.velocity,
+                    confidence: 1.0,
+                    ticks_since_seen: 0,
+                    time_since_seen: 0.0,
+                    paint_ticks: 1,
+                });
+                self.next_track_id += 1;
+            }
+        }
+
+        self.tracks
+            .retain(|t| t.ticks_since_seen <= TRACK_MAX_MISSED_TICKS);
+        if self.tracks.len() > MAX_TRACKS {
+            self.tracks
+                .sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then(a.id.cmp(&b.id)));
+            self.tracks.truncate(MAX_TRACKS);
+            self.tracks.sort_by_key(|t| t.id);
+        }
+    }
+}
+
+/// A contact maintained across ticks by the radar's track file.
+#[derive(Copy, Clone, Debug)]
+pub struct RadarTrack {
+    /// Identifier that stays the same for as long as the track exists.
+    pub id: u64,
+    pub(crate) handle: ShipHandle,
+    /// Class from the most recent sighting that identified the contact.
+    pub class: Option<ShipClass>,
+    /// Smoothed position estimate.
+    pub position: Vector2<f64>,
+    /// Smoothed velocity estimate.
+    pub velocity: Vector2<f64>,
+    /// Acceleration estimated from the change in measured velocity between
+    /// sightings. Zero until the contact has been seen twice.
+    pub acceleration: Vector2<f64>,
+    /// Velocity measured by the latest sighting.
+    pub(crate) last_velocity: Vector2<f64>,
+    /// 1.0 when seen this tick, decaying by `TRACK_CONFIDENCE_DECAY` per missed tick.
+    pub confidence: f64,
+    pub ticks_since_seen: u32,
+    /// `ticks_since_seen` in seconds, at the timestep set with
+    /// [`Simulation::set_dt`].
+    pub(crate) time_since_seen: f64,
+    /// Consecutive ticks the contact was seen, ending at the last sighting.
+    pub paint_ticks: u32,
+}
+
+impl RadarTrack {
+    pub fn has_lock(&self) -> bool {
+        self.ticks_since_seen == 0 && self.paint_ticks >= LOCK_PAINT_TICKS
+    }
+
+    /// Dead-reckons the contact from where it was last seen, see
+    /// [`Radar::estimate_track`].
+    pub fn estimate(&self) -> TrackEstimate {
+        let t = self.time_since_seen;
+        TrackEstimate {
+            position: self.position,
+            uncertainty: 0.5 * oort_api::TRACK_MANEUVER_ACCELERATION * t * t,
+        }
+    }
+}
+
+/// Where a lost contact is expected to be.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TrackEstimate {
+    /// Last known position advanced by the last known velocity.
+    pub position: Vector2<f64>,
+    /// Distance the contact could have strayed from `position` by
+    /// maneuvering at [`oort_api::TRACK_MANEUVER_ACCELERATION`].
+    pub uncertainty: f64,
+}
+
+/// The beam a radar actually emitted during a scan.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RadarBeam {
+    pub heading: f64,
+    pub width: f64,
+    pub start_bearing: f64,
+    pub end_bearing: f64,
+    /// Approximate detection range against a cruiser-sized target.
+    pub range: f64,
 }
 
 struct RadarEmitter {
     handle: ShipHandle,
     center: Point2<f64>,
+    velocity: Vector2<f64>,
     width: f64,
     start_bearing: f64,
     bearing: f64,
@@ -107,22 +498,56 @@ struct RadarEmitter {
     min_distance: f64,
     max_distance: f64,
     square_distance_range: Range<f64>,
+    /// Square of the simulation's hard detection limit.
+    square_horizon: f64,
     power: f64,
     rx_cross_section: f64,
     reliable_rssi: f64,
     min_rssi: f64,
+    /// Returns between this and `min_rssi` give bearing-only contacts.
+    bearing_only_rssi: f64,
+    /// Returns weaker than this are unclassified.
+    classify_rssi: f64,
+    noise_factor: f64,
     team: i32,
+    /// Other teams whose ships the radar ignores.
+    allies: Vec<i32>,
     rays: [Vector2<f64>; 2],
+    omnidirectional: bool,
+}
+
+impl RadarEmitter {
+    fn is_hostile(&self, team: i32) -> bool {
+        team != self.team && !self.allies.contains(&team)
+    }
 }
 
+#[derive(Clone)]
 struct RadarReflector {
+    handle: ShipHandle,
     position: Point2<f64>,
     velocity: Vector2<f64>,
     radar_cross_section: f64,
+    heading: f64,
+    aspect: ship::RadarAspect,
+    thermal_factor: f64,
     class: ShipClass,
     jammer: Option<RadarJammer>,
+    /// Multiples of the world size this copy is shifted by in a toroidal
+    /// world, see [`tile_reflectors`].
+    wrap: (i32, i32),
+}
+
+impl RadarReflector {
+    /// Returns true if this is the copy of the reflector closest to `center`.
+    fn is_nearest_image(&self, center: Point2<f64>, world_size: f64) -> bool {
+        let offset = vector![self.wrap.0 as f64, self.wrap.1 as f64] * world_size;
+        let k = ((self.position - offset - center) / world_size).map(f64::round);
+        k.x as i32 == -self.wrap.0 && k.y as i32 == -self.wrap.1
+    }
 }
 
+#[derive(Clone)]
 struct RadarJammer {
     width: f64,
     bearing: f64,
@@ -132,11 +557,22 @@ struct RadarJammer {
 
 #[derive(Copy, Clone, Debug)]
 pub struct ScanResult {
-    pub class: ShipClass,
+    /// `None` if the return was too weak to identify the contact, see
+    /// [`CLASSIFY_RSSI_FACTOR`].
+    pub class: Option<ShipClass>,
     pub position: Vector2<f64>,
     pub velocity: Vector2<f64>,
     pub rssi: f64,
     pub snr: f64,
+    /// Rate at which the distance to the contact is decreasing (m/s).
+    pub closing_speed: f64,
+    /// Estimated acceleration from the contact's track, see
+    /// [`RadarTrack::acceleration`].
+    pub acceleration: Vector2<f64>,
+    /// The return was below the radar's minimum RSSI, so only the direction
+    /// of `position` from the radar is meaningful. It is one meter away, and
+    /// velocity and class are unknown.
+    pub bearing_only: bool,
 }
 
 struct ReflectorTeam {
@@ -153,9 +589,14 @@ fn from_dbm(x: f64) -> f64 {
     10.0_f64.powf((x - 30.0) / 10.0)
 }
 
+/// `powers` holds the power each radar transmits this tick. Radars missing
+/// from it jam at full power.
 #[inline(never)]
-fn build_reflector_team(sim: &Simulation) -> HashMap<i32, ReflectorTeam> {
-    let mut reflectors_by_team: HashMap<i32, Vec<RadarReflector>> = HashMap::new();
+fn build_reflector_team(
+    sim: &Simulation,
+    powers: &HashMap<ShipHandle, f64>,
+) -> BTreeMap<i32, ReflectorTeam> {
+    let mut reflectors_by_team: BTreeMap<i32, Vec<RadarReflector>> = BTreeMap::new();
 
     for handle in sim.ships.iter() {
         let ship = sim.ship(*handle);
@@ -163,9 +604,11 @@ fn build_reflector_team(sim: &Simulation) -> HashMap<i32, ReflectorTeam> {
 
         let mut class = ship_data.class;
         let mut radar_cross_section = ship_data.radar_cross_section;
+        let mut aspect = ship_data.radar_aspect;
         if ship.is_ability_active(Ability::Decoy) {
             class = ShipClass::Cruiser;
             radar_cross_section = ship::CRUISER_RADAR_CROSS_SECTION / 2.0;
+            aspect = Default::default();
         }
         if class == ShipClass::Planet {
             continue;
@@ -178,7 +621,7 @@ fn build_reflector_team(sim: &Simulation) -> HashMap<i32, ReflectorTeam> {
                 _ => Some(RadarJammer {
                     width: radar.width,
                     bearing: radar.heading,
-                    power: radar.power,
+                    power: powers.get(handle).copied().unwrap_or(radar.power),
                     ecm_mode: radar.ecm_mode,
                 }),
             });
@@ -186,16 +629,27 @@ fn build_reflector_team(sim: &Simulation) -> HashMap<i32, ReflectorTeam> {
             .entry(ship_data.team)
             .or_default()
             .push(RadarReflector {
+                handle: *handle,
                 position: ship.position().vector.into(),
                 velocity: ship.velocity(),
                 radar_cross_section,
+                heading: ship.heading(),
+                aspect,
+                thermal_factor: 1.0 + ship_data.thermal_gain * ship_data.thermal_signature,
                 class,
                 jammer,
+                wrap: (0, 0),
             });
     }
 
-    let mut result: HashMap<i32, ReflectorTeam> = HashMap::new();
-    for (team, reflectors) in reflectors_by_team.drain() {
+    index_reflectors(reflectors_by_team)
+}
+
+fn index_reflectors(
+    reflectors_by_team: BTreeMap<i32, Vec<RadarReflector>>,
+) -> BTreeMap<i32, ReflectorTeam> {
+    let mut result: BTreeMap<i32, ReflectorTeam> = BTreeMap::new();
+    for (team, reflectors) in reflectors_by_team.into_iter() {
         let positions: Vec<Point2<f32>> = reflectors
             .iter()
             .map(|r| r.position.cast::<f32>())
@@ -226,72 +680,151 @@ fn build_reflector_team(sim: &Simulation) -> HashMap<i32, ReflectorTeam> {
     result
 }
 
+/// Adds copies of each reflector shifted by the world size, enough that the
+/// nearest copy to any point of a toroidal world is included. Emitters skip
+/// the other copies with [`RadarReflector::is_nearest_image`]. Copies of a
+/// reflector are kept next to each other so contacts keep their order.
+fn tile_reflectors(
+    reflectors_by_team: &BTreeMap<i32, ReflectorTeam>,
+    world_size: f64,
+) -> BTreeMap<i32, ReflectorTeam> {
+    index_reflectors(
+        reflectors_by_team
+            .iter()
+            .map(|(&team, reflector_team)| {
+                let mut reflectors = vec![];
+                for reflector in reflector_team.reflectors.iter() {
+                    for wrap in (-1..=1).flat_map(|x| (-1..=1).map(move |y| (x, y))) {
+                        let position =
+                            reflector.position + vector![wrap.0 as f64, wrap.1 as f64] * world_size;
+                        if position.x.abs() <= world_size && position.y.abs() <= world_size {
+                            reflectors.push(RadarReflector {
+                                position,
+                                wrap,
+                                ..reflector.clone()
+                            });
+                        }
+                    }
+                }
+                (team, reflectors)
+            })
+            .collect(),
+    )
+}
+
 #[inline(never)]
 pub fn tick(sim: &mut Simulation) {
     let handle_snapshot: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
-    let reflectors_by_team = build_reflector_team(sim);
+    let dt = sim.dt();
+    // Pay for every radar's transmission up front, so jammers are throttled
+    // too.
+    let powers: HashMap<ShipHandle, f64> = handle_snapshot
+        .iter()
+        .filter_map(|&handle| {
+            let mut ship = sim.ship_mut(handle);
+            let radar = ship.data_mut().radar.as_mut()?;
+            Some((handle, radar.draw_power(dt)))
+        })
+        .collect();
+    let reflectors_by_team = build_reflector_team(sim, &powers);
+    let torus_size = (sim.boundary() == Boundary::Torus).then(|| sim.world_size());
+    let reflectors_by_team = match torus_size {
+        Some(world_size) => tile_reflectors(&reflectors_by_team, world_size),
+        None => reflectors_by_team,
+    };
+    let grid = sim
+        .radar_spatial_index
+        .then(|| ReflectorGrid::new(&reflectors_by_team));
     let mut candidates: Vec<(i32, usize)> = Vec::new();
-    let planets = sim
-        .ships
+    let planets = sim.find_ships(|ship| ship.data().class == ShipClass::Planet);
+    let planet_obstacles: Vec<(ShipHandle, Point2<f64>, f64)> = planets
         .iter()
-        .filter(|handle| sim.ship(**handle).data().class == ShipClass::Planet)
-        .cloned()
-        .collect::<Vec<_>>();
+        .map(|&h| {
+            (
+                h,
+                sim.ship(h).position().vector.into(),
+                sim.ship(h).radius(),
+            )
+        })
+        .collect();
+    let obstacles: Vec<(ShipHandle, Point2<f64>, f64)> = if sim.radar_occlusion {
+        sim.find_ships(|ship| matches!(ship.data().class, ShipClass::Asteroid { .. }))
+            .into_iter()
+            .map(|h| {
+                (
+                    h,
+                    sim.ship(h).position().vector.into(),
+                    sim.ship(h).radius(),
+                )
+            })
+            .collect()
+    } else {
+        vec![]
+    };
 
     for handle in handle_snapshot.iter().cloned() {
+        if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
+            radar.slew(dt);
+        }
+
         let ship = sim.ship(handle);
         let ship_data = ship.data();
 
         if let Some(radar) = ship_data.radar.as_ref() {
-            let h = radar.heading;
-            let w = radar.width;
-            assert!(w < TAU / 2.0);
-            let max_distance = compute_max_detection_range(radar, 40.0 /*cruiser*/)
-                .min(radar.max_distance)
-                .min(simulation::MAX_WORLD_SIZE);
-            let reliable_distance = compute_reliable_detection_range(radar, 10.0 /*fighter*/)
-                .min(radar.max_distance)
-                .min(simulation::MAX_WORLD_SIZE);
-
-            let start_bearing = h - 0.5 * w;
-            let end_bearing = h + 0.5 * w;
-            let ray0 = Rotation2::new(start_bearing).transform_vector(&vector![1.0, 0.0]);
-            let ray1 = Rotation2::new(end_bearing).transform_vector(&vector![1.0, 0.0]);
-            assert!(is_clockwise(ray1, ray0));
-            let rays = [ray0, ray1];
-            let mut emitter = RadarEmitter {
-                handle,
-                team: ship_data.team,
-                center: ship.position().vector.into(),
-                power: radar.power,
-                reliable_rssi: radar.reliable_rssi,
-                min_rssi: radar.min_rssi,
-                rx_cross_section: radar.rx_cross_section,
-                width: w,
-                start_bearing,
-                bearing: h,
-                end_bearing,
-                min_distance: radar.min_distance,
-                max_distance,
-                square_distance_range: radar.min_distance.powi(2)..max_distance.powi(2),
-                rays,
-            };
+            let (mut emitter, reliable_distance) = build_emitter(&ship, radar, powers[&handle]);
+            let ecm_mode = radar.ecm_mode;
+            let ping = radar.ping;
+            let tracked_contact = radar.tracked_contact;
+            sim.ship_mut(handle).radar_mut().unwrap().last_beam = Some(RadarBeam {
+                heading: emitter.bearing,
+                width: emitter.width,
+                start_bearing: emitter.start_bearing,
+                end_bearing: emitter.end_bearing,
+                range: emitter.max_distance,
+            });
 
-            if radar.ecm_mode != EcmMode::None {
+            if ecm_mode != EcmMode::None {
                 {
                     let mut ship = sim.ship_mut(emitter.handle);
                     let ship_data = ship.data_mut();
                     let radar = ship_data.radar.as_mut().unwrap();
                     radar.result = None;
+                    radar.closest = None;
+                    radar.contacts.clear();
+                    radar.ping = false;
+                    radar.ping_range = None;
+                    radar.jammer_bearing = None;
+                    radar.tracked_contact = None;
+                    radar.update_tracks(dt, None);
                 }
                 draw_emitter(sim, &emitter, reliable_distance);
                 continue;
             }
 
-            let mut rng = rng::new_rng(sim.tick());
+            if ping {
+                // Farther copies in a toroidal world don't change the minimum.
+                let range = find_ping_range(&emitter, &reflectors_by_team);
+                let mut ship = sim.ship_mut(emitter.handle);
+                let radar = ship.data_mut().radar.as_mut().unwrap();
+                radar.result = None;
+                radar.closest = None;
+                radar.contacts.clear();
+                radar.ping = false;
+                radar.ping_range = range;
+                radar.jammer_bearing = None;
+                radar.tracked_contact = None;
+                radar.update_tracks(dt, None);
+                continue;
+            }
+
+            // Noise must only depend on the seed and tick so replays reproduce it.
+            let mut rng = rng::new_rng(sim.tick() ^ sim.seed().wrapping_mul(0x9e3779b9));
 
-            let mut best_rssi = emitter.min_rssi;
+            let mut best_rssi = emitter.bearing_only_rssi;
             let mut best_reflector: Option<&RadarReflector> = None;
+            let mut closest: Option<(f64, &RadarReflector, f64)> = None;
+            let mut in_beam: Vec<(f64, &RadarReflector)> = Vec::new();
+            let mut strongest_jammer: Option<(f64, &RadarReflector)> = None;
             let mut received_noise = BACKGROUND_NOISE * 2.0f64.powf(rng.gen_range(-1.0..1.0));
             candidates.clear();
 
@@ -302,10 +835,31 @@ pub fn tick(sim: &mut Simulation) {
                 emitter.square_distance_range.end = planet_distance.powi(2);
             }
 
-            find_candidates(&emitter, &reflectors_by_team, &mut candidates);
+            // An omnidirectional beam has no wedge to intersect with the
+            // planets, so each return is checked against them instead.
+            let blocking_planets: &[_] = if emitter.omnidirectional {
+                &planet_obstacles
+            } else {
+                &[]
+            };
+            let occluded = |reflector: &RadarReflector| {
+                is_occluded(&emitter, reflector, &obstacles)
+                    || is_occluded(&emitter, reflector, blocking_planets)
+            };
+
+            if let Some(grid) = grid.as_ref() {
+                grid.find_candidates(&emitter, &reflectors_by_team, &mut candidates);
+            } else {
+                find_candidates(&emitter, &reflectors_by_team, &mut candidates);
+            }
 
             for (team, reflector_index) in candidates.iter() {
                 let reflector = &reflectors_by_team[team].reflectors[*reflector_index];
+                if torus_size.map_or(false, |world_size| {
+                    !reflector.is_nearest_image(emitter.center, world_size)
+                }) {
+                    continue;
+                }
                 if let Some(jammer) = reflector.jammer.as_ref() {
                     match jammer.ecm_mode {
                         EcmMode::None => {}
@@ -320,9 +874,22 @@ pub fn tick(sim: &mut Simulation) {
                                     &emitter.center,
                                     &reflector.position,
                                 );
-                                received_noise +=
+                                let jammer_noise =
                                     JAMMER_COEFF * jammer.power * emitter.rx_cross_section
                                         / (TAU * jammer.width * r_sq);
+                                received_noise += jammer_noise;
+                                if jammer_noise > BACKGROUND_NOISE
+                                    && (emitter.omnidirectional
+                                        || check_inside_beam_raw(
+                                            &emitter.center,
+                                            emitter.bearing,
+                                            emitter.width,
+                                            &reflector.position,
+                                        ))
+                                    && strongest_jammer.map_or(true, |(n, _)| jammer_noise > n)
+                                {
+                                    strongest_jammer = Some((jammer_noise, reflector));
+                                }
                             }
                         }
                     }
@@ -337,10 +904,24 @@ pub fn tick(sim: &mut Simulation) {
                 {
                     let rssi =
                         compute_rssi(&emitter, reflector) * 1.2f64.powf(rng.gen_range(-1.0..1.0));
-                    if rssi > best_rssi {
+                    if is_better_contact(rssi, reflector, best_rssi, best_reflector)
+                        && tracked_contact.map_or(true, |tracked| reflector.handle == tracked)
+                        && !occluded(reflector)
+                    {
                         best_reflector = Some(reflector);
                         best_rssi = rssi;
                     }
+                    let distance_sq =
+                        nalgebra::distance_squared(&emitter.center, &reflector.position);
+                    if rssi >= emitter.min_rssi
+                        && closest.map_or(true, |(d, _, _)| distance_sq < d)
+                        && !occluded(reflector)
+                    {
+                        closest = Some((distance_sq, reflector, rssi));
+                    }
+                    if rssi >= emitter.min_rssi && !occluded(reflector) {
+                        in_beam.push((rssi, reflector));
+                    }
                 }
             }
 
@@ -363,11 +944,34 @@ pub fn tick(sim: &mut Simulation) {
                 }
             }
 
-            let result = if signal_db < 3.0
-                || best_rssi < emitter.min_rssi
-                || (best_rssi < emitter.reliable_rssi
-                    && decide_unreliable_rssi(&mut rng, best_rssi, emitter.reliable_rssi))
-            {
+            let detected = !(signal_db < 3.0 || best_rssi < emitter.bearing_only_rssi);
+            let mut bearing_only = best_rssi < emitter.min_rssi;
+            let unreliable = detected
+                && !bearing_only
+                && best_rssi < emitter.reliable_rssi
+                && decide_unreliable_rssi(&mut rng, best_rssi, emitter.reliable_rssi);
+            // An unreliable return that gets dropped still gives a bearing
+            // when bearing-only contacts are enabled.
+            if unreliable && emitter.bearing_only_rssi < emitter.min_rssi {
+                bearing_only = true;
+            }
+            // Bearing-only contacts don't update tracks or hold a lock.
+            let contact_handle = best_reflector
+                .filter(|_| !bearing_only)
+                .map(|reflector| reflector.handle);
+            let result = if !detected {
+                None
+            } else if bearing_only {
+                best_reflector.map(|reflector| {
+                    make_bearing_only_result(
+                        &emitter,
+                        reflector,
+                        best_rssi_dbm,
+                        received_noise_dbm,
+                        &mut rng,
+                    )
+                })
+            } else if unreliable {
                 None
             } else {
                 best_reflector.map(|reflector| {
@@ -381,11 +985,58 @@ pub fn tick(sim: &mut Simulation) {
                 })
             };
 
+            // Drawn after the main result so it doesn't perturb its noise.
+            let closest = closest
+                .filter(|&(_, _, rssi)| into_dbm(rssi) - received_noise_dbm >= 3.0)
+                .map(|(_, reflector, rssi)| {
+                    make_scan_result(
+                        &emitter,
+                        reflector,
+                        into_dbm(rssi),
+                        received_noise_dbm,
+                        &mut rng,
+                    )
+                });
+
+            in_beam.retain(|&(rssi, _)| into_dbm(rssi) - received_noise_dbm >= 3.0);
+            in_beam.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.handle.cmp(&b.1.handle)));
+            in_beam.truncate(MAX_CONTACTS);
+            let contacts: Vec<ScanResult> = in_beam
+                .iter()
+                .map(|&(rssi, reflector)| {
+                    make_scan_result(
+                        &emitter,
+                        reflector,
+                        into_dbm(rssi),
+                        received_noise_dbm,
+                        &mut rng,
+                    )
+                })
+                .collect();
+
+            let jammer_bearing = strongest_jammer.map(|(_, reflector)| {
+                let dp = reflector.position - emitter.center;
+                dp.y.atan2(dp.x) + rng.sample::<f64, _>(StandardNormal) * JAMMER_BEARING_NOISE
+            });
+
             {
                 let mut ship = sim.ship_mut(emitter.handle);
                 let ship_data = ship.data_mut();
                 let radar = ship_data.radar.as_mut().unwrap();
                 radar.result = result;
+                radar.closest = closest;
+                radar.contacts = contacts;
+                radar.ping_range = None;
+                radar.jammer_bearing = jammer_bearing;
+                if contact_handle.is_none() {
+                    radar.tracked_contact = None;
+                }
+                radar.update_tracks(dt, contact_handle.zip(result.as_ref()));
+                if let (Some(handle), Some(result)) = (contact_handle, radar.result.as_mut()) {
+                    if let Some(track) = radar.tracks.iter().find(|t| t.handle == handle) {
+                        result.acceleration = track.acceleration;
+                    }
+                }
             }
 
             draw_emitter(sim, &emitter, reliable_distance);
@@ -396,10 +1047,223 @@ pub fn tick(sim: &mut Simulation) {
     }
 }
 
+/// Returns the distance to the nearest enemy reflector that returns an
+/// omnidirectional pulse from `emitter` above its minimum RSSI.
+fn find_ping_range(
+    emitter: &RadarEmitter,
+    reflectors_by_team: &BTreeMap<i32, ReflectorTeam>,
+) -> Option<f64> {
+    let max_distance_sq = emitter.max_distance.powi(2).min(emitter.square_horizon);
+    reflectors_by_team
+        .iter()
+        .filter(|(&team, _)| emitter.is_hostile(team))
+        .flat_map(|(_, team)| team.reflectors.iter())
+        .filter_map(|reflector| {
+            let r_sq = nalgebra::distance_squared(&emitter.center, &reflector.position);
+            if r_sq > max_distance_sq {
+                return None;
+            }
+            let rssi = emitter.power
+                * effective_cross_section(emitter, reflector)
+                * emitter.rx_cross_section
+                / (TAU * TAU * r_sq * r_sq);
+            (rssi >= emitter.min_rssi).then(|| r_sq.sqrt())
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// `power` is what the radar transmits this tick, which is less than
+/// `radar.power` when its energy runs low.
+fn build_emitter(ship: &ShipAccessor, radar: &Radar, power: f64) -> (RadarEmitter, f64) {
+    let h = radar.heading;
+    let w = radar.width;
+    let omnidirectional = radar.is_omnidirectional();
+    assert!(omnidirectional || w < TAU / 2.0);
+    let horizon = ship.simulation.max_radar_range().unwrap_or(f64::INFINITY);
+    let max_distance = compute_max_detection_range(radar, power, 40.0 /*cruiser*/)
+        .min(radar.max_distance)
+        .min(simulation::MAX_WORLD_SIZE)
+        .min(horizon);
+    let reliable_distance = compute_reliable_detection_range(radar, power, 10.0 /*fighter*/)
+        .min(radar.max_distance)
+        .min(simulation::MAX_WORLD_SIZE)
+        .min(horizon);
+
+    let start_bearing = h - 0.5 * w;
+    let end_bearing = h + 0.5 * w;
+    let ray0 = Rotation2::new(start_bearing).transform_vector(&vector![1.0, 0.0]);
+    let ray1 = Rotation2::new(end_bearing).transform_vector(&vector![1.0, 0.0]);
+    assert!(omnidirectional || is_clockwise(ray1, ray0));
+    let rays = [ray0, ray1];
+    let emitter = RadarEmitter {
+        handle: ship.handle,
+        team: ship.data().team,
+        allies: ship.simulation.allies(ship.data().team),
+        center: ship.position().vector.into(),
+        velocity: ship.velocity(),
+        power,
+        reliable_rssi: radar.reliable_rssi,
+        min_rssi: radar.min_rssi,
+        bearing_only_rssi: radar.detection_rssi(),
+        classify_rssi: if ship.simulation.radar_classification {
+            radar.min_rssi * CLASSIFY_RSSI_FACTOR
+        } else {
+            0.0
+        },
+        rx_cross_section: radar.rx_cross_section,
+        noise_factor: radar.noise_factor,
+        width: w,
+        start_bearing,
+        bearing: h,
+        end_bearing,
+        min_distance: radar.min_distance,
+        max_distance,
+        square_distance_range: radar.min_distance.powi(2)..max_distance.powi(2),
+        square_horizon: horizon * horizon,
+        rays,
+        omnidirectional,
+    };
+    (emitter, reliable_distance)
+}
+
+/// Uniform grid over reflector positions, rebuilt each tick.
+///
+/// Used to skip reflectors that are outside the bounding box of a radar beam.
+/// Jammers are always checked since they add noise regardless of distance.
+struct ReflectorGrid {
+    cells: HashMap<(i32, i32), Vec<(i32, usize)>>,
+    jammers: Vec<(i32, usize)>,
+    team_order: HashMap<i32, usize>,
+}
+
+impl ReflectorGrid {
+    const CELL_SIZE: f64 = 5000.0;
+    const MARGIN: f64 = 100.0;
+
+    fn to_cell(p: Point2<f64>) -> (i32, i32) {
+        (
+            (p.x / Self::CELL_SIZE).floor() as i32,
+            (p.y / Self::CELL_SIZE).floor() as i32,
+        )
+    }
+
+    #[inline(never)]
+    fn new(reflectors_by_team: &BTreeMap<i32, ReflectorTeam>) -> Self {
+        let mut cells: HashMap<(i32, i32), Vec<(i32, usize)>> = HashMap::new();
+        let mut jammers = vec![];
+        let mut team_order = HashMap::new();
+        for (order, (&team, reflector_team)) in reflectors_by_team.iter().enumerate() {
+            team_order.insert(team, order);
+            for (i, reflector) in reflector_team.reflectors.iter().enumerate() {
+                if reflector.jammer.is_some() {
+                    jammers.push((team, i));
+                } else {
+                    cells
+                        .entry(Self::to_cell(reflector.position))
+                        .or_default()
+                        .push((team, i));
+                }
+            }
+        }
+        Self {
+            cells,
+            jammers,
+            team_order,
+        }
+    }
+
+    /// Returns the same candidates as `find_candidates` that are either within
+    /// range of the emitter or jamming, in the same order.
+    #[inline(never)]
+    fn find_candidates(
+        &self,
+        emitter: &RadarEmitter,
+        reflectors_by_team: &BTreeMap<i32, ReflectorTeam>,
+        candidates: &mut Vec<(i32, usize)>,
+    ) {
+        let rays = [emitter.rays[0].cast::<f32>(), emitter.rays[1].cast::<f32>()];
+        let emitter_position = emitter.center.cast::<f32>();
+        let mut check = |reflectors: &[(i32, usize)]| {
+            for &(team, index) in reflectors {
+                if !emitter.is_hostile(team) {
+                    continue;
+                }
+                let p = reflectors_by_team[&team].reflectors[index]
+                    .position
+                    .cast::<f32>();
+                // Same arithmetic as the SIMD version in find_candidates.
+                let dx = p.x - emitter_position.x;
+                let dy = p.y - emitter_position.y;
+                let c0 = -rays[0].x * dy + rays[0].y * dx;
+                let c1 = -rays[1].x * dy + rays[1].y * dx;
+                if emitter.omnidirectional || (c0 < 0.0 && c1 > 0.0) {
+                    candidates.push((team, index));
+                }
+            }
+        };
+
+        check(&self.jammers);
+
+        let (mins, maxs) = beam_aabb(emitter);
+        let (x0, y0) = Self::to_cell(mins);
+        let (x1, y1) = Self::to_cell(maxs);
+        let num_cells = (x1 - x0 + 1) as i64 * (y1 - y0 + 1) as i64;
+        if num_cells > self.cells.len() as i64 {
+            for (&(x, y), reflectors) in self.cells.iter() {
+                if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) {
+                    check(reflectors);
+                }
+            }
+        } else {
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    if let Some(reflectors) = self.cells.get(&(x, y)) {
+                        check(reflectors);
+                    }
+                }
+            }
+        }
+
+        candidates.sort_by_key(|(team, index)| (self.team_order[team], *index));
+    }
+}
+
+fn beam_aabb(emitter: &RadarEmitter) -> (Point2<f64>, Point2<f64>) {
+    let r = emitter.square_distance_range.end.sqrt();
+    let margin = vector![ReflectorGrid::MARGIN, ReflectorGrid::MARGIN];
+    if emitter.omnidirectional {
+        let extent = vector![r, r] + margin;
+        return (
+            (emitter.center.coords - extent).into(),
+            (emitter.center.coords + extent).into(),
+        );
+    }
+    let mut mins = emitter.center.coords;
+    let mut maxs = emitter.center.coords;
+    let mut add = |dir: Vector2<f64>| {
+        let p = emitter.center.coords + dir * r;
+        mins = mins.inf(&p);
+        maxs = maxs.sup(&p);
+    };
+    add(emitter.rays[0]);
+    add(emitter.rays[1]);
+    for axis in [
+        vector![1.0, 0.0],
+        vector![0.0, 1.0],
+        vector![-1.0, 0.0],
+        vector![0.0, -1.0],
+    ] {
+        if !is_clockwise(emitter.rays[0], axis) && is_clockwise(emitter.rays[1], axis) {
+            add(axis);
+        }
+    }
+    ((mins - margin).into(), (maxs + margin).into())
+}
+
 #[inline(never)]
 fn find_candidates(
     emitter: &RadarEmitter,
-    reflectors_by_team: &HashMap<i32, ReflectorTeam>,
+    reflectors_by_team: &BTreeMap<i32, ReflectorTeam>,
     candidates: &mut Vec<(i32, usize)>,
 ) {
     let rays = [emitter.rays[0].cast::<f32>(), emitter.rays[1].cast::<f32>()];
@@ -413,11 +1277,15 @@ fn find_candidates(
     let wry1 = f32x4::splat(rays[1].y);
 
     for (&team, reflector_team) in reflectors_by_team.iter() {
-        if emitter.team == team {
+        if !emitter.is_hostile(team) {
             continue;
         }
 
         let n = reflector_team.reflectors.len();
+        if emitter.omnidirectional {
+            candidates.extend((0..n).map(|i| (team, i)));
+            continue;
+        }
         for (i, (&wx, &wy)) in reflector_team.xs.iter().zip(&reflector_team.ys).enumerate() {
             let wdx = wx - wex;
             let wdy = wy - wey;
@@ -450,20 +1318,8 @@ fn make_scan_result(
     rng: &mut impl Rng,
 ) -> ScanResult {
     let signal_db = rssi_dbm - noise_dbm;
-    let error_factor = 10.0f64.powf(-signal_db / 10.0);
-    let dp = reflector.position - emitter.center;
-    let beam_rot = Rotation2::new(emitter.bearing);
-    let reflector_rot = Rotation2::rotation_between(&Vector2::x(), &dp);
-    let mut noisy_bearing: f64 = reflector_rot.angle()
-        + rng.sample::<f64, _>(StandardNormal) * (BEARING_NOISE_FACTOR * error_factor);
-    {
-        let angle_to = Rotation2::new(noisy_bearing).angle_to(&beam_rot);
-        if angle_to > emitter.width * 0.5 {
-            noisy_bearing = emitter.bearing - emitter.width * 0.5;
-        } else if angle_to < -emitter.width * 0.5 {
-            noisy_bearing = emitter.bearing + emitter.width * 0.5;
-        }
-    }
+    let error_factor = 10.0f64.powf(-signal_db / 10.0) * emitter.noise_factor;
+    let noisy_bearing = compute_noisy_bearing(emitter, reflector, error_factor, rng);
 
     let mut distance = (reflector.position - emitter.center).magnitude();
     distance += rng.sample::<f64, _>(StandardNormal) * (DISTANCE_NOISE_FACTOR * error_factor);
@@ -474,13 +1330,93 @@ fn make_scan_result(
     let velocity = reflector.velocity
         + vector![rng.sample(StandardNormal), rng.sample(StandardNormal)]
             * (VELOCITY_NOISE_FACTOR * error_factor);
+    let closing_speed = compute_closing_speed(emitter, position, velocity);
 
     ScanResult {
-        class: reflector.class,
+        class: (from_dbm(rssi_dbm) >= emitter.classify_rssi).then_some(reflector.class),
         position,
         velocity,
         rssi: rssi_dbm,
         snr: signal_db,
+        closing_speed,
+        acceleration: Vector2::zeros(),
+        bearing_only: false,
+    }
+}
+
+fn make_bearing_only_result(
+    emitter: &RadarEmitter,
+    reflector: &RadarReflector,
+    rssi_dbm: f64,
+    noise_dbm: f64,
+    rng: &mut impl Rng,
+) -> ScanResult {
+    let signal_db = rssi_dbm - noise_dbm;
+    let error_factor = 10.0f64.powf(-signal_db / 10.0) * emitter.noise_factor;
+    let noisy_bearing = compute_noisy_bearing(emitter, reflector, error_factor, rng);
+    ScanResult {
+        class: None,
+        position: emitter.center.coords
+            + Rotation2::new(noisy_bearing).transform_vector(&vector![1.0, 0.0]),
+        velocity: Vector2::zeros(),
+        rssi: rssi_dbm,
+        snr: signal_db,
+        closing_speed: 0.0,
+        acceleration: Vector2::zeros(),
+        bearing_only: true,
+    }
+}
+
+/// Returns the bearing to `reflector` with noise, clamped to the beam.
+fn compute_noisy_bearing(
+    emitter: &RadarEmitter,
+    reflector: &RadarReflector,
+    error_factor: f64,
+    rng: &mut impl Rng,
+) -> f64 {
+    let dp = reflector.position - emitter.center;
+    let beam_rot = Rotation2::new(emitter.bearing);
+    let reflector_rot = Rotation2::rotation_between(&Vector2::x(), &dp);
+    let noisy_bearing: f64 = reflector_rot.angle()
+        + rng.sample::<f64, _>(StandardNormal) * (BEARING_NOISE_FACTOR * error_factor);
+    if !emitter.omnidirectional {
+        let angle_to = Rotation2::new(noisy_bearing).angle_to(&beam_rot);
+        if angle_to > emitter.width * 0.5 {
+            return emitter.bearing - emitter.width * 0.5;
+        } else if angle_to < -emitter.width * 0.5 {
+            return emitter.bearing + emitter.width * 0.5;
+        }
+    }
+    noisy_bearing
+}
+
+fn compute_closing_speed(
+    emitter: &RadarEmitter,
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+) -> f64 {
+    let dp = position - emitter.center.coords;
+    let dv = velocity - emitter.velocity;
+    if dp.magnitude_squared() == 0.0 {
+        return 0.0;
+    }
+    -dv.dot(&dp.normalize())
+}
+
+/// Compares a candidate against the current best contact. RSSIs within
+/// `RSSI_TIE_EPSILON` of each other are ordered by ship handle so that
+/// symmetric engagements resolve the same way on every run.
+fn is_better_contact(
+    rssi: f64,
+    reflector: &RadarReflector,
+    best_rssi: f64,
+    best_reflector: Option<&RadarReflector>,
+) -> bool {
+    match best_reflector {
+        Some(best) if (rssi - best_rssi).abs() <= RSSI_TIE_EPSILON * best_rssi => {
+            reflector.handle < best.handle
+        }
+        _ => rssi > best_rssi,
     }
 }
 
@@ -511,18 +1447,53 @@ fn check_inside_beam_raw(
 
 fn compute_rssi(emitter: &RadarEmitter, reflector: &RadarReflector) -> f64 {
     let r_sq = nalgebra::distance_squared(&emitter.center, &reflector.position);
-    emitter.power * reflector.radar_cross_section * emitter.rx_cross_section
+    if r_sq > emitter.square_horizon {
+        return 0.0;
+    }
+    emitter.power * effective_cross_section(emitter, reflector) * emitter.rx_cross_section
         / (TAU * emitter.width * r_sq * r_sq)
 }
 
-fn compute_max_detection_range(radar: &Radar, target_cross_section: f64) -> f64 {
-    (radar.power * target_cross_section * radar.rx_cross_section
-        / (TAU * radar.width * radar.min_rssi))
+fn effective_cross_section(emitter: &RadarEmitter, reflector: &RadarReflector) -> f64 {
+    let aspect = &reflector.aspect;
+    let radar_cross_section = reflector.radar_cross_section * reflector.thermal_factor;
+    if aspect.is_uniform() {
+        return radar_cross_section * aspect.head_on;
+    }
+    let dp = reflector.position - emitter.center;
+    radar_cross_section * aspect.factor(dp.y.atan2(dp.x) - reflector.heading)
+}
+
+/// Returns true if an obstacle lies on the line between the emitter and
+/// the reflector.
+fn is_occluded(
+    emitter: &RadarEmitter,
+    reflector: &RadarReflector,
+    obstacles: &[(ShipHandle, Point2<f64>, f64)],
+) -> bool {
+    let dp = reflector.position - emitter.center;
+    let length_sq = dp.norm_squared();
+    if length_sq == 0.0 {
+        return false;
+    }
+    obstacles.iter().any(|&(handle, position, radius)| {
+        if handle == reflector.handle || handle == emitter.handle {
+            return false;
+        }
+        let t = ((position - emitter.center).dot(&dp) / length_sq).clamp(0.0, 1.0);
+        let closest = emitter.center + dp * t;
+        nalgebra::distance_squared(&closest, &position) < radius * radius
+    })
+}
+
+fn compute_max_detection_range(radar: &Radar, power: f64, target_cross_section: f64) -> f64 {
+    let min_rssi = radar.detection_rssi();
+    (power * target_cross_section * radar.rx_cross_section / (TAU * radar.width * min_rssi))
         .powf(0.25)
 }
 
-fn compute_reliable_detection_range(radar: &Radar, target_cross_section: f64) -> f64 {
-    (radar.power * target_cross_section * radar.rx_cross_section
+fn compute_reliable_detection_range(radar: &Radar, power: f64, target_cross_section: f64) -> f64 {
+    (power * target_cross_section * radar.rx_cross_section
         / (TAU * radar.width * radar.reliable_rssi))
         .powf(0.25)
 }
@@ -532,7 +1503,7 @@ fn check_planet_contact(
     emitter: &RadarEmitter,
     planets: &[ShipHandle],
 ) -> Option<Point2<f64>> {
-    if planets.is_empty() {
+    if planets.is_empty() || emitter.omnidirectional {
         return None;
     }
 
@@ -543,7 +1514,7 @@ fn check_planet_contact(
     planets
         .iter()
         .filter_map(|handle| {
-            let radius = model::radius(sim.ship(*handle).data().class) as f64;
+            let radius = sim.ship(*handle).radius();
             let planet_shape = parry::shape::Ball::new(radius);
             let planet_isometry = *sim.ship(*handle).body().position();
 
@@ -562,6 +1533,9 @@ fn check_planet_contact(
 }
 
 fn draw_emitter(sim: &mut Simulation, emitter: &RadarEmitter, reliable_distance: f64) {
+    if !sim.is_debug_ship(emitter.handle) {
+        return;
+    }
     let color = vector![0.2, 0.66, 0.97, 1.0];
     let mut lines = vec![];
     lines.reserve(48);
@@ -585,6 +1559,10 @@ fn draw_emitter(sim: &mut Simulation, emitter: &RadarEmitter, reliable_distance:
     };
     draw_arc(emitter.min_distance);
     draw_arc(reliable_distance);
+    if emitter.omnidirectional {
+        sim.emit_debug_lines(emitter.handle, lines);
+        return;
+    }
     lines.push(Line {
         a: center,
         b: center
@@ -607,6 +1585,9 @@ fn draw_emitter(sim: &mut Simulation, emitter: &RadarEmitter, reliable_distance:
 }
 
 fn draw_contact(sim: &mut Simulation, emitter_handle: ShipHandle, contact: &ScanResult) {
+    if !sim.is_debug_ship(emitter_handle) {
+        return;
+    }
     let color = vector![0.9, 0.9, 0.9, 1.0];
     let w = 10.0;
     let center: Point2<f64> = contact.position.into();
@@ -641,13 +1622,20 @@ fn draw_contact(sim: &mut Simulation, emitter_handle: ShipHandle, contact: &Scan
 
 #[cfg(test)]
 mod test {
+    use super::{
+        build_emitter, build_reflector_team, compute_max_detection_range, compute_rssi,
+        find_candidates, from_dbm, is_better_contact, RadarEnergy, ReflectorGrid, LOCK_PAINT_TICKS,
+        TRACK_CONFIDENCE_DECAY, TRACK_MAX_MISSED_TICKS,
+    };
     use crate::ship;
     use crate::ship::ShipClass;
     use crate::simulation::Code;
     use crate::simulation::Simulation;
+    use crate::simulation::PHYSICS_TICK_LENGTH;
     use nalgebra::{vector, UnitComplex};
     use oort_api::EcmMode;
     use rand::Rng;
+    use std::collections::HashMap;
     use std::f64::consts::{PI, TAU};
     use test_log::test;
 
@@ -842,6 +1830,38 @@ mod test {
         assert!(!check_detection(70e3));
     }
 
+    #[test]
+    fn test_jammer_bearing() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![70e3, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().heading = 0.0;
+        sim.ship_mut(ship0).radar_mut().unwrap().width = TAU / 360.0;
+        sim.step();
+        assert_eq!(sim.ship(ship0).radar().unwrap().jammer_bearing(), None);
+
+        sim.ship_mut(ship1).radar_mut().unwrap().heading = PI;
+        sim.ship_mut(ship1).radar_mut().unwrap().width = TAU / 360.0;
+        sim.ship_mut(ship1).radar_mut().unwrap().ecm_mode = EcmMode::Noise;
+        for _ in 0..10 {
+            sim.step();
+            let bearing = sim.ship(ship0).radar().unwrap().jammer_bearing().unwrap();
+            assert!(bearing.abs() < 0.2, "{bearing}");
+        }
+    }
+
     #[test]
     fn test_random() {
         let mut rng = crate::rng::new_rng(1);
@@ -873,4 +1893,1176 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_closing_speed() {
+        let check = |target_velocity| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            ship::create(
+                &mut sim,
+                vector![1000.0, 0.0],
+                target_velocity,
+                0.0,
+                ship::target(1),
+            );
+            sim.step();
+            sim.ship(ship0)
+                .radar()
+                .unwrap()
+                .result
+                .unwrap()
+                .closing_speed
+        };
+
+        approx::assert_abs_diff_eq!(check(vector![-100.0, 0.0]), 100.0, epsilon = 5.0);
+        approx::assert_abs_diff_eq!(check(vector![100.0, 0.0]), -100.0, epsilon = 5.0);
+        approx::assert_abs_diff_eq!(check(vector![0.0, 100.0]), 0.0, epsilon = 5.0);
+    }
+
+    #[test]
+    fn test_grid_candidates() {
+        let mut rng = crate::rng::new_rng(2);
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        for i in 0..200 {
+            let p = vector![rng.gen_range(-50e3..50e3), rng.gen_range(-50e3..50e3)];
+            let handle = ship::create(&mut sim, p, vector![0.0, 0.0], 0.0, ship::fighter(i % 2));
+            let mut ship = sim.ship_mut(handle);
+            let radar = ship.radar_mut().unwrap();
+            radar.heading = rng.gen_range(0.0..TAU);
+            radar.width = rng.gen_range((TAU / 720.0)..(TAU / 4.0));
+            if i % 17 == 0 {
+                radar.ecm_mode = EcmMode::Noise;
+            }
+        }
+
+        let reflectors_by_team = build_reflector_team(&sim, &HashMap::new());
+        let grid = ReflectorGrid::new(&reflectors_by_team);
+        let mut total = 0;
+        for &handle in sim.ships.iter() {
+            let ship = sim.ship(handle);
+            let radar = ship.radar().unwrap();
+            let (emitter, _) = build_emitter(&ship, radar, radar.power);
+            // Candidates that are neither jamming nor in range don't affect the scan.
+            let relevant = |&(team, index): &(i32, usize)| {
+                let reflector = &reflectors_by_team[&team].reflectors[index];
+                reflector.jammer.is_some()
+                    || emitter
+                        .square_distance_range
+                        .contains(&nalgebra::distance_squared(
+                            &emitter.center,
+                            &reflector.position,
+                        ))
+            };
+            let mut expected = vec![];
+            find_candidates(&emitter, &reflectors_by_team, &mut expected);
+            expected.retain(relevant);
+            let mut got = vec![];
+            grid.find_candidates(&emitter, &reflectors_by_team, &mut got);
+            got.retain(relevant);
+            assert_eq!(got, expected);
+            total += expected.len();
+        }
+        assert!(total > 0);
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        for y in [100.0, -100.0] {
+            ship::create(
+                &mut sim,
+                vector![1000.0, y],
+                vector![0.0, 0.0],
+                0.0,
+                ship::target(1),
+            );
+        }
+        let reflectors_by_team = build_reflector_team(&sim, &HashMap::new());
+        let reflectors = &reflectors_by_team[&1].reflectors;
+        let (a, b) = (&reflectors[0], &reflectors[1]);
+        assert!(a.handle < b.handle);
+        assert!(is_better_contact(1.0, a, 1.0, Some(b)));
+        assert!(!is_better_contact(1.0, b, 1.0, Some(a)));
+        assert!(is_better_contact(1.1, b, 1.0, Some(a)));
+    }
+
+    #[test]
+    fn test_symmetric_contacts_reproducible() {
+        let run = || {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            for y in [100.0, -100.0] {
+                ship::create(
+                    &mut sim,
+                    vector![1000.0, y],
+                    vector![0.0, 0.0],
+                    0.0,
+                    ship::target(1),
+                );
+            }
+            sim.ship_mut(ship0).radar_mut().unwrap().width = TAU / 8.0;
+            (0..10)
+                .map(|_| {
+                    sim.step();
+                    sim.ship(ship0).radar().unwrap().result.unwrap().position.y > 0.0
+                })
+                .collect::<Vec<_>>()
+        };
+        let expected = run();
+        for _ in 0..5 {
+            assert_eq!(run(), expected);
+        }
+    }
+
+    #[test]
+    fn test_min_rssi() {
+        let check = |min_rssi_dbm: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            // RSSI of the target is roughly -85 dBm at this range.
+            let range = {
+                let mut radar = sim.ship(ship0).radar().unwrap().clone();
+                radar.set_min_rssi(from_dbm(-85.0));
+                compute_max_detection_range(
+                    &radar,
+                    radar.power,
+                    ship::target(1).radar_cross_section,
+                ) * 0.95
+            };
+            ship::create(
+                &mut sim,
+                vector![range, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::target(1),
+            );
+            sim.ship_mut(ship0)
+                .radar_mut()
+                .unwrap()
+                .set_min_rssi(from_dbm(min_rssi_dbm));
+            (0..10)
+                .filter(|_| {
+                    sim.step();
+                    sim.ship(ship0).radar().unwrap().result.is_some()
+                })
+                .count()
+        };
+
+        assert_eq!(check(-100.0), 10);
+        assert_eq!(check(-80.0), 0);
+    }
+
+    #[test]
+    fn test_lock() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        let radar = |sim: &Simulation| sim.ship(ship0).radar().unwrap().clone();
+
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        for _ in 0..(LOCK_PAINT_TICKS - 1) {
+            sim.step();
+        }
+        let id = radar(&sim).tracks()[0].id;
+        assert!(!radar(&sim).has_lock(id));
+        sim.step();
+        assert!(radar(&sim).has_lock(id));
+
+        // Breaking the beam loses the lock immediately.
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(PI);
+        sim.step();
+        assert!(!radar(&sim).has_lock(id));
+
+        // Reacquiring restarts the count.
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.step();
+        assert_eq!(radar(&sim).tracks()[0].id, id);
+        assert_eq!(radar(&sim).tracks()[0].paint_ticks, 1);
+        assert!(!radar(&sim).has_lock(id));
+    }
+
+    #[test]
+    fn test_track_contact() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        let far = ship::create(
+            &mut sim,
+            vector![2000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        let scan_x = |sim: &Simulation| sim.ship(ship0).radar().unwrap().scan().unwrap().position.x;
+
+        // Lock on to the far target while the near one is out of range.
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.ship_mut(ship0)
+            .radar_mut()
+            .unwrap()
+            .set_min_distance(1500.0);
+        sim.step();
+        sim.ship_mut(ship0).radar_mut().unwrap().track_contact();
+        assert_eq!(
+            sim.ship(ship0).radar().unwrap().tracked_contact(),
+            Some(far)
+        );
+
+        // The stronger near target is ignored while tracking.
+        sim.ship_mut(ship0)
+            .radar_mut()
+            .unwrap()
+            .set_min_distance(0.0);
+        sim.step();
+        assert!((scan_x(&sim) - 2000.0).abs() < 100.0, "{}", scan_x(&sim));
+
+        // Losing the tracked target stops tracking.
+        sim.ship_mut(far)
+            .body()
+            .set_translation(vector![0.0, 2000.0], true);
+        sim.step();
+        assert_eq!(sim.ship(ship0).radar().unwrap().tracked_contact(), None);
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_none());
+        sim.step();
+        assert!((scan_x(&sim) - 1000.0).abs() < 100.0, "{}", scan_x(&sim));
+    }
+
+    #[test]
+    fn test_tracks() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 100.0],
+            0.0,
+            ship::target(1),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        for _ in 0..10 {
+            sim.step();
+        }
+        let tracks = sim.ship(ship0).radar().unwrap().tracks().to_vec();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].confidence, 1.0);
+        assert_eq!(tracks[0].ticks_since_seen, 0);
+        let id = tracks[0].id;
+
+        // Look away briefly, the track coasts with decayed confidence.
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(PI);
+        for _ in 0..3 {
+            sim.step();
+        }
+        let target_position = sim.ship(ship1).position();
+        let tracks = sim.ship(ship0).radar().unwrap().tracks().to_vec();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, id);
+        assert_eq!(tracks[0].ticks_since_seen, 3);
+        assert!((tracks[0].confidence - TRACK_CONFIDENCE_DECAY.powi(3)).abs() < 1e-9);
+        assert!((tracks[0].position - target_position.vector).magnitude() < 50.0);
+
+        // Looking back reacquires the same track.
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.step();
+        let tracks = sim.ship(ship0).radar().unwrap().tracks().to_vec();
+        assert_eq!(tracks.len(), 1);
+        assert_eq!(tracks[0].id, id);
+        assert_eq!(tracks[0].confidence, 1.0);
+
+        // The track is dropped after too many missed ticks.
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(PI);
+        for _ in 0..TRACK_MAX_MISSED_TICKS {
+            sim.step();
+        }
+        assert_eq!(sim.ship(ship0).radar().unwrap().tracks().len(), 1);
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().tracks().is_empty());
+    }
+
+    #[test]
+    fn test_track_estimate() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 100.0],
+            0.0,
+            ship::target(1),
+        );
+        let radar = |sim: &Simulation| sim.ship(ship0).radar().unwrap().clone();
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        for _ in 0..10 {
+            sim.step();
+        }
+        let last_seen = radar(&sim).tracks()[0];
+        let estimate = radar(&sim).estimate_track(last_seen.id).unwrap();
+        assert_eq!(estimate.position, last_seen.position);
+        assert_eq!(estimate.uncertainty, 0.0);
+
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(PI);
+        let mut uncertainty = 0.0;
+        for ticks in 1..=7 {
+            sim.step();
+            let estimate = radar(&sim).estimate_track(last_seen.id).unwrap();
+            let expected =
+                last_seen.position + last_seen.velocity * ticks as f64 * PHYSICS_TICK_LENGTH;
+            assert!((estimate.position - expected).magnitude() < 1e-6);
+            assert!(estimate.uncertainty > uncertainty);
+            uncertainty = estimate.uncertainty;
+        }
+
+        // Still tracked, but not confident enough for an estimate.
+        sim.step();
+        assert_eq!(radar(&sim).tracks().len(), 1);
+        assert!(radar(&sim).estimate_track(last_seen.id).is_none());
+    }
+
+    #[test]
+    fn test_track_acceleration() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            PI / 2.0,
+            ship::fighter(1),
+        );
+        let acceleration = vector![0.0, 20.0];
+        let step = |sim: &mut Simulation| {
+            sim.ship_mut(ship1).accelerate(vector![20.0, 0.0]);
+            sim.step();
+            sim.ship(ship0).radar().unwrap().scan().unwrap()
+        };
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        assert_eq!(step(&mut sim).acceleration, vector![0.0, 0.0]);
+
+        let mut contact = step(&mut sim);
+        assert_ne!(contact.acceleration, vector![0.0, 0.0]);
+        for _ in 0..120 {
+            contact = step(&mut sim);
+        }
+        assert!(
+            (contact.acceleration - acceleration).magnitude() < 2.0,
+            "{}",
+            contact.acceleration
+        );
+        let track = sim.ship(ship0).radar().unwrap().tracks()[0];
+        assert_eq!(track.acceleration, contact.acceleration);
+    }
+
+    #[test]
+    fn test_debug_ship() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        let beam_color = vector![0.2, 0.66, 0.97, 1.0];
+        let beam_owners = |sim: &Simulation| -> Vec<u64> {
+            let mut owners: Vec<u64> = sim
+                .events()
+                .debug_lines
+                .iter()
+                .filter(|(_, lines)| lines.iter().any(|line| line.color == beam_color))
+                .map(|&(owner, _)| owner)
+                .collect();
+            owners.dedup();
+            owners
+        };
+
+        sim.step();
+        assert_eq!(beam_owners(&sim), vec![u64::from(ship0), u64::from(ship1)]);
+
+        sim.set_debug_ship(Some(ship1));
+        sim.step();
+        assert_eq!(beam_owners(&sim), vec![u64::from(ship1)]);
+        assert!(sim
+            .events()
+            .debug_lines
+            .iter()
+            .all(|&(owner, _)| owner == u64::from(ship1)));
+
+        sim.set_debug_ship(None);
+        sim.step();
+        assert_eq!(beam_owners(&sim).len(), 2);
+    }
+
+    #[test]
+    fn test_omnidirectional() {
+        let focused_range = {
+            let mut radar = ship::fighter(0).radar.unwrap();
+            radar.set_width(TAU / 64.0);
+            compute_max_detection_range(&radar, radar.power, ship::target(1).radar_cross_section)
+        };
+        let omni_range = {
+            let mut radar = ship::fighter(0).radar.unwrap();
+            radar.set_width(TAU);
+            assert!(radar.is_omnidirectional());
+            compute_max_detection_range(&radar, radar.power, ship::target(1).radar_cross_section)
+        };
+        assert!(omni_range < focused_range * 0.5);
+
+        let detect = |bearing: f64, range: f64, width: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            ship::create(
+                &mut sim,
+                UnitComplex::new(bearing).transform_vector(&vector![range, 0.0]),
+                vector![0.0, 0.0],
+                0.0,
+                ship::target(1),
+            );
+            let mut ship = sim.ship_mut(ship0);
+            let radar = ship.radar_mut().unwrap();
+            radar.set_heading(0.0);
+            radar.set_width(width);
+            sim.step();
+            sim.ship(ship0).radar().unwrap().result.is_some()
+        };
+
+        for i in 0..8 {
+            let bearing = TAU * i as f64 / 8.0;
+            assert!(detect(bearing, omni_range * 0.5, TAU), "bearing={bearing}");
+            assert_eq!(
+                detect(bearing, omni_range * 0.5, TAU / 64.0),
+                i == 0,
+                "bearing={bearing}"
+            );
+        }
+        assert!(!detect(0.0, omni_range * 1.25, TAU));
+        assert!(detect(0.0, omni_range * 1.25, TAU / 64.0));
+    }
+
+    #[test]
+    fn test_omnidirectional_planet() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![15000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::ShipData {
+                class: ShipClass::Planet,
+                team: 2,
+                health: 1e9,
+                mass: 1e9,
+                radar_cross_section: 1e6,
+                ..Default::default()
+            },
+        );
+        for x in [30000.0, -30000.0] {
+            ship::create(
+                &mut sim,
+                vector![x, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::target(1),
+            );
+        }
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.set_width(TAU);
+        radar.power = 1e15;
+        sim.step();
+
+        // The target behind the planet is hidden.
+        let ship = sim.ship(ship0);
+        let contacts = ship.radar().unwrap().scan_all();
+        assert_eq!(contacts.len(), 1, "{contacts:?}");
+        assert!(contacts[0].position.x < 0.0);
+    }
+
+    #[test]
+    fn test_slew_rate() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.set_slew_rate(Some(PI));
+        radar.set_heading(PI);
+        assert_eq!(radar.get_heading(), 0.0);
+        assert_eq!(radar.get_target_heading(), PI);
+
+        let remaining = |sim: &Simulation| {
+            let heading = sim.ship(ship0).radar().unwrap().get_heading();
+            ((PI - heading + PI).rem_euclid(TAU) - PI).abs()
+        };
+        let mut ticks = 0;
+        while remaining(&sim) > 1e-6 {
+            let prev = remaining(&sim);
+            sim.step();
+            assert!(remaining(&sim) < prev);
+            assert!(prev - remaining(&sim) <= PI * PHYSICS_TICK_LENGTH + 1e-9);
+            ticks += 1;
+            assert!(ticks < 1000);
+        }
+        assert!(ticks > 1);
+        assert!((ticks as f64 - 1.0 / PHYSICS_TICK_LENGTH).abs() <= 1.0);
+
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.set_slew_rate(None);
+        radar.set_heading(0.0);
+        assert_eq!(radar.get_heading(), 0.0);
+    }
+
+    #[test]
+    fn test_last_beam() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        assert!(sim.ship(ship0).radar().unwrap().last_beam().is_none());
+
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.set_heading(-PI / 2.0);
+        radar.set_width(TAU / 2.0);
+        let heading = radar.get_heading();
+        let max_width = radar.max_width;
+        assert!((heading - 1.5 * PI).abs() < 1e-9);
+        sim.step();
+
+        let beam = sim.ship(ship0).radar().unwrap().last_beam().unwrap();
+        assert_eq!(beam.heading, heading);
+        assert_eq!(beam.width, max_width);
+        assert_eq!(beam.start_bearing, beam.heading - max_width / 2.0);
+        assert_eq!(beam.end_bearing, beam.heading + max_width / 2.0);
+        assert!(beam.range > 0.0);
+        assert!(beam.range <= sim.ship(ship0).radar().unwrap().max_distance);
+    }
+
+    #[test]
+    fn test_occlusion() {
+        let detect = |occlusion: bool, asteroid_y: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            sim.set_radar_occlusion(occlusion);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            ship::create(
+                &mut sim,
+                vector![2000.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(1),
+            );
+            ship::create(
+                &mut sim,
+                vector![1000.0, asteroid_y],
+                vector![0.0, 0.0],
+                0.0,
+                ship::asteroid(0),
+            );
+            let mut ship = sim.ship_mut(ship0);
+            let radar = ship.radar_mut().unwrap();
+            radar.set_heading(0.0);
+            radar.set_width(TAU / 360.0);
+            // Filter out the asteroid itself, it still blocks the beam.
+            radar.set_min_distance(1500.0);
+            sim.step();
+            sim.ship(ship0)
+                .radar()
+                .unwrap()
+                .scan()
+                .map(|contact| contact.class == Some(ShipClass::Fighter))
+                .unwrap_or(false)
+        };
+
+        assert!(detect(false, 0.0));
+        assert!(!detect(true, 0.0));
+        assert!(detect(true, 500.0));
+    }
+
+    #[test]
+    fn test_max_radar_range() {
+        let detect = |distance: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            sim.set_max_radar_range(Some(5000.0));
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            ship::create(
+                &mut sim,
+                vector![distance, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::cruiser(1),
+            );
+            let mut ship = sim.ship_mut(ship0);
+            let radar = ship.radar_mut().unwrap();
+            radar.power = 1e12;
+            radar.set_heading(0.0);
+            let mut detected = false;
+            for _ in 0..10 {
+                sim.step();
+                let ship = sim.ship(ship0);
+                let radar = ship.radar().unwrap();
+                assert!(radar.last_beam().unwrap().range <= 5000.0);
+                detected |= radar.scan().is_some();
+            }
+            detected
+        };
+
+        assert!(detect(4900.0));
+        assert!(!detect(5100.0));
+    }
+
+    #[test]
+    fn test_closest_contact() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let mut weak = ship::fighter(1);
+        weak.radar_cross_section = 0.01;
+        ship::create(&mut sim, vector![1000.0, 0.0], vector![0.0, 0.0], 0.0, weak);
+        ship::create(
+            &mut sim,
+            vector![3000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::cruiser(1),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.step();
+
+        let ship = sim.ship(ship0);
+        let radar = ship.radar().unwrap();
+        assert_eq!(radar.scan().unwrap().class, Some(ShipClass::Cruiser));
+        let closest = radar.closest_contact().unwrap();
+        assert_eq!(closest.class, Some(ShipClass::Fighter));
+        assert!((closest.position - vector![1000.0, 0.0]).magnitude() < 100.0);
+    }
+
+    #[test]
+    fn test_noise_factor() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![5000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::cruiser(1),
+        );
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.set_heading(0.0);
+        radar.noise_factor = 0.0;
+        sim.step();
+        let contact = sim.ship(ship0).radar().unwrap().scan().unwrap();
+        assert!(
+            (contact.position - vector![5000.0, 0.0]).magnitude() < 1e-6,
+            "{}",
+            contact.position
+        );
+        assert_eq!(contact.velocity, vector![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_energy() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![5000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::cruiser(1),
+        );
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.set_heading(0.0);
+        let cost = radar.power * PHYSICS_TICK_LENGTH;
+        radar.energy = Some(RadarEnergy {
+            energy: 3.0 * cost,
+            max_energy: 3.0 * cost,
+            recharge_per_second: 0.5 * radar.power,
+        });
+        let rssi = |sim: &Simulation| sim.ship(ship0).radar().unwrap().scan().unwrap().rssi;
+        let energy = |sim: &Simulation| sim.ship(ship0).radar().unwrap().get_energy().unwrap();
+
+        sim.step();
+        let full_power_rssi = rssi(&sim);
+        approx::assert_abs_diff_eq!(energy(&sim), 2.0 * cost, epsilon = 1e-6);
+
+        // Once drained, the radar runs at the recharge rate, half power.
+        for _ in 0..10 {
+            sim.step();
+        }
+        approx::assert_abs_diff_eq!(energy(&sim), 0.0, epsilon = 1e-6);
+        let half_power_rssi = rssi(&sim);
+        assert!(
+            full_power_rssi - half_power_rssi > 1.0,
+            "{full_power_rssi} {half_power_rssi}"
+        );
+
+        // Jamming draws from the same pool.
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.energy.as_mut().unwrap().energy = 3.0 * cost;
+        radar.ecm_mode = EcmMode::Noise;
+        sim.step();
+        approx::assert_abs_diff_eq!(energy(&sim), 2.0 * cost, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_classification() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.set_radar_classification(true);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.step();
+        let contact = sim.ship(ship0).radar().unwrap().scan().unwrap();
+        assert_eq!(contact.class, Some(ShipClass::Fighter));
+
+        // Put the contact between the minimum RSSI and the classification
+        // threshold, with enough margin for the rssi noise.
+        let rssi = {
+            let reflectors_by_team = build_reflector_team(&sim, &HashMap::new());
+            let ship = sim.ship(ship0);
+            let radar = ship.radar().unwrap();
+            let (emitter, _) = build_emitter(&ship, radar, radar.power);
+            compute_rssi(&emitter, &reflectors_by_team[&1].reflectors[0])
+        };
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.min_rssi = rssi / 1.5;
+        radar.reliable_rssi = radar.min_rssi;
+        sim.step();
+        let ship = sim.ship(ship0);
+        let radar = ship.radar().unwrap();
+        assert_eq!(radar.scan().unwrap().class, None);
+        assert_eq!(radar.tracks()[0].class, Some(ShipClass::Fighter));
+    }
+
+    #[test]
+    fn test_bearing_only() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 100.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+
+        // Put the contact between the two thresholds, with enough margin for
+        // the rssi noise.
+        let rssi = {
+            let reflectors_by_team = build_reflector_team(&sim, &HashMap::new());
+            let ship = sim.ship(ship0);
+            let radar = ship.radar().unwrap();
+            let (emitter, _) = build_emitter(&ship, radar, radar.power);
+            compute_rssi(&emitter, &reflectors_by_team[&1].reflectors[0])
+        };
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.min_rssi = rssi * 1.5;
+        radar.reliable_rssi = radar.min_rssi;
+
+        // Without a bearing-only threshold the contact is missed.
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan().is_none());
+
+        sim.ship_mut(ship0).radar_mut().unwrap().bearing_only_rssi = Some(rssi / 1.5);
+        sim.step();
+        let ship = sim.ship(ship0);
+        let radar = ship.radar().unwrap();
+        let contact = radar.scan().unwrap();
+        assert!(contact.bearing_only);
+        assert_eq!(contact.class, None);
+        approx::assert_abs_diff_eq!(contact.position.norm(), 1.0, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(
+            contact.position.y.atan2(contact.position.x),
+            0.1f64.atan(),
+            epsilon = 0.01
+        );
+        assert!(radar.tracks().is_empty());
+
+        // Above the minimum RSSI contacts are reported in full.
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.min_rssi = rssi / 1.5;
+        radar.reliable_rssi = radar.min_rssi;
+        sim.step();
+        let contact = sim.ship(ship0).radar().unwrap().scan().unwrap();
+        assert!(!contact.bearing_only);
+        assert!(contact.position.norm() > 900.0, "{}", contact.position);
+
+        // Unreliable returns that get dropped fall back to a bearing.
+        let mut ship = sim.ship_mut(ship0);
+        let radar = ship.radar_mut().unwrap();
+        radar.reliable_rssi = rssi * 1.5;
+        radar.bearing_only_rssi = Some(rssi / 3.0);
+        let mut bearing_only = 0;
+        for _ in 0..20 {
+            sim.step();
+            let contact = sim.ship(ship0).radar().unwrap().scan().unwrap();
+            if contact.bearing_only {
+                bearing_only += 1;
+            }
+        }
+        assert!(bearing_only > 0 && bearing_only < 20, "{bearing_only}");
+    }
+
+    #[test]
+    fn test_scan_all() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for x in [1000.0, 2000.0, 3000.0] {
+            ship::create(
+                &mut sim,
+                vector![x, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(1),
+            );
+        }
+        // Outside the beam.
+        ship::create(
+            &mut sim,
+            vector![0.0, 1000.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.step();
+
+        let ship = sim.ship(ship0);
+        let radar = ship.radar().unwrap();
+        let contacts = radar.scan_all();
+        assert_eq!(contacts.len(), 3);
+        assert!(contacts.windows(2).all(|w| w[0].rssi >= w[1].rssi));
+        for (contact, x) in contacts.iter().zip([1000.0, 2000.0, 3000.0]) {
+            assert!((contact.position - vector![x, 0.0]).magnitude() < 100.0);
+        }
+        let strongest = radar.scan().unwrap();
+        assert!((strongest.position - contacts[0].position).magnitude() < 100.0);
+
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(PI);
+        sim.step();
+        assert!(sim.ship(ship0).radar().unwrap().scan_all().is_empty());
+    }
+
+    #[test]
+    fn test_alliances() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        ship::create(
+            &mut sim,
+            vector![3000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(2),
+        );
+        sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+        sim.step();
+        assert_eq!(sim.ship(ship0).radar().unwrap().scan_all().len(), 2);
+
+        sim.set_allied(1, 0, true);
+        assert!(!sim.are_hostile(0, 1));
+        assert!(sim.are_hostile(0, 2));
+        assert!(sim.are_hostile(1, 2));
+        sim.step();
+        let ship = sim.ship(ship0);
+        let radar = ship.radar().unwrap();
+        assert_eq!(radar.scan_all().len(), 1);
+        let contact = radar.scan().unwrap();
+        assert!((contact.position - vector![3000.0, 0.0]).magnitude() < 100.0);
+
+        sim.set_allied(0, 1, false);
+        assert!(sim.are_hostile(0, 1));
+    }
+
+    #[test]
+    fn test_ping() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![0.0, -1500.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        ship::create(
+            &mut sim,
+            vector![3000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        ship::create(
+            &mut sim,
+            vector![500.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+
+        for heading in [0.0, PI / 2.0, PI] {
+            let mut ship = sim.ship_mut(ship0);
+            let radar = ship.radar_mut().unwrap();
+            radar.set_heading(heading);
+            radar.request_ping();
+            sim.step();
+            let ship = sim.ship(ship0);
+            let radar = ship.radar().unwrap();
+            let range = radar.ping_range().expect("ping range");
+            assert!((range - 1500.0).abs() < EPSILON, "{range}");
+            assert!(radar.scan().is_none());
+        }
+
+        // Without a new request the radar goes back to scanning.
+        sim.step();
+        assert_eq!(sim.ship(ship0).radar().unwrap().ping_range(), None);
+    }
+
+    #[test]
+    fn test_radar_aspect() {
+        let rssi = |heading: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            let mut target = ship::fighter(1);
+            target.radar_aspect = ship::RadarAspect {
+                head_on: 0.1,
+                broadside: 1.0,
+            };
+            ship::create(
+                &mut sim,
+                vector![5000.0, 0.0],
+                vector![0.0, 0.0],
+                heading,
+                target,
+            );
+            sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+            sim.step();
+            sim.ship(ship0).radar().unwrap().scan().unwrap().rssi
+        };
+
+        let head_on = rssi(0.0);
+        let tail_on = rssi(PI);
+        let broadside = rssi(PI / 2.0);
+        let oblique = rssi(PI / 4.0);
+        assert!(broadside > head_on + 5.0, "{broadside} {head_on}");
+        assert!(broadside > tail_on + 5.0, "{broadside} {tail_on}");
+        assert!(oblique > head_on && oblique < broadside);
+    }
+
+    #[test]
+    fn test_thermal_signature() {
+        let rssi = |thrust: bool| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            let mut target = ship::fighter(1);
+            target.thermal_gain = 0.05;
+            let target = ship::create(
+                &mut sim,
+                vector![5000.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                target,
+            );
+            sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+            for _ in 0..60 {
+                if thrust {
+                    // Away from the radar, so geometry alone would lower the return.
+                    sim.ship_mut(target).accelerate(vector![60.0, 0.0]);
+                }
+                sim.step();
+            }
+            let signature = sim.ship(target).data().thermal_signature;
+            (
+                sim.ship(ship0).radar().unwrap().scan().unwrap().rssi,
+                signature,
+            )
+        };
+
+        let (coasting, coasting_signature) = rssi(false);
+        let (thrusting, thrusting_signature) = rssi(true);
+        assert_eq!(coasting_signature, 0.0);
+        assert!(thrusting_signature > 30.0, "{thrusting_signature}");
+        assert!(thrusting > coasting + 3.0, "{thrusting} {coasting}");
+    }
 }
@@ -0,0 +1,108 @@
+use crate::collision;
+use crate::ship::ShipHandle;
+use crate::simulation::Simulation;
+use nalgebra::Vector2;
+use rapier2d_f64::prelude::*;
+use std::collections::BTreeSet;
+
+/// Fraction of a ship's maximum health and fuel restored per second.
+pub const REPAIR_RATE: f64 = 0.1;
+
+/// A sensor region that repairs and refuels friendly ships inside it.
+#[derive(Clone)]
+pub struct RepairZone {
+    pub position: Vector2<f64>,
+    pub radius: f64,
+    pub team: i32,
+    collider: ColliderHandle,
+    occupants: BTreeSet<ShipHandle>,
+}
+
+/// Adds a repair zone for ships of `team` and its allies.
+///
+/// While a friendly ship overlaps the zone it regains health and fuel at
+/// [`REPAIR_RATE`] and its gun magazines are kept full. Enemy ships are
+/// unaffected and pass through freely.
+pub fn add_repair_zone(sim: &mut Simulation, position: Vector2<f64>, radius: f64, team: i32) {
+    let rigid_body = RigidBodyBuilder::fixed().translation(position).build();
+    let body_handle = sim.bodies.insert(rigid_body);
+    let collider = ColliderBuilder::ball(radius)
+        .sensor(true)
+        .collision_groups(collision::zone_interaction_groups())
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .build();
+    let collider = sim
+        .colliders
+        .insert_with_parent(collider, body_handle, &mut sim.bodies);
+    sim.repair_zones.push(RepairZone {
+        position,
+        radius,
+        team,
+        collider,
+        occupants: BTreeSet::new(),
+    });
+}
+
+/// Tracks ships entering and leaving repair zones.
+///
+/// Returns true if the event involved a repair zone and needs no further
+/// handling.
+pub(crate) fn handle_collision_event(sim: &mut Simulation, event: &CollisionEvent) -> bool {
+    let (h1, h2, started) = match *event {
+        CollisionEvent::Started(h1, h2, _) => (h1, h2, true),
+        CollisionEvent::Stopped(h1, h2, _) => (h1, h2, false),
+    };
+    let Some(zone_index) = sim
+        .repair_zones
+        .iter()
+        .position(|zone| zone.collider == h1 || zone.collider == h2)
+    else {
+        return false;
+    };
+    let other = if sim.repair_zones[zone_index].collider == h1 {
+        h2
+    } else {
+        h1
+    };
+    let ship = sim
+        .colliders
+        .get(other)
+        .and_then(|collider| collider.parent())
+        .map(|body| ShipHandle(body.0))
+        .filter(|&handle| sim.ships.contains(handle));
+    if let Some(ship) = ship {
+        let occupants = &mut sim.repair_zones[zone_index].occupants;
+        if started {
+            occupants.insert(ship);
+        } else {
+            occupants.remove(&ship);
+        }
+    }
+    true
+}
+
+pub(crate) fn tick(sim: &mut Simulation) {
+    let dt = sim.dt();
+    let mut zones = std::mem::take(&mut sim.repair_zones);
+    for zone in zones.iter_mut() {
+        zone.occupants.retain(|&handle| sim.ships.contains(handle));
+        for &handle in zone.occupants.iter() {
+            if sim.are_hostile(sim.ship(handle).data().team, zone.team) {
+                continue;
+            }
+            let mut ship = sim.ship_mut(handle);
+            let data = ship.data_mut();
+            if data.destroyed {
+                continue;
+            }
+            data.health = (data.health + data.max_health * REPAIR_RATE * dt).min(data.max_health);
+            if let (Some(fuel), Some(max_fuel)) = (data.fuel.as_mut(), data.max_fuel) {
+                *fuel = (*fuel + max_fuel * REPAIR_RATE * dt).min(max_fuel);
+            }
+            for gun in data.guns.iter_mut() {
+                gun.magazine_remaining = gun.magazine_size;
+            }
+        }
+    }
+    sim.repair_zones = zones;
+}
@@ -0,0 +1,131 @@
+//! Recording and playback of matches without re-simulating.
+//!
+//! A replay is a gzip-compressed stream of JSON lines. The first line is a
+//! [`ReplayHeader`] and each following line is the [`RenderState`] of one
+//! tick.
+use crate::simulation::Simulation;
+use crate::snapshot::RenderState;
+use libflate::gzip::{Decoder, Encoder};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayHeader {
+    pub version: u32,
+    pub scenario: String,
+    pub seed: u32,
+}
+
+/// Captures the render state of a running simulation every tick.
+pub struct MatchRecorder<W: Write> {
+    encoder: Encoder<W>,
+    frames: usize,
+}
+
+impl<W: Write> MatchRecorder<W> {
+    pub fn new(writer: W, scenario: &str, seed: u32) -> Result<Self, String> {
+        let encoder = Encoder::new(writer).map_err(|e| format!("Failed to create encoder: {e}"))?;
+        let mut recorder = MatchRecorder { encoder, frames: 0 };
+        recorder.write_line(&ReplayHeader {
+            version: REPLAY_VERSION,
+            scenario: scenario.to_string(),
+            seed,
+        })?;
+        Ok(recorder)
+    }
+
+    /// Appends the current state of `sim` as a new frame.
+    pub fn record(&mut self, sim: &Simulation) -> Result<(), String> {
+        self.write_line(&sim.render_state())?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Steps the simulation and records the resulting frame.
+    pub fn step(&mut self, sim: &mut Simulation) -> Result<(), String> {
+        sim.step();
+        self.record(sim)
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Flushes the compressed stream and returns the underlying writer.
+    pub fn finish(self) -> Result<W, String> {
+        self.encoder
+            .finish()
+            .into_result()
+            .map_err(|e| format!("Failed to finish replay: {e}"))
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        serde_json::to_writer(&mut self.encoder, value)
+            .map_err(|e| format!("Failed to write replay: {e}"))?;
+        self.encoder
+            .write_all(b"\n")
+            .map_err(|e| format!("Failed to write replay: {e}"))
+    }
+}
+
+/// A recorded match read back from a [`MatchRecorder`] stream.
+pub struct MatchReplay {
+    pub header: ReplayHeader,
+    frames: Vec<RenderState>,
+}
+
+impl MatchReplay {
+    pub fn load(path: &Path) -> Result<MatchReplay, String> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+        Self::from_reader(file)
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<MatchReplay, String> {
+        let decoder = Decoder::new(reader).map_err(|e| format!("Failed to read replay: {e}"))?;
+        let mut lines = BufReader::new(decoder).lines();
+        let mut next_line = || -> Result<Option<String>, String> {
+            lines
+                .next()
+                .transpose()
+                .map_err(|e| format!("Failed to read replay: {e}"))
+        };
+
+        let header: ReplayHeader = match next_line()? {
+            Some(line) => serde_json::from_str(&line)
+                .map_err(|e| format!("Failed to parse replay header: {e}"))?,
+            None => return Err("Empty replay".to_string()),
+        };
+        if header.version != REPLAY_VERSION {
+            return Err(format!(
+                "Unsupported replay version {} (expected {})",
+                header.version, REPLAY_VERSION
+            ));
+        }
+
+        let mut frames = vec![];
+        while let Some(line) = next_line()? {
+            frames.push(
+                serde_json::from_str(&line)
+                    .map_err(|e| format!("Failed to parse replay frame: {e}"))?,
+            );
+        }
+
+        Ok(MatchReplay { header, frames })
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn frame(&self, index: usize) -> Option<&RenderState> {
+        self.frames.get(index)
+    }
+}
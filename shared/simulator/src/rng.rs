@@ -1,5 +1,31 @@
+use rand::SeedableRng;
 pub use rand_chacha::ChaCha8Rng as SeededRng;
 
 pub fn new_rng(seed: u32) -> SeededRng {
     rand_seeder::Seeder::from(seed).make_rng()
 }
+
+/// The complete state of a [`SeededRng`], enough to resume its stream exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
+}
+
+impl RngState {
+    pub fn capture(rng: &SeededRng) -> Self {
+        Self {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        }
+    }
+
+    pub fn restore(&self) -> SeededRng {
+        let mut rng = SeededRng::from_seed(self.seed);
+        rng.set_stream(self.stream);
+        rng.set_word_pos(self.word_pos);
+        rng
+    }
+}
@@ -0,0 +1,149 @@
+use crate::scenario::Status;
+use crate::simulation::Simulation;
+use instant::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Drives a [`Simulation`] either as fast as possible or paced to wall-clock
+/// time.
+pub struct SimulationRunner {
+    sim: Box<Simulation>,
+    paused: Arc<AtomicBool>,
+}
+
+impl SimulationRunner {
+    pub fn new(sim: Box<Simulation>) -> Self {
+        Self {
+            sim,
+            paused: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn sim(&self) -> &Simulation {
+        &self.sim
+    }
+
+    pub fn sim_mut(&mut self) -> &mut Simulation {
+        &mut self.sim
+    }
+
+    pub fn into_inner(self) -> Box<Simulation> {
+        self.sim
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.sim.tick()
+    }
+
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Returns a flag that pauses the runner while set, e.g. from an input
+    /// handling thread.
+    pub fn pause_handle(&self) -> Arc<AtomicBool> {
+        self.paused.clone()
+    }
+
+    /// Steps until the scenario finishes, `ticks` steps have been taken, or
+    /// the runner is paused.
+    pub fn run_headless(&mut self, ticks: u32) -> Status {
+        for _ in 0..ticks {
+            if self.is_paused() || self.sim.status() != Status::Running {
+                break;
+            }
+            self.sim.step();
+        }
+        self.sim.status()
+    }
+
+    /// Like [`run_headless`](Self::run_headless) but sleeps between steps to
+    /// run at `ticks_per_second`. Waits instead of returning while paused.
+    pub fn run_realtime(&mut self, ticks_per_second: f64, ticks: u32) -> Status {
+        let period = Duration::from_secs_f64(1.0 / ticks_per_second);
+        let mut next_deadline = Instant::now();
+        let mut remaining = ticks;
+        while remaining > 0 && self.sim.status() == Status::Running {
+            if self.is_paused() {
+                sleep(period);
+                next_deadline = Instant::now();
+                continue;
+            }
+            self.sim.step();
+            remaining -= 1;
+            next_deadline += period;
+            let now = Instant::now();
+            if next_deadline > now {
+                sleep(next_deadline - now);
+            } else {
+                // Fell behind, don't try to catch up with a burst of steps.
+                next_deadline = now;
+            }
+        }
+        self.sim.status()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sleep(duration: Duration) {
+    std::thread::sleep(duration);
+}
+
+#[cfg(target_arch = "wasm32")]
+fn sleep(_duration: Duration) {}
+
+#[cfg(test)]
+mod test {
+    use super::SimulationRunner;
+    use crate::scenario::Status;
+    use crate::ship;
+    use crate::simulation::{Code, Simulation};
+    use instant::Instant;
+    use nalgebra::vector;
+    use test_log::test;
+
+    fn runner() -> SimulationRunner {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![10.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        SimulationRunner::new(sim)
+    }
+
+    #[test]
+    fn test_run_headless() {
+        let mut runner = runner();
+        assert_eq!(runner.run_headless(100), Status::Running);
+        assert_eq!(runner.tick(), 100);
+
+        runner.pause();
+        runner.run_headless(100);
+        assert_eq!(runner.tick(), 100);
+
+        runner.resume();
+        runner.run_headless(50);
+        assert_eq!(runner.tick(), 150);
+    }
+
+    #[test]
+    fn test_run_realtime() {
+        let mut runner = runner();
+        let start = Instant::now();
+        runner.run_realtime(200.0, 20);
+        assert_eq!(runner.tick(), 20);
+        assert!(start.elapsed().as_secs_f64() >= 0.09);
+    }
+}
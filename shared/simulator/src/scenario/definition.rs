@@ -0,0 +1,656 @@
+use super::prelude::*;
+use super::{check_victory_with_filter, MAX_TICKS};
+use crate::collision::MAX_TEAMS;
+use crate::ship::{ShipClass, ShipData};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+/// A scenario described by data instead of code.
+///
+/// Can be loaded from TOML or JSON. Asteroids and planets are listed as ships
+/// with the corresponding class. The world border is set by `world_size`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScenarioDef {
+    pub name: String,
+    #[serde(default = "default_world_size")]
+    pub world_size: f64,
+    #[serde(default)]
+    pub boundary: Boundary,
+    #[serde(default)]
+    pub victory: VictoryCondition,
+    #[serde(default = "default_max_ticks")]
+    pub max_ticks: u32,
+    /// Interior walls, each a closed polygon, see [`add_polygon_wall`].
+    #[serde(default)]
+    pub walls: Vec<Vec<Point2<f64>>>,
+    #[serde(default)]
+    pub ships: Vec<ShipDef>,
+    /// Starting resources by team, for asymmetric scenarios.
+    #[serde(default)]
+    pub loadouts: Vec<Loadout>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShipDef {
+    pub team: i32,
+    #[serde(serialize_with = "serialize_class")]
+    pub class: ShipClass,
+    pub position: Vector2<f64>,
+    #[serde(default = "zero")]
+    pub velocity: Vector2<f64>,
+    #[serde(default)]
+    pub heading: f64,
+}
+
+/// Overrides for the resources a team's ships spawn with. Unset values keep
+/// the class defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Loadout {
+    pub team: i32,
+    #[serde(default)]
+    pub health: Option<f64>,
+    #[serde(default)]
+    pub fuel: Option<f64>,
+    /// Rounds per gun.
+    #[serde(default)]
+    pub ammo: Option<i32>,
+}
+
+impl Loadout {
+    pub fn apply(&self, data: &mut ShipData) {
+        if let Some(health) = self.health {
+            data.max_health = data.max_health.max(data.health).max(health);
+            data.health = health;
+        }
+        if let Some(fuel) = self.fuel {
+            data.max_fuel = Some(data.max_fuel.or(data.fuel).unwrap_or(0.0).max(fuel));
+            data.fuel = Some(fuel);
+        }
+        if let Some(ammo) = self.ammo {
+            for gun in data.guns.iter_mut() {
+                gun.ammo = Some(ammo);
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VictoryCondition {
+    /// The scenario runs until `max_ticks`.
+    #[default]
+    None,
+    /// Team 0 or 1 wins when the other has no fighters, frigates, or cruisers left.
+    LastTeamStanding,
+    /// Like `LastTeamStanding` but only frigates and cruisers count.
+    CapitalShips,
+    /// Team 0 must destroy all other non-missile ships.
+    Tutorial,
+}
+
+impl VictoryCondition {
+    /// Returns true if a ship of `class` on `team` keeps its team in the game.
+    fn counts(&self, class: ShipClass, team: i32) -> bool {
+        match self {
+            VictoryCondition::None => false,
+            VictoryCondition::LastTeamStanding => {
+                [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser].contains(&class)
+                    && team < 2
+            }
+            VictoryCondition::CapitalShips => {
+                [ShipClass::Frigate, ShipClass::Cruiser].contains(&class) && team < 2
+            }
+            VictoryCondition::Tutorial => {
+                ![ShipClass::Missile, ShipClass::Torpedo].contains(&class)
+            }
+        }
+    }
+}
+
+/// A mistake found by [`ScenarioDef::validate`]. Ships are identified by
+/// their index in [`ScenarioDef::ships`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioError {
+    OverlappingShips {
+        first: usize,
+        second: usize,
+    },
+    /// A ship touches the world border or one of [`ScenarioDef::walls`].
+    OverlapsWall {
+        ship: usize,
+    },
+    OutOfBounds {
+        ship: usize,
+    },
+    /// A team has no ships although it has a loadout, or although a
+    /// higher-numbered team has ships other than asteroids and planets.
+    EmptyTeam {
+        team: i32,
+    },
+    /// A ship's team is outside `0..MAX_TEAMS`.
+    InvalidTeam {
+        ship: usize,
+        team: i32,
+    },
+    /// A wall has fewer than two vertices.
+    InvalidWall {
+        wall: usize,
+    },
+    /// The victory condition is decided before the scenario starts, because
+    /// fewer than two teams (or, in tutorials, not team 0) have ships that
+    /// count towards it.
+    UnreachableVictory,
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScenarioError::OverlappingShips { first, second } => {
+                write!(f, "ships {first} and {second} overlap")
+            }
+            ScenarioError::OverlapsWall { ship } => write!(f, "ship {ship} overlaps a wall"),
+            ScenarioError::OutOfBounds { ship } => write!(f, "ship {ship} is out of bounds"),
+            ScenarioError::EmptyTeam { team } => write!(f, "team {team} has no ships"),
+            ScenarioError::InvalidTeam { ship, team } => {
+                write!(f, "ship {ship} has team {team}, outside 0..{MAX_TEAMS}")
+            }
+            ScenarioError::InvalidWall { wall } => {
+                write!(f, "wall {wall} has fewer than two vertices")
+            }
+            ScenarioError::UnreachableVictory => {
+                write!(f, "victory condition is decided before the scenario starts")
+            }
+        }
+    }
+}
+
+fn default_world_size() -> f64 {
+    40000.0
+}
+
+fn default_max_ticks() -> u32 {
+    MAX_TICKS
+}
+
+fn zero() -> Vector2<f64> {
+    vector![0.0, 0.0]
+}
+
+/// TOML can't serialize struct variants, so asteroids are written as the
+/// equivalent table, `{ Asteroid = { variant = 2 } }`. Both formats read
+/// that back as the usual externally tagged enum.
+fn serialize_class<S: Serializer>(class: &ShipClass, serializer: S) -> Result<S::Ok, S::Error> {
+    match class {
+        ShipClass::Asteroid { variant } => {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry("Asteroid", &BTreeMap::from([("variant", *variant)]))?;
+            map.end()
+        }
+        _ => class.serialize(serializer),
+    }
+}
+
+impl ScenarioDef {
+    pub fn load(path: &Path) -> Result<ScenarioDef, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("json") => Self::from_json(&text),
+            _ => Err(format!("Unknown scenario format {:?}", path.display())),
+        }
+        .and_then(|def| {
+            def.validate().map_err(|errors| {
+                let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                format!("Invalid scenario {}: {}", path.display(), errors.join(", "))
+            })?;
+            Ok(def)
+        })
+    }
+
+    pub fn from_toml(text: &str) -> Result<ScenarioDef, String> {
+        toml::from_str(text).map_err(|e| format!("Failed to parse scenario: {e}"))
+    }
+
+    pub fn from_json(text: &str) -> Result<ScenarioDef, String> {
+        serde_json::from_str(text).map_err(|e| format!("Failed to parse scenario: {e}"))
+    }
+
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|e| format!("Failed to serialize scenario: {e}"))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize scenario: {e}"))
+    }
+
+    /// Returns the starting resources configured for `team`, if any.
+    pub fn loadout(&self, team: i32) -> Option<&Loadout> {
+        self.loadouts.iter().find(|loadout| loadout.team == team)
+    }
+
+    /// Checks for authoring mistakes, returning every problem found.
+    pub fn validate(&self) -> Result<(), Vec<ScenarioError>> {
+        let mut errors = vec![];
+        let radii: Vec<f64> = self
+            .ships
+            .iter()
+            .map(|def| def.data().collider.radius(def.class))
+            .collect();
+
+        let half_size = self.world_size / 2.0;
+        for (i, def) in self.ships.iter().enumerate() {
+            if !(0..MAX_TEAMS).contains(&def.team) {
+                errors.push(ScenarioError::InvalidTeam {
+                    ship: i,
+                    team: def.team,
+                });
+                continue;
+            }
+            let extent = def.position.abs().max();
+            if self.boundary != Boundary::Open && extent > half_size {
+                errors.push(ScenarioError::OutOfBounds { ship: i });
+                continue;
+            }
+            let overlaps_border = self.boundary == Boundary::Walls && extent + radii[i] > half_size;
+            let overlaps_wall = self
+                .walls
+                .iter()
+                .any(|wall| wall_distance(wall, def.position.into()) < radii[i]);
+            if overlaps_border || overlaps_wall {
+                errors.push(ScenarioError::OverlapsWall { ship: i });
+            }
+        }
+
+        for i in 0..self.ships.len() {
+            for j in (i + 1)..self.ships.len() {
+                let distance = (self.ships[i].position - self.ships[j].position).magnitude();
+                if distance < radii[i] + radii[j] {
+                    errors.push(ScenarioError::OverlappingShips {
+                        first: i,
+                        second: j,
+                    });
+                }
+            }
+        }
+
+        let teams: BTreeSet<i32> = self.ships.iter().map(|def| def.team).collect();
+        let mut empty_teams: BTreeSet<i32> = self
+            .loadouts
+            .iter()
+            .map(|loadout| loadout.team)
+            .filter(|team| !teams.contains(team))
+            .collect();
+        let player_teams: BTreeSet<i32> = self
+            .ships
+            .iter()
+            .filter(|def| !matches!(def.class, ShipClass::Asteroid { .. } | ShipClass::Planet))
+            .map(|def| def.team)
+            .filter(|team| (0..MAX_TEAMS).contains(team))
+            .collect();
+        if let Some(&last) = player_teams.last() {
+            empty_teams.extend((0..last).filter(|team| !player_teams.contains(team)));
+        }
+        for team in empty_teams {
+            errors.push(ScenarioError::EmptyTeam { team });
+        }
+
+        for (i, wall) in self.walls.iter().enumerate() {
+            if wall.len() < 2 {
+                errors.push(ScenarioError::InvalidWall { wall: i });
+            }
+        }
+
+        if self.victory != VictoryCondition::None {
+            let counted: BTreeSet<i32> = self
+                .ships
+                .iter()
+                .filter(|def| self.victory.counts(def.class, def.team))
+                .map(|def| def.team)
+                .collect();
+            let reachable = counted.len() >= 2
+                && (self.victory != VictoryCondition::Tutorial || counted.contains(&0));
+            if !reachable {
+                errors.push(ScenarioError::UnreachableVictory);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validates the scenario and builds a simulation from it.
+    pub fn build(self, seed: u32, codes: &[Code]) -> Result<Box<Simulation>, Vec<ScenarioError>> {
+        self.validate()?;
+        Ok(Simulation::new_with_scenario(Box::new(self), seed, codes))
+    }
+}
+
+/// Returns the distance from `point` to the closed polygon through `wall`.
+fn wall_distance(wall: &[Point2<f64>], point: Point2<f64>) -> f64 {
+    (0..wall.len())
+        .map(|i| {
+            let (a, b) = (wall[i], wall[(i + 1) % wall.len()]);
+            let ab = b - a;
+            let t = if ab.norm_squared() > 0.0 {
+                ((point - a).dot(&ab) / ab.norm_squared()).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            (a + ab * t - point).norm()
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+impl ShipDef {
+    fn data(&self) -> ShipData {
+        match self.class {
+            ShipClass::Fighter => fighter(self.team),
+            ShipClass::Frigate => frigate(self.team),
+            ShipClass::Cruiser => cruiser(self.team),
+            ShipClass::Asteroid { variant } => ShipData {
+                team: self.team,
+                ..asteroid(variant)
+            },
+            ShipClass::Target => target(self.team),
+            ShipClass::Missile => missile(self.team),
+            ShipClass::Torpedo => torpedo(self.team),
+            ShipClass::Planet => ShipData {
+                class: ShipClass::Planet,
+                team: self.team,
+                health: 1e9,
+                mass: 1e9,
+                radar_cross_section: 1e6,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Scenario for ScenarioDef {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, _seed: u32) {
+        for def in self.ships.iter() {
+            let mut data = def.data();
+            if let Some(loadout) = self.loadout(def.team) {
+                loadout.apply(&mut data);
+            }
+            ship::create(sim, def.position, def.velocity, def.heading, data);
+        }
+        for wall in self.walls.iter() {
+            add_polygon_wall(sim, wall);
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        match self.victory {
+            VictoryCondition::None => Status::Running,
+            VictoryCondition::LastTeamStanding | VictoryCondition::CapitalShips => {
+                check_victory_with_filter(sim, self.max_ticks, |ship| {
+                    self.victory.counts(ship.data().class, ship.data().team)
+                })
+            }
+            VictoryCondition::Tutorial => check_tutorial_victory(sim, self.max_ticks),
+        }
+    }
+
+    fn world_size(&self) -> f64 {
+        self.world_size
+    }
+
+    fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Boundary, Loadout, ScenarioDef, ScenarioError, ShipDef, VictoryCondition};
+    use crate::ship::ShipClass;
+    use crate::simulation::Code;
+    use nalgebra::{point, vector};
+
+    fn example() -> ScenarioDef {
+        ScenarioDef {
+            name: "example".to_string(),
+            world_size: 10000.0,
+            boundary: Default::default(),
+            victory: VictoryCondition::LastTeamStanding,
+            max_ticks: 1000,
+            walls: vec![vec![
+                point![-3000.0, -3000.0],
+                point![3000.0, -3000.0],
+                point![3000.0, 3000.0],
+            ]],
+            ships: vec![
+                ShipDef {
+                    team: 0,
+                    class: ShipClass::Fighter,
+                    position: vector![-1000.0, 0.0],
+                    velocity: vector![10.0, 0.0],
+                    heading: 0.0,
+                },
+                ShipDef {
+                    team: 1,
+                    class: ShipClass::Frigate,
+                    position: vector![1000.0, 0.0],
+                    velocity: vector![0.0, 0.0],
+                    heading: std::f64::consts::PI,
+                },
+                ShipDef {
+                    team: 9,
+                    class: ShipClass::Asteroid { variant: 2 },
+                    position: vector![0.0, 2000.0],
+                    velocity: vector![0.0, 0.0],
+                    heading: 0.0,
+                },
+            ],
+            loadouts: vec![Loadout {
+                team: 1,
+                health: Some(2000.0),
+                fuel: None,
+                ammo: Some(50),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let def = example();
+        assert_eq!(
+            ScenarioDef::from_json(&def.to_json().unwrap()).unwrap(),
+            def
+        );
+        assert_eq!(
+            ScenarioDef::from_toml(&def.to_toml().unwrap()).unwrap(),
+            def
+        );
+    }
+
+    #[test]
+    fn test_defaults() {
+        let def = ScenarioDef::from_toml(
+            r#"
+name = "minimal"
+
+[[ships]]
+team = 0
+class = "Fighter"
+position = [100.0, 200.0]
+"#,
+        )
+        .unwrap();
+        assert_eq!(def.world_size, 40000.0);
+        assert_eq!(def.victory, VictoryCondition::None);
+        assert_eq!(def.ships[0].velocity, vector![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_load() {
+        let path = std::env::temp_dir().join(format!(
+            "oort_scenario_def_test_{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, example().to_toml().unwrap()).unwrap();
+        let def = ScenarioDef::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let sim = def.build(0, &[Code::None, Code::None]).unwrap();
+        assert_eq!(sim.world_size(), 10000.0);
+        assert_eq!(sim.ships.len(), 3);
+        let mut positions: Vec<_> = sim
+            .ships
+            .iter()
+            .map(|&handle| sim.ship(handle).position().vector)
+            .collect();
+        positions.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+        assert_eq!(
+            positions,
+            vec![
+                vector![-1000.0, 0.0],
+                vector![0.0, 2000.0],
+                vector![1000.0, 0.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_scenario() {
+        let def = ScenarioDef::from_json(
+            r#"{
+                "name": "json",
+                "world_size": 10000.0,
+                "walls": [[[500.0, -500.0], [500.0, 500.0]]],
+                "ships": [
+                    {"team": 0, "class": "Fighter", "position": [0.0, 0.0], "velocity": [200.0, 0.0]},
+                    {"team": 0, "class": "Fighter", "position": [0.0, 1000.0]},
+                    {"team": 1, "class": "Frigate", "position": [2000.0, 0.0]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(def.validate(), Ok(()));
+
+        let mut sim = def.build(0, &[Code::None, Code::None]).unwrap();
+        let moving = sim.find_ships(|ship| ship.velocity().x > 0.0)[0];
+        for _ in 0..180 {
+            sim.step();
+        }
+        // The moving fighter would be 600 m out by now, so it hit the wall.
+        let team_size = |team| sim.find_ships(|ship| ship.data().team == team).len();
+        assert_eq!((team_size(0), team_size(1)), (1, 1));
+        assert!(!sim.ships.contains(moving));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(example().validate(), Ok(()));
+        let errors = |def: ScenarioDef| def.validate().unwrap_err();
+        let fighter = |team, x, y| ShipDef {
+            team,
+            class: ShipClass::Fighter,
+            position: vector![x, y],
+            velocity: vector![0.0, 0.0],
+            heading: 0.0,
+        };
+
+        let mut def = example();
+        def.ships.push(fighter(0, -1005.0, 0.0));
+        assert_eq!(
+            errors(def),
+            vec![ScenarioError::OverlappingShips {
+                first: 0,
+                second: 3
+            }]
+        );
+
+        let mut def = example();
+        def.ships.push(fighter(0, 4995.0, 0.0));
+        def.ships.push(fighter(0, 0.0, -6000.0));
+        assert_eq!(
+            errors(def.clone()),
+            vec![
+                ScenarioError::OverlapsWall { ship: 3 },
+                ScenarioError::OutOfBounds { ship: 4 }
+            ]
+        );
+        def.boundary = Boundary::Torus;
+        assert_eq!(errors(def), vec![ScenarioError::OutOfBounds { ship: 4 }]);
+
+        // Ships touching an interior wall, on an edge or a corner.
+        let mut def = example();
+        def.ships.push(fighter(0, 3005.0, 0.0));
+        def.ships.push(fighter(0, -3000.0, -3005.0));
+        def.ships.push(fighter(0, 0.0, 500.0));
+        assert_eq!(
+            errors(def),
+            vec![
+                ScenarioError::OverlapsWall { ship: 3 },
+                ScenarioError::OverlapsWall { ship: 4 }
+            ]
+        );
+
+        let mut def = example();
+        def.loadouts[0].team = 2;
+        assert_eq!(errors(def), vec![ScenarioError::EmptyTeam { team: 2 }]);
+
+        // Team 1 is skipped, asteroids on team 9 don't count.
+        let mut def = example();
+        def.ships[1].team = 2;
+        def.loadouts.clear();
+        def.victory = VictoryCondition::None;
+        assert_eq!(errors(def), vec![ScenarioError::EmptyTeam { team: 1 }]);
+
+        let mut def = example();
+        def.ships[2].team = 10;
+        def.ships.push(fighter(-1, 0.0, -2000.0));
+        assert!(def.clone().build(0, &[Code::None, Code::None]).is_err());
+        let errors_found = errors(def);
+        assert_eq!(
+            errors_found,
+            vec![
+                ScenarioError::InvalidTeam { ship: 2, team: 10 },
+                ScenarioError::InvalidTeam { ship: 3, team: -1 }
+            ]
+        );
+        assert_eq!(
+            errors_found[0].to_string(),
+            "ship 2 has team 10, outside 0..10"
+        );
+
+        let mut def = example();
+        def.walls[0].truncate(1);
+        assert_eq!(errors(def), vec![ScenarioError::InvalidWall { wall: 0 }]);
+
+        // Only frigates and cruisers count, and none are left.
+        let mut def = example();
+        def.victory = VictoryCondition::CapitalShips;
+        def.ships[1].class = ShipClass::Fighter;
+        assert_eq!(errors(def), vec![ScenarioError::UnreachableVictory]);
+
+        let mut def = example();
+        def.victory = VictoryCondition::Tutorial;
+        assert_eq!(def.validate(), Ok(()));
+        def.ships.remove(0);
+        let errors = errors(def);
+        assert_eq!(
+            errors,
+            vec![
+                ScenarioError::EmptyTeam { team: 0 },
+                ScenarioError::UnreachableVictory
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "victory condition is decided before the scenario starts"
+        );
+    }
+}
@@ -51,6 +51,7 @@ impl Scenario for GunneryScenario {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         let handles = sim.ships.iter().cloned().collect::<Vec<_>>();
         for handle in handles {
             let mut ship = sim.ship_mut(handle);
@@ -0,0 +1,133 @@
+use super::prelude::*;
+use crate::color;
+use crate::ship::ShipClass;
+use crate::simulation::PHYSICS_TICK_LENGTH;
+use std::collections::BTreeMap;
+
+/// Radius of the capture zone at the center of the world.
+pub const CAPTURE_RADIUS: f64 = 2000.0;
+
+/// Seconds of uncontested control needed to win.
+pub const SCORE_TO_WIN: f64 = 60.0;
+
+/// Teams score one point per second while they are the only team with a
+/// combat ship inside the capture zone.
+pub struct KingOfTheHill {
+    control_ticks: BTreeMap<i32, u32>,
+    controller: Option<i32>,
+    /// Simulation timestep, to convert control ticks to seconds.
+    dt: f64,
+}
+
+impl KingOfTheHill {
+    pub fn new() -> Self {
+        Self {
+            control_ticks: BTreeMap::new(),
+            controller: None,
+            dt: PHYSICS_TICK_LENGTH,
+        }
+    }
+
+    fn occupying_teams(sim: &Simulation) -> Vec<i32> {
+        let mut teams: Vec<i32> = sim
+            .find_ships(|ship| {
+                let data = ship.data();
+                !data.destroyed
+                    && [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser]
+                        .contains(&data.class)
+                    && ship.position().vector.norm() < CAPTURE_RADIUS
+            })
+            .into_iter()
+            .map(|handle| sim.ship(handle).data().team)
+            .collect();
+        teams.sort();
+        teams.dedup();
+        teams
+    }
+}
+
+impl Scenario for KingOfTheHill {
+    fn name(&self) -> String {
+        "king_of_the_hill".into()
+    }
+
+    fn human_name(&self) -> String {
+        "King of the Hill".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            ship::create(
+                sim,
+                position,
+                vector![0.0, 0.0],
+                heading,
+                fighter(team as i32),
+            );
+            self.control_ticks.insert(team as i32, 0);
+        }
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
+        self.dt = sim.dt();
+        let teams = Self::occupying_teams(sim);
+        self.controller = match teams[..] {
+            [team] => Some(team),
+            _ => None,
+        };
+        if let Some(team) = self.controller {
+            *self.control_ticks.entry(team).or_insert(0) += 1;
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        let ticks_to_win = (SCORE_TO_WIN / self.dt).round() as u32;
+        if let Some((&team, _)) = self
+            .control_ticks
+            .iter()
+            .find(|(_, &ticks)| ticks >= ticks_to_win)
+        {
+            return Status::Victory { team };
+        }
+        check_tournament_victory(sim)
+    }
+
+    fn team_scores(&self) -> BTreeMap<i32, f64> {
+        self.control_ticks
+            .iter()
+            .map(|(&team, &ticks)| (team, ticks as f64 * self.dt))
+            .collect()
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let mut lines = vec![];
+        let n = 40;
+        let color = match self.controller {
+            Some(team) => color::team(team),
+            None => vector![0.5, 0.5, 0.5, 1.0],
+        };
+        for i in 0..n {
+            let angle_a = TAU * (i as f64) / (n as f64);
+            let angle_b = TAU * ((i + 1) as f64) / (n as f64);
+            lines.push(Line {
+                a: point![angle_a.cos(), angle_a.sin()] * CAPTURE_RADIUS,
+                b: point![angle_b.cos(), angle_b.sin()] * CAPTURE_RADIUS,
+                color,
+            });
+        }
+        lines
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+}
@@ -1,16 +1,19 @@
 mod asteroid_duel;
 mod belt;
 mod cruiser_duel;
+mod definition;
 mod fighter_duel;
 mod fleet;
 mod frigate_duel;
 mod gunnery;
+mod king_of_the_hill;
 mod orbit;
 mod planetary_defense;
 mod primitive_duel;
 mod radar_duel;
 mod squadrons;
 mod stress;
+mod survival;
 mod test;
 mod tutorial_acceleration;
 mod tutorial_acceleration2;
@@ -26,14 +29,24 @@ mod tutorial_search;
 mod tutorial_squadron;
 mod welcome;
 
-use crate::ship::{asteroid, fighter, ShipAccessor, ShipClass, ShipData};
+pub use crate::collision::add_polygon_wall;
+pub use crate::debris::add_debris;
+pub use crate::mine::add_mine;
+pub use definition::{Loadout, ScenarioDef, ScenarioError, ShipDef, VictoryCondition};
+
+use crate::ship::{asteroid, fighter, ShipAccessor, ShipClass, ShipData, ShipHandle};
 use crate::simulation::{Code, Line, Simulation};
-use nalgebra::{vector, Vector2};
+use nalgebra::{vector, Rotation2, Vector2};
 use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 pub mod prelude {
+    pub use super::add_asteroid_field;
+    pub use super::add_debris;
+    pub use super::add_mine;
+    pub use super::add_polygon_wall;
+    pub use super::Boundary;
     pub use super::Scenario;
     pub use super::Status;
     pub use super::{builtin, empty_ai, reference_ai};
@@ -41,7 +54,7 @@ pub mod prelude {
         check_capital_ship_tournament_victory, check_tournament_victory, check_tutorial_victory,
     };
     pub use super::{fighter_without_missiles, fighter_without_missiles_or_radar, target_asteroid};
-    pub use super::{place_teams, Placement};
+    pub use super::{place_teams, spawn_teams_symmetric, Placement};
     pub use super::{DEFAULT_TUTORIAL_MAX_TICKS, TOURNAMENT_MAX_TICKS};
     pub use crate::rng::{new_rng, SeededRng};
     pub use crate::ship::{
@@ -65,6 +78,25 @@ pub enum Status {
     Draw,
 }
 
+/// What happens at the edge of the world.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Copy, Clone, Default)]
+pub enum Boundary {
+    /// Ships are destroyed by walls at the edge and bullets leaving the world
+    /// are removed.
+    #[default]
+    Walls,
+    /// There is no edge. Ships fly free and bullets last until they expire.
+    Open,
+    /// Leaving one edge re-enters at the opposite one. Radar sees across the
+    /// edges and reports the nearest copy of each contact, and distance
+    /// queries such as [`Simulation::nearest_ship`] use the
+    /// [shortest displacement](Simulation::shortest_displacement).
+    ///
+    /// Physics doesn't wrap: collisions, bullet hits, beams and trigger zones
+    /// such as mines only act between bodies on the same side of an edge.
+    Torus,
+}
+
 pub trait Scenario {
     fn name(&self) -> String;
 
@@ -74,7 +106,11 @@ pub trait Scenario {
 
     fn init(&mut self, sim: &mut Simulation, seed: u32);
 
-    fn tick(&mut self, _: &mut Simulation) {}
+    /// Called at the end of every step. Overrides should call
+    /// [`Simulation::apply_sudden_death`] to keep sudden death working.
+    fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
+    }
 
     fn status(&self, _: &Simulation) -> Status {
         Status::Running
@@ -115,9 +151,18 @@ pub trait Scenario {
         vec![]
     }
 
+    /// Points accrued by each team in objective-based scenarios.
+    fn team_scores(&self) -> BTreeMap<i32, f64> {
+        BTreeMap::new()
+    }
+
     fn world_size(&self) -> f64 {
         40000.0
     }
+
+    fn boundary(&self) -> Boundary {
+        Boundary::Walls
+    }
 }
 
 pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
@@ -153,6 +198,8 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         // Challenge
         "gunnery" => Some(Box::new(gunnery::GunneryScenario {})),
         "planetary_defense" => Some(Box::new(planetary_defense::PlanetaryDefense::new())),
+        "king_of_the_hill" => Some(Box::new(king_of_the_hill::KingOfTheHill::new())),
+        "survival" => Some(Box::new(survival::Survival::new())),
         // Testing
         "test" => Some(Box::new(test::TestScenario {})),
         "basic" => Some(Box::new(test::BasicScenario {})),
@@ -203,7 +250,15 @@ pub fn list() -> Vec<(String, Vec<String>)> {
                 "tutorial_cruiser",
             ],
         ),
-        ("Challenge", vec!["gunnery", "planetary_defense"]),
+        (
+            "Challenge",
+            vec![
+                "gunnery",
+                "planetary_defense",
+                "king_of_the_hill",
+                "survival",
+            ],
+        ),
         (
             "Tournament",
             vec![
@@ -244,21 +299,26 @@ pub fn empty_ai() -> Code {
 pub fn check_victory_with_filter(
     sim: &Simulation,
     max_ticks: u32,
-    ship_filter: fn(&ShipAccessor) -> bool,
+    ship_filter: impl Fn(&ShipAccessor) -> bool,
 ) -> Status {
     let mut team_health: HashMap<i32, u32> = HashMap::new();
-    for &handle in sim.ships.iter() {
+    for handle in sim.find_ships(ship_filter) {
         let ship = sim.ship(handle);
-        if ship_filter(&ship) {
-            *team_health.entry(ship.data().team).or_insert(0) += ship.data().health as u32;
-        }
+        let data = ship.data();
+        *team_health.entry(data.team).or_insert(0) += data.health as u32;
     }
-    if team_health.is_empty() {
+    let mut teams: Vec<i32> = team_health.keys().copied().collect();
+    teams.sort();
+    let allied = teams
+        .iter()
+        .enumerate()
+        .all(|(i, &a)| teams[i + 1..].iter().all(|&b| !sim.are_hostile(a, b)));
+    if teams.is_empty() {
         Status::Draw
-    } else if team_health.len() == 1 {
-        Status::Victory {
-            team: *team_health.iter().next().unwrap().0,
-        }
+    } else if allied {
+        // Only one team, or only teams allied with each other, survive. The
+        // lowest numbered one is reported as the winner.
+        Status::Victory { team: teams[0] }
     } else if sim.tick() >= max_ticks - 1 {
         Status::Draw
     } else {
@@ -309,6 +369,73 @@ pub fn target_asteroid(variant: i32) -> ShipData {
     asteroid
 }
 
+/// Scatters `count` asteroids across the world.
+///
+/// The layout depends only on `seed` and the world size, so every client given
+/// the same seed builds an identical field.
+pub fn add_asteroid_field(sim: &mut Simulation, count: usize, seed: u32) -> Vec<ShipHandle> {
+    let mut rng = crate::rng::new_rng(seed);
+    let bound = (sim.world_size() / 2.0) * 0.9;
+    (0..count)
+        .map(|_| {
+            let position = vector![rng.gen_range(-bound..bound), rng.gen_range(-bound..bound)];
+            let velocity = vector![rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0)];
+            let heading = rng.gen_range(0.0..(2.0 * std::f64::consts::PI));
+            let data = asteroid(rng.gen_range(0..30));
+            crate::ship::create(sim, position, velocity, heading, data)
+        })
+        .collect()
+}
+
+/// Spawns `ships_per_team` fighters for each of `teams` teams, spread evenly
+/// around the center of the world and facing it.
+///
+/// The whole layout is rotated by a random angle and every ship gets a small
+/// random offset and heading error. The same offsets are used for every
+/// team, so the teams stay exact rotations of each other and the match stays
+/// balanced. The layout depends only on `seed`. Returns the ships indexed by
+/// team.
+pub fn spawn_teams_symmetric(
+    sim: &mut Simulation,
+    teams: usize,
+    ships_per_team: usize,
+    seed: u32,
+) -> Vec<Vec<ShipHandle>> {
+    let mut rng = crate::rng::new_rng(seed);
+    let rotation = rng.gen_range(0.0..std::f64::consts::TAU);
+    let distance = sim.world_size() * 0.35;
+    let spacing = 200.0;
+    let perturbations: Vec<(Vector2<f64>, f64)> = (0..ships_per_team)
+        .map(|_| {
+            let offset = vector![rng.gen_range(-50.0..50.0), rng.gen_range(-50.0..50.0)];
+            (offset, rng.gen_range(-0.1..0.1))
+        })
+        .collect();
+    (0..teams)
+        .map(|team| {
+            let angle = rotation + std::f64::consts::TAU * team as f64 / teams as f64;
+            let outward = Rotation2::new(angle);
+            let heading = angle + std::f64::consts::PI;
+            perturbations
+                .iter()
+                .enumerate()
+                .map(|(i, (offset, heading_error))| {
+                    // Line abreast, centered on the team's spawn point.
+                    let lateral = (i as f64 - (ships_per_team as f64 - 1.0) / 2.0) * spacing;
+                    let local = vector![distance, lateral] + offset;
+                    crate::ship::create(
+                        sim,
+                        outward * local,
+                        vector![0.0, 0.0],
+                        heading + heading_error,
+                        fighter(team as i32),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
 pub struct Placement {
     pub position: Vector2<f64>,
     pub heading: f64,
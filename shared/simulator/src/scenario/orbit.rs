@@ -2,7 +2,6 @@ use rapier2d_f64::prelude::RigidBody;
 
 use super::prelude::*;
 use crate::ship::{ShipClass, ShipData};
-use crate::simulation::PHYSICS_TICK_LENGTH;
 
 pub struct Orbit {}
 
@@ -50,11 +49,13 @@ impl Scenario for Orbit {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         let g = 10.0;
+        let dt = sim.dt();
 
         let apply_gravity = |body: &mut RigidBody| {
             let acc = body.translation().normalize() * -g;
-            let impulse = acc * body.mass() * PHYSICS_TICK_LENGTH;
+            let impulse = acc * body.mass() * dt;
             body.apply_impulse(impulse, true);
         };
 
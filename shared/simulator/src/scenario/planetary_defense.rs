@@ -1,6 +1,5 @@
 use super::prelude::*;
 use crate::ship::{ShipClass, ShipData};
-use crate::simulation::PHYSICS_TICK_LENGTH;
 
 pub struct PlanetaryDefense {
     rng: SeededRng,
@@ -81,11 +80,12 @@ impl Scenario for PlanetaryDefense {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         if sim.time() < Self::SPAWN_DURATION {
             let bound = (sim.world_size() / 2.0) * 0.9;
             if self
                 .rng
-                .gen_bool(PHYSICS_TICK_LENGTH * (sim.time() / Self::SPAWN_DURATION) * 2.0)
+                .gen_bool(sim.dt() * (sim.time() / Self::SPAWN_DURATION) * 2.0)
             {
                 let mut ship_data = if self.rng.gen_bool(0.1) {
                     torpedo(1)
@@ -64,6 +64,7 @@ impl Scenario for PrimitiveDuel {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         if !sim.ships.contains(self.ship0.unwrap()) || !sim.ships.contains(self.ship1.unwrap()) {
             return;
         }
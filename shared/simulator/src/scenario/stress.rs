@@ -1,5 +1,5 @@
 use super::prelude::*;
-use crate::bullet::{self, BulletData};
+use crate::bullet::{self, BulletData, DamageType};
 use crate::color;
 
 pub struct StressScenario {}
@@ -91,6 +91,9 @@ impl Scenario for BulletStressScenario {
                     team: 0,
                     color: color::to_u32(vector![1.00, 0.63, 0.00, 0.30]),
                     ttl: 100.0,
+                    source: None,
+                    damage_type: DamageType::Kinetic,
+                    ..Default::default()
                 },
             );
         }
@@ -0,0 +1,131 @@
+use super::prelude::*;
+use crate::ship::ShipClass;
+use std::collections::BTreeMap;
+
+/// Seconds between waves.
+pub const WAVE_INTERVAL: f64 = 30.0;
+
+/// Waves spawned before the player can win by clearing the last one.
+pub const MAX_WAVES: u32 = 10;
+
+/// Team 0 defends against ever larger waves of enemy fighters, arriving on a
+/// ring around the center of the world.
+pub struct Survival {
+    rng: SeededRng,
+    wave: u32,
+}
+
+impl Survival {
+    pub fn new() -> Self {
+        Self {
+            rng: new_rng(0),
+            wave: 0,
+        }
+    }
+
+    pub fn wave_interval_ticks(dt: f64) -> u32 {
+        (WAVE_INTERVAL / dt).round() as u32
+    }
+
+    pub fn wave_size(wave: u32) -> usize {
+        2 * wave as usize
+    }
+
+    fn spawn_wave(&mut self, sim: &mut Simulation) {
+        self.wave += 1;
+        let radius = sim.world_size() * 0.4;
+        for _ in 0..Self::wave_size(self.wave) {
+            let angle = self.find_clear_angle(sim, radius);
+            let position = Rotation2::new(angle) * vector![radius, 0.0];
+            ship::create(sim, position, vector![0.0, 0.0], angle + PI, fighter(1));
+        }
+    }
+
+    /// Picks a spawn point on the ring that isn't too close to another ship,
+    /// giving up after a fixed number of tries.
+    fn find_clear_angle(&mut self, sim: &Simulation, radius: f64) -> f64 {
+        let min_distance = 500.0;
+        let mut angle = 0.0;
+        for _ in 0..100 {
+            angle = self.rng.gen_range(0.0..TAU);
+            let position = Rotation2::new(angle) * vector![radius, 0.0];
+            let clear = sim.ships.iter().all(|&handle| {
+                (sim.ship(handle).position().vector - position).magnitude() > min_distance
+            });
+            if clear {
+                break;
+            }
+        }
+        angle
+    }
+
+    fn combat_ships(sim: &Simulation, team: i32) -> usize {
+        sim.ships
+            .iter()
+            .filter(|&&handle| {
+                let ship = sim.ship(handle);
+                let data = ship.data();
+                data.team == team
+                    && [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser]
+                        .contains(&data.class)
+            })
+            .count()
+    }
+}
+
+impl Scenario for Survival {
+    fn name(&self) -> String {
+        "survival".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Survival".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        self.rng = new_rng(seed);
+        let heading = TAU / 4.0;
+        ship::create(
+            sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            heading,
+            frigate(0),
+        );
+        for x in [-500.0, 500.0] {
+            ship::create(sim, vector![x, 0.0], vector![0.0, 0.0], heading, fighter(0));
+        }
+        self.spawn_wave(sim);
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
+        let tick = sim.tick();
+        if tick > 0 && tick % Self::wave_interval_ticks(sim.dt()) == 0 && self.wave < MAX_WAVES {
+            self.spawn_wave(sim);
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        if Self::combat_ships(sim, 0) == 0 {
+            Status::Victory { team: 1 }
+        } else if self.wave >= MAX_WAVES && Self::combat_ships(sim, 1) == 0 {
+            Status::Victory { team: 0 }
+        } else {
+            Status::Running
+        }
+    }
+
+    /// The defending team's score is the current wave number.
+    fn team_scores(&self) -> BTreeMap<i32, f64> {
+        BTreeMap::from([(0, self.wave as f64)])
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+}
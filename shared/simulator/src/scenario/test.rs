@@ -110,6 +110,7 @@ impl Scenario for MissileTest {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         let target = self.target.unwrap();
         if !sim.ships.contains(target) && self.current_iteration < MissileTest::MAX_ITERATIONS {
             self.current_iteration += 1;
@@ -298,6 +299,7 @@ impl Scenario for RadarTest {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         if sim.tick() > 120 && sim.tick() % 120 == 0 {
             let handles: Vec<_> = sim.ships.iter().cloned().collect();
             for handle in handles {
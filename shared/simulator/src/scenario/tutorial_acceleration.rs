@@ -33,6 +33,7 @@ impl Scenario for TutorialAcceleration {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         if let Some(&handle) = sim.ships.iter().next() {
             let ship = sim.ship(handle);
             if (ship.position().vector - Self::TARGET).magnitude() < 50.0 {
@@ -41,6 +41,7 @@ impl Scenario for TutorialAcceleration2 {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         if let Some(&handle) = sim.ships.iter().next() {
             let ship = sim.ship(handle);
             if (ship.position().vector - self.target.unwrap().coords).magnitude() < 50.0 {
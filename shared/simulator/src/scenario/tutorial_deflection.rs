@@ -50,6 +50,7 @@ impl Scenario for TutorialDeflection {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         if sim.ships.len() < 2 {
             return;
         }
@@ -52,6 +52,7 @@ impl Scenario for Welcome {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
+        sim.apply_sudden_death();
         let rng = self.rng.as_mut().unwrap();
         let asteroid_variants = [1, 6, 14];
         let num_asteroids = sim
@@ -6,15 +6,18 @@ use crate::radar::Radar;
 use crate::radio::Radio;
 use crate::rng;
 use crate::simulation::{self, PHYSICS_TICK_LENGTH};
-use crate::simulation::{Particle, Simulation};
+use crate::simulation::{
+    ExplosionEffect, Kill, Particle, ShipFilter, SimEvent, Simulation, TeamFilter,
+};
+use crate::vm::CompiledAi;
 use crate::{bullet, collision};
-use bullet::BulletData;
+use bullet::{BulletData, BulletKind, DamageFalloff, DamageType};
 use nalgebra::{vector, Rotation2, UnitComplex, Vector2};
 use oort_api::Ability;
 use rand::Rng;
 use rapier2d_f64::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::f64::consts::TAU;
+use std::f64::consts::{PI, TAU};
 
 #[derive(Hash, PartialEq, Eq, Copy, Clone, Debug, Ord, PartialOrd)]
 pub struct ShipHandle(pub Index);
@@ -76,6 +79,48 @@ pub struct Gun {
     pub burst_size: i32,
     pub ttl: f32,
     pub bullet_mass: f64,
+    pub damage_type: DamageType,
+    pub falloff: DamageFalloff,
+    /// Effect applied to ships hit by this gun's bullets.
+    pub status_effect: Option<StatusEffect>,
+    /// Muzzles that each fire a bullet per shot. If empty the gun fires a
+    /// single bullet from `offset`.
+    pub hardpoints: Vec<Hardpoint>,
+    /// Heat added per shot. The gun jams when `heat` reaches 1.0. Zero
+    /// disables overheating.
+    pub heat_per_shot: f64,
+    /// Heat dissipated per second.
+    pub cooling_rate: f64,
+    pub heat: f64,
+    /// Ticks the gun can't fire after jamming, regardless of heat.
+    pub jam_ticks: u32,
+    pub jam_ticks_remaining: u32,
+    /// Times this gun's bullets bounce off walls before being destroyed.
+    pub max_bounces: u32,
+    /// Collider radius of this gun's bullets.
+    pub bullet_radius: f64,
+    pub bullet_kind: BulletKind,
+    /// Rounds left to fire, including the loaded magazine. `None` means
+    /// unlimited.
+    pub ammo: Option<i32>,
+}
+
+impl Gun {
+    pub fn hardpoint_count(&self) -> usize {
+        self.hardpoints.len().max(1)
+    }
+
+    pub fn is_jammed(&self) -> bool {
+        self.jam_ticks_remaining > 0
+    }
+}
+
+/// A gun muzzle, in ship-local coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hardpoint {
+    pub offset: Vector2<f64>,
+    /// Angle added to the gun heading, e.g. to make muzzles converge.
+    pub angle: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -88,6 +133,37 @@ pub struct MissileLauncher {
     pub angle: f64,
 }
 
+/// A hitscan energy weapon, see [`ShipAccessorMut::fire_beam`].
+#[derive(Debug, Clone)]
+pub struct Beam {
+    /// Maximum length of the beam.
+    pub range: f64,
+    /// Damage per second dealt to the ship the beam is held on.
+    pub damage_per_second: f64,
+    pub energy: f64,
+    pub max_energy: f64,
+    /// Energy drained per second while firing.
+    pub energy_per_second: f64,
+    /// Energy recovered per second while not firing.
+    pub recharge_per_second: f64,
+    /// Heading and range requested for this tick.
+    pub(crate) command: Option<(f64, f64)>,
+}
+
+impl Default for Beam {
+    fn default() -> Self {
+        Self {
+            range: 2000.0,
+            damage_per_second: 30.0,
+            energy: 100.0,
+            max_energy: 100.0,
+            energy_per_second: 20.0,
+            recharge_per_second: 10.0,
+            command: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ShipAbility {
     pub ability: Ability,
@@ -111,6 +187,11 @@ pub struct ShipData {
     pub class: ShipClass,
     pub team: i32,
     pub health: f64,
+    /// Health restored to by repairs. Set to the initial health by [`create`] if zero.
+    pub max_health: f64,
+    pub shield: f64,
+    pub max_shield: f64,
+    pub shield_recharge_ticks_remaining: u32,
     pub mass: f64,
     pub acceleration: Vector2<f64>,
     pub last_acceleration: Vector2<f64>,
@@ -122,14 +203,242 @@ pub struct ShipData {
     pub destroyed: bool,
     pub ttl: Option<u64>,
     pub fuel: Option<f64>,
+    /// Fuel restored to by repairs. Set to the initial fuel by [`create`] if unset.
+    pub max_fuel: Option<f64>,
     pub guns: Vec<Gun>,
     pub missile_launchers: Vec<MissileLauncher>,
+    pub beam: Option<Beam>,
     pub radar: Option<Radar>,
     pub radar_cross_section: f64,
+    /// How `radar_cross_section` varies with the angle it's viewed from.
+    pub radar_aspect: RadarAspect,
     pub radios: Vec<Radio>,
     pub abilities: Vec<ShipAbility>,
     pub target: Option<Box<Target>>,
     pub warhead: Warhead,
+    /// Explodes when an enemy ship comes within this distance, see
+    /// [`ShipAccessorMut::explode`].
+    pub proximity_fuze: Option<f64>,
+    pub collider: ColliderShape,
+    pub resistance: DamageResistance,
+    /// Turns the ship to face along its velocity on ticks without a
+    /// commanded torque.
+    pub spin_stabilized: bool,
+    pub status_effects: Vec<StatusEffect>,
+    /// Running average of the ship's acceleration (m/s²), see
+    /// [`THERMAL_TIME_CONSTANT`].
+    pub thermal_signature: f64,
+    /// Fraction of `radar_cross_section` added per m/s² of
+    /// `thermal_signature`. Zero makes engine heat invisible to radar.
+    pub thermal_gain: f64,
+    /// Runs the ship's AI only every this many ticks, repeating its last
+    /// acceleration and torque in between. Larger values make for slower,
+    /// easier enemies.
+    pub think_interval: u32,
+    /// Largest velocity change (m/s) from a single
+    /// [`impulse`](ShipAccessorMut::impulse). Zero means the ship has no
+    /// impulse drive.
+    pub max_impulse_delta_v: f64,
+    pub impulse_cooldown_ticks_remaining: u32,
+    /// The ship is disabled while its health is at or below this, see
+    /// [`ShipData::is_disabled`]. Zero means it is never disabled.
+    pub disable_threshold: f64,
+}
+
+/// Time (in seconds) for the thermal signature to settle after a change in
+/// thrust.
+pub const THERMAL_TIME_CONSTANT: f64 = 1.0;
+
+impl ShipData {
+    /// Adds `effect`, replacing an existing effect of the same kind if the new
+    /// one lasts longer.
+    pub fn apply_status_effect(&mut self, effect: StatusEffect) {
+        match self
+            .status_effects
+            .iter_mut()
+            .find(|e| std::mem::discriminant(*e) == std::mem::discriminant(&effect))
+        {
+            Some(existing) => {
+                if effect.ticks_left() >= existing.ticks_left() {
+                    *existing = effect;
+                }
+            }
+            None => self.status_effects.push(effect),
+        }
+    }
+
+    /// Returns true if the ship's engines and weapons are offline.
+    ///
+    /// A disabled ship stays in the simulation and keeps colliding, and can
+    /// still be destroyed by further damage. Repairing it above
+    /// `disable_threshold` brings it back online.
+    pub fn is_disabled(&self) -> bool {
+        !self.destroyed && self.health <= self.disable_threshold
+    }
+
+    pub fn is_burning(&self) -> bool {
+        self.status_effects
+            .iter()
+            .any(|e| matches!(e, StatusEffect::Burning { .. }))
+    }
+}
+
+/// Temporary effects that act on a ship every tick until they expire.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusEffect {
+    /// Deals `dps` damage per second, credited to `source`.
+    Burning {
+        dps: f64,
+        ticks_left: u32,
+        source: Option<ShipHandle>,
+    },
+}
+
+impl StatusEffect {
+    pub fn ticks_left(&self) -> u32 {
+        match self {
+            StatusEffect::Burning { ticks_left, .. } => *ticks_left,
+        }
+    }
+
+    /// Returns the effect credited to `source`.
+    pub fn with_source(self, source: Option<ShipHandle>) -> StatusEffect {
+        match self {
+            StatusEffect::Burning {
+                dps, ticks_left, ..
+            } => StatusEffect::Burning {
+                dps,
+                ticks_left,
+                source,
+            },
+        }
+    }
+}
+
+/// Multipliers applied to the radar cross-section depending on aspect.
+///
+/// Viewed along the ship's heading (from the nose or tail) the return is
+/// scaled by `head_on`, and from the side by `broadside`, blending smoothly in
+/// between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadarAspect {
+    pub head_on: f64,
+    pub broadside: f64,
+}
+
+impl RadarAspect {
+    /// Returns the multiplier for an angle between the viewing direction
+    /// and the ship's heading.
+    pub fn factor(&self, angle: f64) -> f64 {
+        let s = angle.sin();
+        self.head_on + (self.broadside - self.head_on) * s * s
+    }
+
+    pub fn is_uniform(&self) -> bool {
+        self.head_on == self.broadside
+    }
+}
+
+impl Default for RadarAspect {
+    fn default() -> Self {
+        Self {
+            head_on: 1.0,
+            broadside: 1.0,
+        }
+    }
+}
+
+/// Multipliers applied to incoming damage of each type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DamageResistance {
+    pub kinetic: f64,
+    pub energy: f64,
+}
+
+impl DamageResistance {
+    pub fn multiplier(&self, damage_type: DamageType) -> f64 {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+        }
+    }
+}
+
+impl Default for DamageResistance {
+    fn default() -> Self {
+        Self {
+            kinetic: 1.0,
+            energy: 1.0,
+        }
+    }
+}
+
+/// Shape used for collisions, in the ship's frame of reference (x is forward).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ColliderShape {
+    /// Convex hull of the class's model.
+    #[default]
+    Model,
+    Circle {
+        radius: f64,
+    },
+    Rectangle {
+        length: f64,
+        width: f64,
+    },
+    /// Convex hull of the given vertices.
+    Polygon(Vec<Vector2<f64>>),
+}
+
+impl ColliderShape {
+    fn vertices(&self, class: ShipClass) -> Vec<Point<Real>> {
+        match self {
+            ColliderShape::Model => model::load(class)
+                .iter()
+                .map(|&v| point![v.x as f64, v.y as f64])
+                .collect(),
+            ColliderShape::Circle { .. } => vec![],
+            ColliderShape::Rectangle { length, width } => {
+                let (x, y) = (length / 2.0, width / 2.0);
+                vec![point![x, y], point![-x, y], point![-x, -y], point![x, -y]]
+            }
+            ColliderShape::Polygon(vertices) => vertices.iter().map(|&v| v.into()).collect(),
+        }
+    }
+
+    fn builder(&self, class: ShipClass) -> ColliderBuilder {
+        match self {
+            ColliderShape::Circle { radius } => ColliderBuilder::ball(*radius),
+            ColliderShape::Rectangle { length, width } => {
+                ColliderBuilder::cuboid(length / 2.0, width / 2.0)
+            }
+            _ => ColliderBuilder::convex_hull(&self.vertices(class))
+                .expect("collider shape must have at least three non-collinear vertices"),
+        }
+    }
+
+    /// Distance from the ship's center to its furthest point.
+    pub fn radius(&self, class: ShipClass) -> f64 {
+        match self {
+            ColliderShape::Circle { radius } => *radius,
+            _ => self
+                .vertices(class)
+                .iter()
+                .map(|p| p.coords.norm())
+                .fold(0.0, f64::max),
+        }
+    }
+}
+
+impl ShipData {
+    /// Replaces the collider, scaling the radar cross section with the square
+    /// of the change in radius.
+    pub fn with_collider(mut self, collider: ColliderShape) -> ShipData {
+        let scale = collider.radius(self.class) / self.collider.radius(self.class);
+        self.radar_cross_section *= scale * scale;
+        self.collider = collider;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -138,12 +447,41 @@ pub struct Target {
     pub velocity: Vector2<f64>,
 }
 
+/// Commands for a single tick, equivalent to what a ship AI writes to its system state.
+#[derive(Debug, Clone)]
+pub struct ShipAction {
+    /// Linear acceleration in the ship's frame of reference (x is forward).
+    pub acceleration: Vector2<f64>,
+    pub torque: f64,
+    pub radar_heading: Option<f64>,
+    pub radar_width: Option<f64>,
+    pub aim: [f64; 4],
+    pub fire: [bool; 4],
+}
+
+impl Default for ShipAction {
+    fn default() -> Self {
+        Self {
+            acceleration: vector![0.0, 0.0],
+            torque: 0.0,
+            radar_heading: None,
+            radar_width: None,
+            aim: [0.0; 4],
+            fire: [false; 4],
+        }
+    }
+}
+
 impl Default for ShipData {
     fn default() -> ShipData {
         ShipData {
             class: ShipClass::Fighter,
             team: 0,
             health: 100.0,
+            max_health: 0.0,
+            shield: 0.0,
+            max_shield: 0.0,
+            shield_recharge_ticks_remaining: 0,
             mass: 1000.0,
             acceleration: vector![0.0, 0.0],
             last_acceleration: vector![0.0, 0.0],
@@ -155,14 +493,28 @@ impl Default for ShipData {
             destroyed: false,
             ttl: None,
             fuel: None,
+            max_fuel: None,
             guns: vec![],
             missile_launchers: vec![],
+            beam: None,
             radar: None,
             radar_cross_section: 10.0,
+            radar_aspect: Default::default(),
             radios: vec![],
             abilities: vec![],
             target: None,
             warhead: Default::default(),
+            proximity_fuze: None,
+            collider: ColliderShape::Model,
+            resistance: Default::default(),
+            spin_stabilized: false,
+            status_effects: vec![],
+            thermal_signature: 0.0,
+            thermal_gain: 0.0,
+            think_interval: 1,
+            max_impulse_delta_v: 0.0,
+            impulse_cooldown_ticks_remaining: 0,
+            disable_threshold: 0.0,
         }
     }
 }
@@ -185,6 +537,19 @@ impl Default for Gun {
             burst_size: 1,
             ttl: 10.0,
             bullet_mass: 1.0,
+            damage_type: DamageType::Kinetic,
+            falloff: DamageFalloff::None,
+            status_effect: None,
+            hardpoints: vec![],
+            heat_per_shot: 0.0,
+            cooling_rate: 0.0,
+            heat: 0.0,
+            jam_ticks: 0,
+            jam_ticks_remaining: 0,
+            max_bounces: 0,
+            bullet_radius: bullet::DEFAULT_RADIUS,
+            bullet_kind: BulletKind::Kinetic,
+            ammo: None,
         }
     }
 }
@@ -237,18 +602,76 @@ pub fn vulcan_gun() -> Gun {
     }
 }
 
+/// Tunable numbers for the stock ship constructors, see [`fighter_with`] and
+/// [`missile_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShipStats {
+    pub health: f64,
+    pub mass: f64,
+    pub max_forward_acceleration: f64,
+    pub max_backward_acceleration: f64,
+    pub max_lateral_acceleration: f64,
+    pub max_angular_acceleration: f64,
+    pub radar_power: f64,
+    pub radar_rx_cross_section: f64,
+    /// Ticks between shots, applied to every gun.
+    pub gun_reload_ticks: u32,
+    /// Bullet speed in m/s, applied to every gun.
+    pub bullet_speed: f64,
+}
+
+impl ShipStats {
+    pub fn fighter() -> Self {
+        let gun = vulcan_gun();
+        Self {
+            health: 100.0,
+            mass: 15000.0,
+            max_forward_acceleration: 60.0,
+            max_backward_acceleration: 30.0,
+            max_lateral_acceleration: 30.0,
+            max_angular_acceleration: TAU,
+            radar_power: 20e3,
+            radar_rx_cross_section: 5.0,
+            gun_reload_ticks: gun.reload_ticks,
+            bullet_speed: gun.speed,
+        }
+    }
+
+    /// Missiles have no guns, so the gun stats are unused.
+    pub fn missile() -> Self {
+        Self {
+            health: 20.0,
+            mass: 150.0,
+            max_forward_acceleration: 300.0,
+            max_backward_acceleration: 0.0,
+            max_lateral_acceleration: 100.0,
+            max_angular_acceleration: 4.0 * TAU,
+            radar_power: 1e3,
+            radar_rx_cross_section: 3.0,
+            gun_reload_ticks: 0,
+            bullet_speed: 0.0,
+        }
+    }
+}
+
 pub fn fighter(team: i32) -> ShipData {
+    fighter_with(team, ShipStats::fighter())
+}
+
+pub fn fighter_with(team: i32, stats: ShipStats) -> ShipData {
     ShipData {
         class: ShipClass::Fighter,
         team,
-        health: 100.0,
-        mass: 15000.0,
-        max_forward_acceleration: 60.0,
-        max_backward_acceleration: 30.0,
-        max_lateral_acceleration: 30.0,
-        max_angular_acceleration: TAU,
+        health: stats.health,
+        mass: stats.mass,
+        max_forward_acceleration: stats.max_forward_acceleration,
+        max_backward_acceleration: stats.max_backward_acceleration,
+        max_lateral_acceleration: stats.max_lateral_acceleration,
+        max_angular_acceleration: stats.max_angular_acceleration,
         guns: vec![Gun {
             offset: vector![20.0, 0.0],
+            reload_ticks: stats.gun_reload_ticks,
+            speed: stats.bullet_speed,
             ..vulcan_gun()
         }],
         missile_launchers: vec![MissileLauncher {
@@ -260,8 +683,8 @@ pub fn fighter(team: i32) -> ShipData {
             angle: 0.0,
         }],
         radar: Some(Radar {
-            power: 20e3,
-            rx_cross_section: 5.0,
+            power: stats.radar_power,
+            rx_cross_section: stats.radar_rx_cross_section,
             min_width: TAU / 720.0,
             ..Default::default()
         }),
@@ -273,6 +696,7 @@ pub fn fighter(team: i32) -> ShipData {
             reload_time: 10.0,
             ..Default::default()
         }],
+        max_impulse_delta_v: 100.0,
         ..Default::default()
     }
 }
@@ -330,6 +754,15 @@ pub fn frigate(team: i32) -> ShipData {
 }
 
 pub const CRUISER_RADAR_CROSS_SECTION: f64 = 40.0;
+/// Ticks after being hit before the shield starts recharging.
+pub const SHIELD_RECHARGE_DELAY_TICKS: u32 = 120;
+/// Seconds for a shield to recharge from empty to full.
+pub const SHIELD_RECHARGE_TIME: f64 = 10.0;
+/// Ticks after an [`impulse`](ShipAccessorMut::impulse) before the ship can
+/// apply another.
+pub const IMPULSE_COOLDOWN_TICKS: u32 = 600;
+// Inverse of the time constant (s) for the final approach to the target heading.
+const SPIN_STABILIZATION_GAIN: f64 = 10.0;
 
 pub fn cruiser(team: i32) -> ShipData {
     let missile_launcher = MissileLauncher {
@@ -432,18 +865,22 @@ pub fn target(team: i32) -> ShipData {
 }
 
 pub fn missile(team: i32) -> ShipData {
+    missile_with(team, ShipStats::missile())
+}
+
+pub fn missile_with(team: i32, stats: ShipStats) -> ShipData {
     ShipData {
         class: ShipClass::Missile,
         team,
-        health: 20.0,
-        mass: 150.0,
-        max_forward_acceleration: 300.0,
-        max_backward_acceleration: 0.0,
-        max_lateral_acceleration: 100.0,
-        max_angular_acceleration: 4.0 * TAU,
+        health: stats.health,
+        mass: stats.mass,
+        max_forward_acceleration: stats.max_forward_acceleration,
+        max_backward_acceleration: stats.max_backward_acceleration,
+        max_lateral_acceleration: stats.max_lateral_acceleration,
+        max_angular_acceleration: stats.max_angular_acceleration,
         radar: Some(Radar {
-            power: 1e3,
-            rx_cross_section: 3.0,
+            power: stats.radar_power,
+            rx_cross_section: stats.radar_rx_cross_section,
             ..Default::default()
         }),
         radar_cross_section: 0.1,
@@ -503,6 +940,21 @@ pub fn torpedo(team: i32) -> ShipData {
     }
 }
 
+/// Like [`create`] but the ship runs `ai` instead of its team's AI, e.g. a
+/// scripted boss among minions.
+pub fn create_with_ai(
+    sim: &mut Simulation,
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+    heading: f64,
+    data: ShipData,
+    ai: &CompiledAi,
+) -> ShipHandle {
+    let handle = create(sim, position, velocity, heading, data);
+    sim.set_ship_ai(handle, ai);
+    handle
+}
+
 pub fn create(
     sim: &mut Simulation,
     position: Vector2<f64>,
@@ -514,6 +966,8 @@ pub fn create(
         .translation(position)
         .linvel(velocity)
         .rotation(heading)
+        .linear_damping(sim.linear_damping)
+        .angular_damping(sim.angular_damping)
         .ccd_enabled(true);
     if data.class == ShipClass::Planet {
         builder = builder.lock_translations()
@@ -522,23 +976,19 @@ pub fn create(
     let body_handle = sim.bodies.insert(rigid_body);
     let handle = ShipHandle(body_handle.0);
     let team = data.team;
-    let model = model::load(data.class);
     let restitution = match data.class {
         ShipClass::Missile => 0.0,
         _ => 0.1,
     };
-    let vertices = model
-        .iter()
-        .map(|&v| point![v.x as f64, v.y as f64])
-        .collect::<Vec<_>>();
-    let collider = ColliderBuilder::convex_hull(&vertices)
-        .unwrap()
+    let collider = data
+        .collider
+        .builder(data.class)
         .mass(data.mass)
         .restitution(restitution)
         .collision_groups(if data.class == ShipClass::Planet {
             collision::planet_interaction_groups()
         } else {
-            collision::ship_interaction_groups(team)
+            collision::ship_interaction_groups(team, collision::DEFAULT_SHIP_COLLISION_MASK)
         })
         .active_events(ActiveEvents::COLLISION_EVENTS)
         .build();
@@ -548,8 +998,24 @@ pub fn create(
     for gun in data.guns.iter_mut() {
         gun.magazine_remaining = gun.magazine_size;
     }
+    if data.max_health == 0.0 {
+        data.max_health = data.health;
+    }
+    if data.max_fuel.is_none() {
+        data.max_fuel = data.fuel;
+    }
 
+    let tick = sim.tick();
+    sim.log_event(SimEvent::ShipCreated {
+        tick,
+        ship: handle,
+        team,
+        class: data.class,
+        position,
+    });
     sim.ships.insert(handle);
+    sim.invalidate_ship_grid();
+    *sim.ship_counts.entry(data.team).or_default() += 1;
     sim.new_ships.push((data.team, handle));
     sim.ship_data.insert(handle.index(), data);
 
@@ -589,18 +1055,67 @@ impl<'a> ShipAccessor<'a> {
         self.body().angvel()
     }
 
+    /// Returns the direction from the ship to a world point, in the same
+    /// range as [`heading`](Self::heading). On a torus this is the direction
+    /// of the shortest path.
+    pub fn heading_to(&self, target: Vector2<f64>) -> Real {
+        let dp = self
+            .simulation
+            .shortest_displacement(self.position().vector, target);
+        dp.y.atan2(dp.x).rem_euclid(TAU)
+    }
+
     pub fn data(&self) -> &ShipData {
         self.simulation.ship_data.get(self.handle.index()).unwrap()
     }
 
+    pub fn radius(&self) -> f64 {
+        self.data().collider.radius(self.data().class)
+    }
+
     pub fn radar(&self) -> Option<&Radar> {
         self.data().radar.as_ref()
     }
 
+    /// Returns the ship this ship's radar is locked on to, see
+    /// [`Radar::lock_target`].
+    pub fn lock_target(&self) -> Option<ShipHandle> {
+        self.radar()
+            .and_then(Radar::lock_target)
+            .filter(|&handle| self.simulation.ships.contains(handle))
+    }
+
     pub fn radio(&self, idx: usize) -> Option<&Radio> {
         self.data().radios.get(idx)
     }
 
+    /// Returns the closest other ship on the same team within
+    /// [`oort_api::DATALINK_RANGE`].
+    pub fn nearest_friendly(&self) -> Option<ShipHandle> {
+        let filter = ShipFilter {
+            team: TeamFilter::Same(self.data().team),
+            exclude: Some(self.handle),
+            ..Default::default()
+        };
+        self.simulation
+            .nearest_ship(self.position().vector.into(), filter)
+            .filter(|&(_, distance)| distance <= oort_api::DATALINK_RANGE)
+            .map(|(other, _)| other)
+    }
+
+    /// Returns true if [`impulse`](ShipAccessorMut::impulse) would take effect.
+    pub fn impulse_ready(&self) -> bool {
+        self.data().max_impulse_delta_v > 0.0
+            && self.data().impulse_cooldown_ticks_remaining == 0
+            && self.data().fuel.map_or(true, |fuel| fuel > 0.0)
+            && !self.is_disabled()
+    }
+
+    /// See [`ShipData::is_disabled`].
+    pub fn is_disabled(&self) -> bool {
+        self.data().is_disabled()
+    }
+
     pub fn is_ability_active(&self, ability: oort_api::Ability) -> bool {
         self.data()
             .abilities
@@ -610,6 +1125,10 @@ impl<'a> ShipAccessor<'a> {
             .unwrap_or(false)
     }
 
+    pub fn shield_up(&self) -> bool {
+        self.data().shield > 0.0
+    }
+
     pub fn active_abilities(&self) -> Vec<oort_api::Ability> {
         self.data()
             .abilities
@@ -619,9 +1138,10 @@ impl<'a> ShipAccessor<'a> {
             .collect()
     }
 
+    /// Ticks until the weapon can fire again, including any jam.
     pub fn get_reload_ticks(&self, idx: usize) -> u32 {
         if let Some(gun) = self.data().guns.get(idx) {
-            gun.reload_ticks_remaining
+            gun.reload_ticks_remaining.max(gun.jam_ticks_remaining)
         } else if let Some(missile) = self
             .data()
             .missile_launchers
@@ -632,6 +1152,53 @@ impl<'a> ShipAccessor<'a> {
             0
         }
     }
+
+    /// Returns the force (in N, world frame) the acceleration commanded so
+    /// far this tick will apply, before impulse and fuel limits. Reset to zero
+    /// each time the ship ticks.
+    pub fn commanded_force(&self) -> Vector2<f64> {
+        let body = self.body();
+        body.position().rotation * self.data().acceleration * body.mass()
+    }
+
+    /// Returns the torque (in N·m) commanded so far this tick. Reset to zero
+    /// each time the ship ticks.
+    pub fn commanded_torque(&self) -> f64 {
+        let inertia_sqrt = 1.0
+            / self
+                .body()
+                .mass_properties()
+                .local_mprops
+                .inv_principal_inertia_sqrt;
+        self.data().angular_acceleration * inertia_sqrt * inertia_sqrt
+    }
+
+    pub fn is_jammed(&self, idx: usize) -> bool {
+        self.data()
+            .guns
+            .get(idx)
+            .map_or(false, |gun| gun.is_jammed())
+    }
+
+    /// Speed of bullets fired by a gun relative to the ship, before
+    /// `speed_error`. Zero if the weapon isn't a gun.
+    pub fn gun_muzzle_velocity(&self, idx: usize) -> f64 {
+        self.data().guns.get(idx).map_or(0.0, |gun| gun.speed)
+    }
+
+    /// Lifetime (in seconds) of bullets fired by a gun. Zero if the weapon
+    /// isn't a gun.
+    pub fn gun_bullet_ttl(&self, idx: usize) -> f64 {
+        self.data().guns.get(idx).map_or(0.0, |gun| gun.ttl as f64)
+    }
+
+    /// Ticks left before a jammed gun can fire again. Zero if not jammed.
+    pub fn weapon_cooldown_remaining(&self, idx: usize) -> u32 {
+        self.data()
+            .guns
+            .get(idx)
+            .map_or(0, |gun| gun.jam_ticks_remaining)
+    }
 }
 
 pub struct ShipAccessorMut<'a> {
@@ -673,6 +1240,30 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         self.data_mut().radios.get_mut(idx)
     }
 
+    /// Instantly changes the ship's velocity by `delta_v`, in world
+    /// coordinates.
+    ///
+    /// The change is limited to `max_impulse_delta_v` and costs as much fuel
+    /// as thrusting to the same velocity. The ship then can't apply another
+    /// impulse for [`IMPULSE_COOLDOWN_TICKS`]. Returns false without effect,
+    /// leaving the cooldown untouched, if the impulse isn't ready.
+    pub fn impulse(&mut self, delta_v: Vector2<f64>) -> bool {
+        let magnitude = delta_v.norm();
+        if !self.readonly().impulse_ready() || magnitude == 0.0 || !magnitude.is_finite() {
+            return false;
+        }
+        let mut magnitude = magnitude.min(self.data().max_impulse_delta_v);
+        if let Some(fuel) = self.data_mut().fuel.as_mut() {
+            magnitude = magnitude.min(*fuel);
+            *fuel -= magnitude;
+        }
+        let mass = self.body().mass();
+        self.body()
+            .apply_impulse(delta_v.normalize() * magnitude * mass, true);
+        self.data_mut().impulse_cooldown_ticks_remaining = IMPULSE_COOLDOWN_TICKS;
+        true
+    }
+
     pub fn accelerate(&mut self, acceleration: Vector2<f64>) {
         let data = self.data();
         let clamped_acceleration = acceleration
@@ -695,6 +1286,9 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
     }
 
     pub fn fire(&mut self, index: i64) {
+        if self.data().is_disabled() {
+            return;
+        }
         let num_guns = self.data().guns.len() as i64;
         if index >= num_guns {
             self.launch_missile(index - num_guns);
@@ -705,16 +1299,26 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
 
     pub fn fire_gun(&mut self, index: i64) {
         let ship_data = self.data_mut();
-        if index as usize >= ship_data.guns.len() {
+        if index as usize >= ship_data.guns.len() || ship_data.is_disabled() {
             return;
         }
         let team = ship_data.team;
         let gun = {
             let gun = &mut ship_data.guns[index as usize];
-            if gun.reload_ticks_remaining > 0 {
+            if gun.reload_ticks_remaining > 0 || gun.is_jammed() || gun.ammo == Some(0) {
                 return;
             }
             gun.reload_ticks_remaining = gun.reload_ticks;
+            if let Some(ammo) = gun.ammo.as_mut() {
+                *ammo = (*ammo - gun.burst_size).max(0);
+            }
+            if gun.heat_per_shot > 0.0 {
+                gun.heat += gun.heat_per_shot;
+                if gun.heat >= 1.0 {
+                    gun.heat = 1.0;
+                    gun.jam_ticks_remaining = gun.jam_ticks;
+                }
+            }
             gun.magazine_remaining -= gun.burst_size;
             if gun.magazine_remaining <= 0 {
                 gun.magazine_remaining = gun.magazine_size;
@@ -728,45 +1332,153 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         let alpha = (gun.bullet_mass as f32).clamp(0.7, 1.0);
         let color = color::to_u32(vector![1.0, 1.0, 1.0, alpha]);
         let mut t = 0.0;
-        let dt = simulation::PHYSICS_TICK_LENGTH / gun.burst_size as f64;
+        let dt = self.simulation.dt() / gun.burst_size as f64;
 
         let relative_heading = (gun.heading - self.readonly().heading())
             .rem_euclid(TAU)
             .clamp(gun.min_angle, gun.max_angle);
 
+        let hardpoints = if gun.hardpoints.is_empty() {
+            vec![Hardpoint {
+                offset: gun.offset,
+                angle: 0.0,
+            }]
+        } else {
+            gun.hardpoints.clone()
+        };
+
         for _ in 0..gun.burst_size {
-            let relative_heading = if gun.inaccuracy > 0.0 {
-                relative_heading + rng.gen_range(-gun.inaccuracy..gun.inaccuracy)
-            } else {
-                relative_heading
-            };
-            let speed = if gun.speed_error > 0.0 {
-                gun.speed + rng.gen_range(-gun.speed_error..gun.speed_error)
-            } else {
-                gun.speed
-            };
-            let body = self.body();
-            let rot = body.position().rotation * UnitComplex::new(relative_heading);
-            let v = body.linvel() + rot.transform_vector(&vector![speed, 0.0]);
-            let p = body.position().translation.vector
-                + body.position().rotation.transform_vector(&gun.offset)
-                + v * t;
-            bullet::create(
-                self.simulation,
-                p,
-                v,
-                BulletData {
-                    mass: gun.bullet_mass as f32,
-                    team,
-                    color,
-                    ttl: gun.ttl + t as f32,
-                },
-            );
+            for hardpoint in hardpoints.iter() {
+                let relative_heading = if gun.inaccuracy > 0.0 {
+                    relative_heading
+                        + hardpoint.angle
+                        + rng.gen_range(-gun.inaccuracy..gun.inaccuracy)
+                } else {
+                    relative_heading + hardpoint.angle
+                };
+                let speed = if gun.speed_error > 0.0 {
+                    gun.speed + rng.gen_range(-gun.speed_error..gun.speed_error)
+                } else {
+                    gun.speed
+                };
+                let body = self.body();
+                let rot = body.position().rotation * UnitComplex::new(relative_heading);
+                let v = body.linvel() + rot.transform_vector(&vector![speed, 0.0]);
+                let p = body.position().translation.vector
+                    + body.position().rotation.transform_vector(&hardpoint.offset)
+                    + v * t;
+                bullet::create(
+                    self.simulation,
+                    p,
+                    v,
+                    BulletData {
+                        mass: gun.bullet_mass as f32,
+                        team,
+                        color,
+                        ttl: gun.ttl + t as f32,
+                        source: Some(self.handle),
+                        damage_type: gun.damage_type,
+                        falloff: gun.falloff,
+                        status_effect: gun.status_effect,
+                        max_bounces: gun.max_bounces,
+                        radius: gun.bullet_radius,
+                        kind: gun.bullet_kind,
+                        ..Default::default()
+                    },
+                );
+            }
             t += dt;
         }
+
+        let body = self.body();
+        let position = body.position().translation.vector
+            + body.position().rotation.transform_vector(&gun.offset);
+        let tick = self.simulation.tick();
+        self.simulation.log_event(SimEvent::ShotFired {
+            tick,
+            ship: self.handle,
+            gun: index as usize,
+            position,
+        });
+    }
+
+    /// Fires the ship's beam along the world-frame `heading` for this tick,
+    /// up to `range` meters or the beam's own range if shorter.
+    ///
+    /// The beam is resolved when the ship ticks. It damages the nearest ship
+    /// of another team along the ray and drains energy, and does nothing if
+    /// energy has run out.
+    pub fn fire_beam(&mut self, heading: f64, range: f64) {
+        let disabled = self.data().is_disabled();
+        if let Some(beam) = self.data_mut().beam.as_mut() {
+            if !disabled && range > 0.0 && heading.is_finite() {
+                beam.command = Some((heading, range.min(beam.range)));
+            }
+        }
+    }
+
+    fn tick_beam(&mut self) {
+        let dt = self.simulation.dt();
+        let Some(beam) = self.data_mut().beam.as_mut() else {
+            return;
+        };
+        let Some((heading, range)) = beam.command.take() else {
+            beam.energy =
+                (beam.energy + beam.recharge_per_second * dt).min(beam.max_energy);
+            return;
+        };
+        let cost = beam.energy_per_second * dt;
+        if beam.energy < cost {
+            return;
+        }
+        beam.energy -= cost;
+        let damage = beam.damage_per_second * dt;
+
+        let team = self.data().team;
+        let origin = self.body().position().translation.vector;
+        let ray = Ray::new(origin.into(), vector![heading.cos(), heading.sin()]);
+        let sim = &*self.simulation;
+        let hit = sim
+            .ships
+            .iter()
+            .filter(|&&other| sim.are_hostile(sim.ship(other).data().team, team))
+            .filter_map(|&other| {
+                sim.ship(other)
+                    .body()
+                    .colliders()
+                    .iter()
+                    .filter_map(|&collider| {
+                        let collider = sim.colliders.get(collider)?;
+                        collider
+                            .shape()
+                            .cast_ray(collider.position(), &ray, range, true)
+                    })
+                    .min_by(|a, b| a.total_cmp(b))
+                    .map(|distance| (other, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((target, _)) = hit {
+            let handle = self.handle;
+            let mut target = self.simulation.ship_mut(target);
+            let damage = damage * target.data().resistance.multiplier(DamageType::Energy);
+            target.damage(damage, Some(handle));
+        }
+        if self.simulation.is_debug_ship(self.handle) {
+            let length = hit.map_or(range, |(_, distance)| distance);
+            let line = simulation::Line {
+                a: origin.into(),
+                b: (origin + ray.dir * length).into(),
+                color: vector![1.0, 0.3, 0.3, 1.0],
+            };
+            self.simulation.emit_debug_lines(self.handle, vec![line]);
+        }
     }
 
     pub fn launch_missile(&mut self, index: i64) {
+        if self.data().is_disabled() {
+            return;
+        }
         let missile_launcher = {
             let ship_data = self.data_mut();
             if let Some(missile_launcher) =
@@ -812,16 +1524,69 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         gun.heading = heading;
     }
 
+    /// Deals `damage`, scaled by [`Simulation::set_damage_multiplier`], with
+    /// the shield absorbing what it can before the hull. When health runs out
+    /// the ship is marked destroyed and a [`Kill`] credited to `source`.
+    /// Returns whether it was destroyed.
+    pub(crate) fn damage(&mut self, damage: f64, source: Option<ShipHandle>) -> bool {
+        let damage = damage * self.simulation.damage_multiplier;
+        let destroyed = {
+            let data = self.data_mut();
+            let absorbed = damage.min(data.shield);
+            data.shield -= absorbed;
+            if data.max_shield > 0.0 {
+                data.shield_recharge_ticks_remaining = SHIELD_RECHARGE_DELAY_TICKS;
+            }
+            data.health -= damage - absorbed;
+            data.health <= 0.0
+        };
+        if !destroyed {
+            return false;
+        }
+        let sim = &mut *self.simulation;
+        sim.events.kills.push(Kill {
+            victim: self.handle,
+            victim_team: sim.ship(self.handle).data().team,
+            source,
+            source_alive: source
+                .map(|h| sim.ships.contains(h) && !sim.ship(h).data().destroyed)
+                .unwrap_or(false),
+        });
+        let dt = sim.dt();
+        for _ in 0..10 {
+            let rot = Rotation2::new(sim.rng.gen_range(0.0..TAU));
+            let v = rot.transform_vector(&vector![sim.rng.gen_range(0.0..200.0), 0.0]);
+            let p = sim.ship(self.handle).body().position().translation.vector
+                + v * sim.rng.gen_range(0.0..0.1);
+            let lifetime = (sim.ship(self.handle).data().mass.log2() * dt) as f32;
+            sim.events.particles.push(Particle {
+                position: p,
+                velocity: v,
+                color: vector![1.0, 1.0, 1.0, sim.rng.gen_range(0.5..1.0)],
+                lifetime,
+            });
+        }
+        self.data_mut().destroyed = true;
+        true
+    }
+
     pub fn explode(&mut self) {
         if self.data().destroyed {
             return;
         }
         self.data_mut().destroyed = true;
+        let position = self.body().position().translation.vector;
+        let tick = self.simulation.tick();
+        self.simulation.log_event(SimEvent::Explosion {
+            tick,
+            ship: Some(self.handle),
+            position,
+        });
 
         let warhead = self.data().warhead.clone();
         let team = self.data().team;
-        let p =
-            self.body().position().translation.vector - self.body().linvel() * PHYSICS_TICK_LENGTH;
+        let dt = self.simulation.dt();
+        let p = self.body().position().translation.vector - self.body().linvel() * dt;
         let mut rng = new_rng(0);
         for _ in 0..warhead.count {
             let color = vector![rng.gen_range(0.7..1.0), 0.5, 0.5, rng.gen_range(0.5..1.0)];
@@ -829,7 +1594,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                 * Rotation2::new(rng.gen_range((-warhead.width / 2.0)..(warhead.width / 2.0)));
             let speed = warhead.speed * 2.0 * rng.gen_range(0.0..1.0);
             let v = self.body().linvel() + rot.transform_vector(&vector![speed, 0.0]);
-            let offset = v * rng.gen_range(0.0..PHYSICS_TICK_LENGTH);
+            let offset = v * rng.gen_range(0.0..dt);
             bullet::create(
                 self.simulation,
                 p + offset,
@@ -839,6 +1604,9 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                     team,
                     color: color::to_u32(color),
                     ttl: warhead.ttl,
+                    source: Some(self.handle),
+                    damage_type: DamageType::Kinetic,
+                    ..Default::default()
                 },
             );
             self.simulation.events.particles.push(Particle {
@@ -851,6 +1619,10 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
     }
 
     pub fn activate_ability(&mut self, ability: oort_api::Ability) {
+        if self.data().is_disabled() {
+            return;
+        }
+        let dt = self.simulation.dt();
         if let Some(ship_ability) = self
             .data_mut()
             .abilities
@@ -860,7 +1632,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if ship_ability.reload_time_remaining > 0.0 {
                 return;
             }
-            ship_ability.active_time_remaining = ship_ability.active_time - PHYSICS_TICK_LENGTH;
+            ship_ability.active_time_remaining = ship_ability.active_time - dt;
             ship_ability.reload_time_remaining = ship_ability.reload_time;
         }
     }
@@ -876,7 +1648,28 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         }
     }
 
+    pub fn apply_action(&mut self, action: &ShipAction) {
+        self.accelerate(action.acceleration);
+        self.torque(action.torque);
+        for (i, (&aim, &fire)) in action.aim.iter().zip(action.fire.iter()).enumerate() {
+            if fire {
+                self.aim(i as i64, aim);
+                self.fire(i as i64);
+            }
+        }
+        if let Some(radar) = self.radar_mut() {
+            if let Some(heading) = action.radar_heading {
+                radar.set_heading(heading);
+            }
+            if let Some(width) = action.radar_width {
+                radar.set_width(width);
+            }
+        }
+    }
+
     pub fn tick(&mut self) {
+        let dt = self.simulation.dt();
+
         // Weapons.
         {
             let ship_data = self
@@ -888,6 +1681,10 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                 if gun.reload_ticks_remaining > 0 {
                     gun.reload_ticks_remaining -= 1;
                 }
+                if gun.jam_ticks_remaining > 0 {
+                    gun.jam_ticks_remaining -= 1;
+                }
+                gun.heat = (gun.heat - gun.cooling_rate * dt).max(0.0);
             }
 
             for missile_launcher in ship_data.missile_launchers.iter_mut() {
@@ -897,13 +1694,36 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             }
         }
 
+        // Shield.
+        {
+            let ship_data = self.data_mut();
+            if ship_data.shield_recharge_ticks_remaining > 0 {
+                ship_data.shield_recharge_ticks_remaining -= 1;
+            } else if ship_data.shield < ship_data.max_shield {
+                ship_data.shield = (ship_data.shield
+                    + ship_data.max_shield * dt / SHIELD_RECHARGE_TIME)
+                    .min(ship_data.max_shield);
+            }
+        }
+
+        // Impulse cooldown.
+        {
+            let ship_data = self.data_mut();
+            if ship_data.impulse_cooldown_ticks_remaining > 0 {
+                ship_data.impulse_cooldown_ticks_remaining -= 1;
+            }
+        }
+
         // Acceleration.
         {
             let mut acceleration = self.data().acceleration;
             if self.readonly().is_ability_active(Ability::Boost) {
                 acceleration += vector![100.0, 0.0];
             }
-            let fuel_consumption = (acceleration * PHYSICS_TICK_LENGTH).norm();
+            if self.data().is_disabled() {
+                acceleration = vector![0.0, 0.0];
+            }
+            let fuel_consumption = (acceleration * dt).norm();
             if let Some(fuel) = self.data_mut().fuel {
                 if fuel < fuel_consumption {
                     acceleration *= fuel / fuel_consumption;
@@ -919,6 +1739,29 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             self.body().add_force(inertial_acceleration * mass, true);
             self.data_mut().last_acceleration = inertial_acceleration;
             self.data_mut().acceleration = vector![0.0, 0.0];
+
+            let data = self.data_mut();
+            let alpha = 1.0 - (-dt / THERMAL_TIME_CONSTANT).exp();
+            data.thermal_signature += (acceleration.norm() - data.thermal_signature) * alpha;
+        }
+
+        // Spin stabilization.
+        if self.data().spin_stabilized && self.data().angular_acceleration == 0.0 {
+            let velocity = self.readonly().velocity();
+            if velocity.magnitude_squared() > 1e-6 {
+                let max = self.data().max_angular_acceleration;
+                let error = (velocity.y.atan2(velocity.x) - self.readonly().heading() + PI)
+                    .rem_euclid(TAU)
+                    - PI;
+                // Fastest angular velocity that can still stop at the target heading,
+                // switching to proportional control close to it to avoid overshoot.
+                let target_angular_velocity = (2.0 * max * error.abs())
+                    .sqrt()
+                    .min(error.abs() * SPIN_STABILIZATION_GAIN)
+                    * error.signum();
+                let angular_velocity = self.readonly().angular_velocity();
+                self.torque((target_angular_velocity - angular_velocity) / dt);
+            }
         }
 
         // Torque.
@@ -929,7 +1772,12 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                     .mass_properties()
                     .local_mprops
                     .inv_principal_inertia_sqrt;
-            let torque = self.data().angular_acceleration * inertia_sqrt * inertia_sqrt;
+            let angular_acceleration = if self.data().is_disabled() {
+                0.0
+            } else {
+                self.data().angular_acceleration
+            };
+            let torque = angular_acceleration * inertia_sqrt * inertia_sqrt;
             self.body().reset_torques(false);
             self.body().add_torque(torque, true);
             self.data_mut().angular_acceleration = 0.0;
@@ -945,36 +1793,103 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             }
         }
 
+        // Proximity fuze.
+        if let Some(radius) = self.data().proximity_fuze {
+            let filter = ShipFilter {
+                team: TeamFilter::Enemy(self.data().team),
+                exclude: Some(self.handle),
+                ..Default::default()
+            };
+            let triggered = self
+                .simulation
+                .nearest_ship(self.readonly().position().vector.into(), filter)
+                .map_or(false, |(_, distance)| distance <= radius);
+            if triggered {
+                self.explode();
+            }
+        }
+
+        self.tick_beam();
+
         // Special abilities.
         {
             for ship_ability in self.data_mut().abilities.iter_mut() {
                 ship_ability.active_time_remaining =
-                    (ship_ability.active_time_remaining - PHYSICS_TICK_LENGTH).max(0.0);
+                    (ship_ability.active_time_remaining - dt).max(0.0);
                 ship_ability.reload_time_remaining =
-                    (ship_ability.reload_time_remaining - PHYSICS_TICK_LENGTH).max(0.0);
+                    (ship_ability.reload_time_remaining - dt).max(0.0);
+            }
+        }
+
+        // Status effects.
+        if !self.data().status_effects.is_empty() {
+            let mut damage = vec![];
+            let ship_data = self.data_mut();
+            for effect in ship_data.status_effects.iter_mut() {
+                match effect {
+                    StatusEffect::Burning {
+                        dps,
+                        ticks_left,
+                        source,
+                    } => {
+                        damage.push((*dps * dt, *source));
+                        *ticks_left = ticks_left.saturating_sub(1);
+                    }
+                }
+            }
+            ship_data.status_effects.retain(|e| e.ticks_left() > 0);
+            for (amount, source) in damage {
+                if self.data().destroyed {
+                    break;
+                }
+                self.damage(amount, source);
             }
         }
 
         // Destruction.
         if self.data().destroyed {
-            if let Some(team_ctrl) = self.simulation.get_team_controller(self.data().team) {
-                team_ctrl.borrow_mut().remove_ship(self.handle);
-            }
-            self.simulation.ships.remove(self.handle);
-            self.simulation.bodies.remove(
-                RigidBodyHandle(self.handle.index()),
-                &mut self.simulation.island_manager,
-                &mut self.simulation.colliders,
-                &mut self.simulation.impulse_joints,
-                &mut self.simulation.multibody_joints,
-                /*remove_attached_colliders=*/ true,
-            );
-            self.simulation
-                .ship_data
-                .remove(self.handle.index(), ShipData::default());
+            let position = self.body().position().translation.vector;
+            let tick = self.simulation.tick();
+            self.simulation.log_event(SimEvent::ShipDestroyed {
+                tick,
+                ship: self.handle,
+                position,
+            });
+            let radius = self.readonly().radius();
+            self.simulation.events.explosions.push(ExplosionEffect {
+                position,
+                radius,
+                tick,
+            });
+            self.remove();
         }
     }
 
+    /// Removes the ship from the simulation without any destruction effects.
+    pub(crate) fn remove(&mut self) {
+        let team = self.data().team;
+        if let Some(ctrl) = self.simulation.ship_controller(self.handle, team) {
+            ctrl.borrow_mut().remove_ship(self.handle);
+        }
+        self.simulation.ship_controllers.remove(&self.handle);
+        self.simulation.frozen_ships.remove(&self.handle);
+        self.simulation.ships.remove(self.handle);
+        if let Some(count) = self.simulation.ship_counts.get_mut(&team) {
+            *count -= 1;
+        }
+        self.simulation.bodies.remove(
+            RigidBodyHandle(self.handle.index()),
+            &mut self.simulation.island_manager,
+            &mut self.simulation.colliders,
+            &mut self.simulation.impulse_joints,
+            &mut self.simulation.multibody_joints,
+            /*remove_attached_colliders=*/ true,
+        );
+        self.simulation
+            .ship_data
+            .remove(self.handle.index(), ShipData::default());
+    }
+
     pub fn handle_collision(&mut self) {
         if self.data().class == ShipClass::Missile || self.data().class == ShipClass::Torpedo {
             self.explode();
@@ -988,8 +1903,48 @@ mod test {
     use crate::simulation::Code;
     use crate::simulation::Simulation;
     use nalgebra::vector;
+    use std::f64::consts::{PI, TAU};
     use test_log::test;
 
+    #[test]
+    fn test_spin_stabilization() {
+        let heading_error = |spin_stabilized: bool| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let handle = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![500.0, 0.0],
+                0.3,
+                ship::ShipData {
+                    spin_stabilized,
+                    ..ship::missile(0)
+                },
+            );
+            sim.ship_mut(handle).body().set_angvel(1.0, true);
+            for _ in 0..120 {
+                sim.step();
+            }
+            let ship = sim.ship(handle);
+            let velocity = ship.velocity();
+            let error = (velocity.y.atan2(velocity.x) - ship.heading() + PI).rem_euclid(TAU) - PI;
+            (error.abs(), ship.angular_velocity().abs())
+        };
+
+        let (error, angular_velocity) = heading_error(true);
+        assert!(error < 0.01, "error={error}");
+        assert!(
+            angular_velocity < 0.01,
+            "angular_velocity={angular_velocity}"
+        );
+
+        let (error, angular_velocity) = heading_error(false);
+        assert!(error > 0.1, "error={error}");
+        assert!(
+            angular_velocity > 0.5,
+            "angular_velocity={angular_velocity}"
+        );
+    }
+
     #[test]
     fn test_gun_reload_ticks() {
         let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
@@ -1046,4 +2001,89 @@ mod test {
         sim.ship_mut(ship0).fire(1);
         assert_eq!(sim.ships.len(), 3);
     }
+
+    #[test]
+    fn test_rectangle_collider() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let data = ship::target(0).with_collider(ship::ColliderShape::Rectangle {
+            length: 100.0,
+            width: 20.0,
+        });
+        let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+
+        let body = sim.ship(ship0).body();
+        let collider = sim.colliders.get(body.colliders()[0]).unwrap();
+        let extents = collider.compute_aabb().half_extents();
+        approx::assert_relative_eq!(extents.x, 50.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(extents.y, 10.0, epsilon = 1e-6);
+        approx::assert_relative_eq!(
+            sim.ship(ship0).radius(),
+            (50.0f64.powi(2) + 10.0f64.powi(2)).sqrt(),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_with_collider_scales_radar_cross_section() {
+        let data = ship::fighter(0);
+        let radius = data.collider.radius(data.class);
+        let rcs = data.radar_cross_section;
+        let data = data.with_collider(ship::ColliderShape::Circle {
+            radius: radius * 2.0,
+        });
+        approx::assert_relative_eq!(data.radar_cross_section, rcs * 4.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::ShipData {
+                disable_threshold: 20.0,
+                ..ship::fighter(0)
+            },
+        );
+        let fly = |sim: &mut Simulation| {
+            let velocity = sim.ship(handle).velocity();
+            let bullets = sim.bullets.len();
+            for _ in 0..10 {
+                sim.ship_mut(handle).accelerate(vector![50.0, 0.0]);
+                sim.ship_mut(handle).torque(1.0);
+                sim.ship_mut(handle).fire(0);
+                sim.step();
+            }
+            (
+                sim.ship(handle).velocity() - velocity,
+                sim.bullets.len() > bullets,
+            )
+        };
+
+        assert!(!sim.ship(handle).is_disabled());
+        let (dv, fired) = fly(&mut sim);
+        assert!(dv.x > 0.0);
+        assert!(fired);
+
+        // Forces from the last enabled tick are applied by the next step.
+        sim.ship_mut(handle).data_mut().health = 20.0;
+        sim.step();
+        sim.ship_mut(handle).body().set_angvel(0.0, true);
+        let angular_velocity = sim.ship(handle).angular_velocity();
+        let (dv, fired) = fly(&mut sim);
+        assert!(sim.ship(handle).is_disabled());
+        assert!(dv.norm() < 1e-9, "{dv}");
+        assert_eq!(sim.ship(handle).angular_velocity(), angular_velocity);
+        assert!(!fired);
+        assert!(!sim.ship(handle).impulse_ready());
+        assert!(sim.ships.contains(handle));
+
+        // Further damage still destroys it.
+        sim.ship_mut(handle).data_mut().health = 0.0;
+        sim.ship_mut(handle).data_mut().destroyed = true;
+        sim.step();
+        assert!(!sim.ships.contains(handle));
+    }
 }
@@ -0,0 +1,182 @@
+use crate::ship::ShipHandle;
+use crate::simulation::Simulation;
+use nalgebra::{Point2, Vector2};
+use std::collections::HashMap;
+
+/// Uniform grid over ship positions, used by [`Simulation::nearest_ship`] and
+/// [`Simulation::ships_within`].
+///
+/// The simulation builds one on the first query after ships have moved and
+/// reuses it until the next physics step or until a ship is created. Cells
+/// wrap around the edges of a toroidal world.
+pub(crate) struct ShipGrid {
+    cells: HashMap<(i32, i32), Vec<Entry>>,
+    /// Number of cells along each axis.
+    dim: i32,
+    cell_size: f64,
+    wrap: bool,
+    /// Largest radius of any ship in the grid, so hulls reaching into a
+    /// neighbouring cell are still found.
+    max_radius: f64,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    handle: ShipHandle,
+    /// Position in `Simulation::ships` when the grid was built, so results
+    /// come out in iteration order.
+    order: usize,
+}
+
+impl ShipGrid {
+    const CELL_SIZE: f64 = 2000.0;
+
+    pub(crate) fn new(sim: &Simulation) -> Self {
+        let world_size = sim.world_size();
+        let dim = ((world_size / Self::CELL_SIZE).ceil() as i32).max(1);
+        let mut grid = Self {
+            cells: HashMap::new(),
+            dim,
+            cell_size: world_size / dim as f64,
+            wrap: sim.boundary() == crate::scenario::Boundary::Torus,
+            max_radius: 0.0,
+        };
+        for (order, &handle) in sim.ships.iter().enumerate() {
+            let ship = sim.ship(handle);
+            if ship.data().destroyed {
+                continue;
+            }
+            grid.max_radius = grid.max_radius.max(ship.radius());
+            grid.cells
+                .entry(grid.to_cell(ship.position().vector))
+                .or_default()
+                .push(Entry { handle, order });
+        }
+        grid
+    }
+
+    /// Positions outside the world are clamped into the edge cells, which
+    /// only ever brings cells closer together than the ships in them.
+    fn to_cell(&self, p: Vector2<f64>) -> (i32, i32) {
+        let half = self.cell_size * self.dim as f64 / 2.0;
+        let index = |x: f64| (((x + half) / self.cell_size).floor() as i32).clamp(0, self.dim - 1);
+        (index(p.x), index(p.y))
+    }
+
+    /// Distance in cells between two indices along one axis.
+    fn axis_distance(&self, a: i32, b: i32) -> i32 {
+        let d = (a - b).abs();
+        if self.wrap {
+            d.min(self.dim - d)
+        } else {
+            d
+        }
+    }
+
+    /// Indices along one axis within `k` cells of `c`, each listed once.
+    fn axis_range(&self, c: i32, k: i32) -> Vec<i32> {
+        if self.wrap {
+            if 2 * k + 1 >= self.dim {
+                (0..self.dim).collect()
+            } else {
+                (c - k..=c + k).map(|i| i.rem_euclid(self.dim)).collect()
+            }
+        } else {
+            ((c - k).max(0)..=(c + k).min(self.dim - 1)).collect()
+        }
+    }
+
+    /// Same contract as [`Simulation::nearest_ship`], with `matches`
+    /// standing in for the filter.
+    pub(crate) fn nearest(
+        &self,
+        sim: &Simulation,
+        from: Point2<f64>,
+        matches: impl Fn(ShipHandle) -> bool,
+    ) -> Option<(ShipHandle, f64)> {
+        let (cx, cy) = self.to_cell(from.coords);
+        let mut best: Option<(f64, usize, ShipHandle)> = None;
+        let check = |entries: &[Entry], best: &mut Option<(f64, usize, ShipHandle)>| {
+            for entry in entries {
+                if !sim.ships.contains(entry.handle) || !matches(entry.handle) {
+                    continue;
+                }
+                let distance_squared = sim
+                    .shortest_displacement(from.coords, sim.ship(entry.handle).position().vector)
+                    .norm_squared();
+                let better = best.map_or(true, |(d, order, _)| {
+                    (distance_squared, entry.order) < (d, order)
+                });
+                if better {
+                    *best = Some((distance_squared, entry.order, entry.handle));
+                }
+            }
+        };
+
+        // Search rings of cells outwards until no unvisited cell can contain
+        // anything closer. A ship in ring `r` is at least `r - 1` cells away.
+        for r in 0.. {
+            if let Some((distance_squared, _, _)) = best {
+                let bound = (r - 1) as f64 * self.cell_size;
+                if bound > 0.0 && bound * bound > distance_squared {
+                    break;
+                }
+            }
+            let side = 2 * r + 1;
+            if side * side > self.cells.len() as i32 || (self.wrap && side > self.dim) {
+                // Cheaper to look at every remaining occupied cell directly.
+                for (&(x, y), entries) in self.cells.iter() {
+                    if self.axis_distance(x, cx).max(self.axis_distance(y, cy)) >= r {
+                        check(entries, &mut best);
+                    }
+                }
+                break;
+            }
+            if r > self.dim {
+                break;
+            }
+            for x in self.axis_range(cx, r) {
+                for y in self.axis_range(cy, r) {
+                    if self.axis_distance(x, cx).max(self.axis_distance(y, cy)) != r {
+                        continue;
+                    }
+                    if let Some(entries) = self.cells.get(&(x, y)) {
+                        check(entries, &mut best);
+                    }
+                }
+            }
+        }
+        best.map(|(distance_squared, _, handle)| (handle, distance_squared.sqrt()))
+    }
+
+    /// Same contract as [`Simulation::ships_within`].
+    pub(crate) fn within(
+        &self,
+        sim: &Simulation,
+        center: Vector2<f64>,
+        radius: f64,
+    ) -> Vec<ShipHandle> {
+        let (cx, cy) = self.to_cell(center);
+        let k = ((radius + self.max_radius) / self.cell_size).ceil() as i32;
+        let mut found: Vec<Entry> = vec![];
+        for x in self.axis_range(cx, k) {
+            for y in self.axis_range(cy, k) {
+                let Some(entries) = self.cells.get(&(x, y)) else {
+                    continue;
+                };
+                found.extend(entries.iter().filter(|entry| {
+                    if !sim.ships.contains(entry.handle) {
+                        return false;
+                    }
+                    let ship = sim.ship(entry.handle);
+                    let distance = sim
+                        .shortest_displacement(center, ship.position().vector)
+                        .norm();
+                    !ship.data().destroyed && distance - ship.radius() <= radius
+                }));
+            }
+        }
+        found.sort_by_key(|entry| entry.order);
+        found.into_iter().map(|entry| entry.handle).collect()
+    }
+}
@@ -1,30 +1,42 @@
 use crate::bullet::{self, BulletData, BulletHandle};
 use crate::collision;
+use crate::debris;
 use crate::debug;
 pub use crate::debug::Line;
 use crate::index_set::{HasIndex, IndexSet};
+use crate::mine;
 use crate::radar;
 use crate::radio;
+use crate::repair;
 use crate::scenario;
 use crate::scenario::Scenario;
-use crate::ship::{ShipAccessor, ShipAccessorMut, ShipData, ShipHandle, Target};
+use crate::ship::{
+    ShipAccessor, ShipAccessorMut, ShipAction, ShipClass, ShipData, ShipHandle, Target,
+};
+use crate::ship_grid::ShipGrid;
 use crate::snapshot::*;
 use crate::vm;
 use crate::vm::TeamController;
 use crossbeam::channel::Sender;
 use instant::Instant;
-use nalgebra::{Vector2, Vector4};
-use oort_api::Text;
+use nalgebra::{Point2, Vector2, Vector4};
+use oort_api::{DebugValue, Text};
 use rand_chacha::ChaCha8Rng;
 use rapier2d_f64::data::Coarena;
 use rapier2d_f64::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::rc::Rc;
 
 pub const MAX_WORLD_SIZE: f64 = 200000.0;
 pub const PHYSICS_TICK_LENGTH: f64 = 1.0 / 60.0;
+/// Upper bound on the physics substeps per tick, see
+/// [`Simulation::set_adaptive_substeps`].
+pub const MAX_PHYSICS_SUBSTEPS: u32 = 32;
+/// Fraction of a ship's maximum health lost per second during sudden death,
+/// multiplied by the seconds since it began.
+pub const SUDDEN_DEATH_DAMAGE_RAMP: f64 = 0.01;
 
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, Hash, PartialEq)]
 pub enum Code {
@@ -41,6 +53,7 @@ pub struct Simulation {
     pub ships: IndexSet<ShipHandle>,
     pub(crate) ship_data: Coarena<ShipData>,
     team_controllers: HashMap<i32, Rc<RefCell<Box<TeamController>>>>,
+    pub(crate) ship_controllers: BTreeMap<ShipHandle, Rc<RefCell<Box<TeamController>>>>,
     pub new_ships: Vec<(/*team*/ i32, ShipHandle)>,
     pub bullets: IndexSet<BulletHandle>,
     pub(crate) bullet_data: Coarena<BulletData>,
@@ -52,7 +65,7 @@ pub struct Simulation {
     physics_pipeline: PhysicsPipeline,
     pub(crate) island_manager: IslandManager,
     broad_phase: BroadPhase,
-    narrow_phase: NarrowPhase,
+    pub(crate) narrow_phase: NarrowPhase,
     ccd_solver: CCDSolver,
     event_collector: CollisionEventHandler,
     contact_recv: crossbeam::channel::Receiver<CollisionEvent>,
@@ -63,12 +76,52 @@ pub struct Simulation {
     timing: Timing,
     pub(crate) rng: ChaCha8Rng,
     world_size: f64,
+    boundary: scenario::Boundary,
+    team_count: usize,
+    alliances: BTreeSet<(i32, i32)>,
+    event_log: Option<Vec<SimEvent>>,
+    pub(crate) radar_spatial_index: bool,
+    pub(crate) radar_occlusion: bool,
+    pub(crate) radar_classification: bool,
+    pub(crate) thrust_debug: bool,
+    debug_ship: Option<ShipHandle>,
+    max_radar_range: Option<f64>,
+    pub(crate) bullet_collisions: bool,
+    pub(crate) bullet_pool: bullet::BulletPool,
+    pub(crate) arena_radius: Option<f64>,
+    pub(crate) polygon_walls: bool,
+    adaptive_substeps: bool,
+    pub(crate) repair_zones: Vec<repair::RepairZone>,
+    pub(crate) debris: Vec<debris::Debris>,
+    pub(crate) mines: Vec<mine::Mine>,
+    pub(crate) ship_counts: HashMap<i32, usize>,
+    pub(crate) linear_damping: f64,
+    pub(crate) angular_damping: f64,
+    pub(crate) damage_multiplier: f64,
+    pub(crate) collision_cooldowns: BTreeMap<(ShipHandle, ShipHandle), u32>,
+    pub(crate) ram_damage: f64,
+    script_budget: Option<f64>,
+    sudden_death_tick: Option<u32>,
+    script_budget_start: Option<Instant>,
+    pub(crate) scripts_run: u32,
+    script_resume: usize,
+    ship_errors: BTreeMap<ShipHandle, String>,
+    pub(crate) frozen_ships: BTreeMap<ShipHandle, LockedAxes>,
+    ship_grid: RefCell<Option<ShipGrid>>,
 }
 
 impl Simulation {
     pub fn new(scenario_name: &str, seed: u32, codes: &[Code]) -> Box<Simulation> {
-        let mut scenario = scenario::load(scenario_name);
+        Self::new_with_scenario(scenario::load(scenario_name), seed, codes)
+    }
 
+    /// Creates a simulation from an already constructed scenario, e.g. a
+    /// [`scenario::ScenarioDef`] loaded from a file.
+    pub fn new_with_scenario(
+        mut scenario: Box<dyn Scenario>,
+        seed: u32,
+        codes: &[Code],
+    ) -> Box<Simulation> {
         log::debug!("seed {seed}");
         let (contact_send, contact_recv) = crossbeam::channel::unbounded();
         let mut sim = Box::new(Simulation {
@@ -76,6 +129,7 @@ impl Simulation {
             ships: IndexSet::new(),
             ship_data: Coarena::new(),
             team_controllers: HashMap::new(),
+            ship_controllers: BTreeMap::new(),
             new_ships: Vec::new(),
             bullets: IndexSet::new(),
             bullet_data: Coarena::new(),
@@ -102,6 +156,38 @@ impl Simulation {
             timing: Default::default(),
             rng: crate::rng::new_rng(seed),
             world_size: scenario.world_size(),
+            boundary: scenario.boundary(),
+            team_count: codes.len(),
+            alliances: BTreeSet::new(),
+            event_log: None,
+            radar_spatial_index: true,
+            radar_occlusion: false,
+            radar_classification: false,
+            thrust_debug: false,
+            debug_ship: None,
+            max_radar_range: None,
+            bullet_collisions: false,
+            bullet_pool: Default::default(),
+            arena_radius: None,
+            polygon_walls: false,
+            adaptive_substeps: false,
+            repair_zones: Vec::new(),
+            debris: Vec::new(),
+            mines: Vec::new(),
+            collision_cooldowns: BTreeMap::new(),
+            ram_damage: 0.0,
+            script_budget: None,
+            sudden_death_tick: None,
+            script_budget_start: None,
+            scripts_run: 0,
+            script_resume: 0,
+            ship_counts: HashMap::new(),
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            damage_multiplier: 1.0,
+            ship_errors: BTreeMap::new(),
+            frozen_ships: BTreeMap::new(),
+            ship_grid: RefCell::new(None),
         });
 
         for (team, code) in codes.iter().enumerate() {
@@ -110,12 +196,20 @@ impl Simulation {
             }
         }
 
-        collision::add_walls(&mut sim);
+        if sim.boundary == scenario::Boundary::Walls {
+            collision::add_walls(&mut sim);
+        }
 
         scenario.init(&mut sim, seed);
         sim.scenario = Some(scenario);
 
-        for team in sim.team_controllers.keys().copied().collect::<Vec<_>>() {
+        let mut teams: Vec<i32> = sim.team_controllers.keys().copied().collect();
+        for &handle in sim.ship_controllers.keys() {
+            teams.push(sim.ship(handle).data().team);
+        }
+        teams.sort();
+        teams.dedup();
+        for team in teams {
             sim.update_environment(team, BTreeMap::new());
         }
 
@@ -127,21 +221,415 @@ impl Simulation {
     }
 
     pub fn time(&self) -> f64 {
-        self.tick as f64 * PHYSICS_TICK_LENGTH
+        self.tick as f64 * self.dt()
     }
 
     pub fn score_time(&self) -> f64 {
         self.scenario.as_ref().unwrap().score_time(self)
     }
 
+    /// Points accrued by each team in objective-based scenarios such as
+    /// king of the hill. Empty for scenarios without scoring.
+    pub fn team_scores(&self) -> BTreeMap<i32, f64> {
+        self.scenario.as_ref().unwrap().team_scores()
+    }
+
     pub fn seed(&self) -> u32 {
         self.seed
     }
 
+    /// Returns the state of the simulation's random number generator.
+    ///
+    /// This is the generator used for effects such as particles, not the
+    /// per-tick radar noise, which is derived from the seed and tick.
+    pub fn rng_state(&self) -> crate::rng::RngState {
+        crate::rng::RngState::capture(&self.rng)
+    }
+
+    /// Replaces the random number generator state. Restoring a state captured
+    /// with [`rng_state`](Self::rng_state) continues the same deterministic
+    /// stream from that point.
+    pub fn set_rng_state(&mut self, state: &crate::rng::RngState) {
+        self.rng = state.restore();
+    }
+
+    /// Captures the physics, ship and bullet state so it can be returned to
+    /// with [`restore`](Self::restore).
+    ///
+    /// Ship and team controllers, team scripts and the scenario are not
+    /// captured, so stepping after a restore is only exact for ships without
+    /// code under a stateless scenario.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            tick: self.tick,
+            ships: self.ships.clone(),
+            ship_data: self.ship_data.clone(),
+            new_ships: self.new_ships.clone(),
+            bullets: self.bullets.clone(),
+            bullet_data: self.bullet_data.clone(),
+            bodies: self.bodies.clone(),
+            impulse_joints: self.impulse_joints.clone(),
+            multibody_joints: self.multibody_joints.clone(),
+            colliders: self.colliders.clone(),
+            island_manager: self.island_manager.clone(),
+            broad_phase: self.broad_phase.clone(),
+            narrow_phase: self.narrow_phase.clone(),
+            ccd_solver: self.ccd_solver.clone(),
+            rng: self.rng.clone(),
+            bullet_pool: self.bullet_pool.clone(),
+            repair_zones: self.repair_zones.clone(),
+            debris: self.debris.clone(),
+            mines: self.mines.clone(),
+            ship_counts: self.ship_counts.clone(),
+            collision_cooldowns: self.collision_cooldowns.clone(),
+            frozen_ships: self.frozen_ships.clone(),
+        }
+    }
+
+    /// Returns to the state captured by [`checkpoint`](Self::checkpoint).
+    ///
+    /// Stepping afterwards reproduces exactly what stepping from the
+    /// checkpoint did originally.
+    pub fn restore(&mut self, checkpoint: &Checkpoint) {
+        let checkpoint = checkpoint.clone();
+        self.tick = checkpoint.tick;
+        self.ships = checkpoint.ships;
+        self.ship_data = checkpoint.ship_data;
+        self.new_ships = checkpoint.new_ships;
+        self.bullets = checkpoint.bullets;
+        self.bullet_data = checkpoint.bullet_data;
+        self.bodies = checkpoint.bodies;
+        self.impulse_joints = checkpoint.impulse_joints;
+        self.multibody_joints = checkpoint.multibody_joints;
+        self.colliders = checkpoint.colliders;
+        self.island_manager = checkpoint.island_manager;
+        self.broad_phase = checkpoint.broad_phase;
+        self.narrow_phase = checkpoint.narrow_phase;
+        self.ccd_solver = checkpoint.ccd_solver;
+        self.rng = checkpoint.rng;
+        self.bullet_pool = checkpoint.bullet_pool;
+        self.repair_zones = checkpoint.repair_zones;
+        self.debris = checkpoint.debris;
+        self.mines = checkpoint.mines;
+        self.ship_counts = checkpoint.ship_counts;
+        self.collision_cooldowns = checkpoint.collision_cooldowns;
+        self.frozen_ships = checkpoint.frozen_ships;
+        self.invalidate_ship_grid();
+        self.events = SimEvents::new();
+        while self.contact_recv.try_recv().is_ok() {}
+    }
+
     pub fn world_size(&self) -> f64 {
         self.world_size
     }
 
+    /// Number of teams the simulation was created with, one per code.
+    pub fn team_count(&self) -> usize {
+        self.team_count
+    }
+
+    pub fn boundary(&self) -> scenario::Boundary {
+        self.boundary
+    }
+
+    /// Makes teams `a` and `b` friendly to each other, or hostile again if
+    /// `allied` is false.
+    ///
+    /// Radars and [`TeamFilter::Enemy`] ignore allied ships. By default every
+    /// team is hostile to every other team.
+    pub fn set_allied(&mut self, a: i32, b: i32, allied: bool) {
+        let key = (a.min(b), a.max(b));
+        if allied {
+            self.alliances.insert(key);
+        } else {
+            self.alliances.remove(&key);
+        }
+    }
+
+    pub fn are_hostile(&self, a: i32, b: i32) -> bool {
+        a != b && !self.alliances.contains(&(a.min(b), a.max(b)))
+    }
+
+    /// Returns the teams allied with `team`, not including itself.
+    pub fn allies(&self, team: i32) -> Vec<i32> {
+        self.alliances
+            .iter()
+            .filter_map(|&(a, b)| {
+                if a == team {
+                    Some(b)
+                } else if b == team {
+                    Some(a)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the vector from `from` to `to`, taking the shorter way
+    /// across the edges if the world is a [torus](scenario::Boundary::Torus).
+    pub fn shortest_displacement(&self, from: Vector2<f64>, to: Vector2<f64>) -> Vector2<f64> {
+        match self.boundary {
+            scenario::Boundary::Torus => wrap_displacement(to - from, self.world_size),
+            _ => to - from,
+        }
+    }
+
+    /// Returns the time (in seconds) until ships `a` and `b` are nearest to
+    /// each other and the distance between them at that point, assuming both
+    /// keep their current velocities.
+    ///
+    /// The time is zero if the ships are already moving apart.
+    pub fn closest_point_of_approach(&self, a: ShipHandle, b: ShipHandle) -> (f64, f64) {
+        let (a, b) = (self.ship(a), self.ship(b));
+        let dp = self.shortest_displacement(a.position().vector, b.position().vector);
+        let dv = b.velocity() - a.velocity();
+        let speed_squared = dv.magnitude_squared();
+        let time = if speed_squared > 0.0 {
+            (-dp.dot(&dv) / speed_squared).max(0.0)
+        } else {
+            0.0
+        };
+        (time, (dp + dv * time).magnitude())
+    }
+
+    /// Moves ships and bullets that left a toroidal world back in on the
+    /// opposite side.
+    fn wrap_positions(&mut self) {
+        let half = self.world_size / 2.0;
+        let handles = self
+            .ships
+            .iter()
+            .map(|&handle| RigidBodyHandle(handle.index()))
+            .chain(
+                self.bullets
+                    .iter()
+                    .map(|&handle| RigidBodyHandle::from(handle)),
+            );
+        for handle in handles {
+            let body = self.bodies.get_mut(handle).unwrap();
+            let position = *body.translation();
+            if position.x.abs() > half || position.y.abs() > half {
+                let wrapped = position.map(|x| (x + half).rem_euclid(self.world_size) - half);
+                body.set_translation(wrapped, true);
+            }
+        }
+    }
+
+    /// Radius of the circular boundary, if one was added with
+    /// [`collision::add_circular_boundary`].
+    pub fn arena_radius(&self) -> Option<f64> {
+        self.arena_radius
+    }
+
+    /// Returns the sum of `0.5 * m * v²` over all ships.
+    ///
+    /// Useful for catching physics instabilities, which show up as a sudden
+    /// increase in energy.
+    pub fn total_kinetic_energy(&self) -> f64 {
+        self.ships
+            .iter()
+            .map(|&handle| {
+                let body = self.ship(handle).body();
+                0.5 * body.mass() * body.linvel().norm_squared()
+            })
+            .sum()
+    }
+
+    /// Sets which ships a ship physically collides with.
+    ///
+    /// Two ships collide only if their masks share a bit. All ships start with
+    /// [`collision::DEFAULT_SHIP_COLLISION_MASK`], so giving a ship a mask of 0
+    /// lets it pass through every other ship. Walls, planets, and bullets are
+    /// unaffected.
+    pub fn set_collision_filter(&mut self, handle: ShipHandle, mask: u8) {
+        let ship = self.ship(handle);
+        let data = ship.data();
+        if data.class == ShipClass::Planet {
+            return;
+        }
+        let groups = collision::ship_interaction_groups(data.team, mask);
+        let colliders = self.ship(handle).body().colliders().to_vec();
+        for collider in colliders {
+            self.colliders
+                .get_mut(collider)
+                .unwrap()
+                .set_collision_groups(groups);
+        }
+    }
+
+    /// Scales the damage done by every bullet hit, for balancing a scenario
+    /// without editing its weapons. The default is 1.
+    pub fn set_damage_multiplier(&mut self, multiplier: f64) {
+        self.damage_multiplier = multiplier.max(0.0);
+    }
+
+    pub fn damage_multiplier(&self) -> f64 {
+        self.damage_multiplier
+    }
+
+    /// Makes colliding ships, including teammates, damage each other by
+    /// `factor * 0.5 * v²`, where `v` is their relative speed (in m/s) at
+    /// impact. Impacts slower than [`collision::RAM_DAMAGE_MIN_SPEED`] do no
+    /// damage, and a pair in sustained contact is only damaged once every
+    /// [`collision::COLLISION_DAMAGE_COOLDOWN_TICKS`]. The default is zero.
+    pub fn set_ram_damage(&mut self, factor: f64) {
+        self.ram_damage = factor.max(0.0);
+    }
+
+    /// Caps the wall-clock time (in seconds) spent running ship AIs each
+    /// step. Once it's used up the remaining ships keep their previous
+    /// acceleration and torque and run again next tick, logging a
+    /// [`SimEvent::ScriptDeferred`]. Unlimited by default.
+    ///
+    /// At least one AI runs every step, and deferred ships go first on the
+    /// next step, so every ship keeps running however small the budget.
+    pub fn set_script_budget(&mut self, budget: Option<f64>) {
+        self.script_budget = budget;
+    }
+
+    /// Starts sudden death at `tick`. From then on every ship except
+    /// asteroids and planets takes damage each tick, growing at
+    /// [`SUDDEN_DEATH_DAMAGE_RAMP`], so a stalemated match always ends.
+    /// Disabled by default.
+    ///
+    /// The damage is dealt by [`Simulation::apply_sudden_death`], which the
+    /// default [`Scenario::tick`] calls. Scenarios that override `tick` call
+    /// it themselves.
+    pub fn set_sudden_death_tick(&mut self, tick: Option<u32>) {
+        self.sudden_death_tick = tick;
+    }
+
+    pub fn sudden_death_active(&self) -> bool {
+        matches!(self.sudden_death_tick, Some(start) if self.tick >= start)
+    }
+
+    /// Deals this tick's sudden death damage, if it has started.
+    pub fn apply_sudden_death(&mut self) {
+        let Some(start) = self.sudden_death_tick.filter(|&start| self.tick >= start) else {
+            return;
+        };
+        let dt = self.dt();
+        let elapsed = (self.tick - start + 1) as f64 * dt;
+        let fraction = SUDDEN_DEATH_DAMAGE_RAMP * elapsed * dt;
+        let handles: Vec<ShipHandle> = self.ships.iter().cloned().collect();
+        for handle in handles {
+            let ship = self.ship(handle);
+            let data = ship.data();
+            if data.destroyed
+                || matches!(data.class, ShipClass::Asteroid { .. } | ShipClass::Planet)
+            {
+                continue;
+            }
+            let damage = data.max_health * fraction;
+            self.ship_mut(handle).damage(damage, None);
+        }
+    }
+
+    pub(crate) fn script_budget_exceeded(&self) -> bool {
+        if self.scripts_run == 0 {
+            return false;
+        }
+        match (self.script_budget, self.script_budget_start) {
+            (Some(budget), Some(start)) => (Instant::now() - start).as_secs_f64() >= budget,
+            _ => false,
+        }
+    }
+
+    /// Sets the number of velocity and position (stabilization) solver iterations.
+    ///
+    /// More iterations reduce jitter and penetration in dense contacts at the cost
+    /// of speed. Changing this changes the outcome of the simulation, so replays
+    /// are only deterministic if every client uses the same values.
+    pub fn set_solver_iterations(
+        &mut self,
+        velocity_iterations: usize,
+        position_iterations: usize,
+    ) {
+        self.integration_parameters.max_velocity_iterations = velocity_iterations.max(1);
+        self.integration_parameters.max_stabilization_iterations = position_iterations;
+    }
+
+    pub fn solver_iterations(&self) -> (usize, usize) {
+        (
+            self.integration_parameters.max_velocity_iterations,
+            self.integration_parameters.max_stabilization_iterations,
+        )
+    }
+
+    /// Sets the physics integration timestep (in seconds). The default is
+    /// [`PHYSICS_TICK_LENGTH`].
+    ///
+    /// A smaller timestep makes fast-moving bodies less likely to tunnel
+    /// through thin colliders, on top of what CCD already catches, at the cost
+    /// of simulating less time per tick. Rates given per second, such as
+    /// fuel use, cooling and bullet TTLs, are scaled to match, while reloads
+    /// and cooldowns given in ticks are not. Ship AIs still see the default
+    /// [`TICK_LENGTH`](oort_api::prelude::TICK_LENGTH).
+    ///
+    /// Like the solver iterations this changes the outcome of the simulation,
+    /// so it should be set once during scenario setup and be the same on
+    /// every client.
+    pub fn set_dt(&mut self, dt: f64) {
+        assert!(dt > 0.0 && dt.is_finite(), "Invalid timestep {dt}");
+        self.integration_parameters.dt = dt;
+    }
+
+    pub fn dt(&self) -> f64 {
+        self.integration_parameters.dt
+    }
+
+    /// Splits each physics step into as many substeps as needed for no ship
+    /// to move more than half its radius in one, up to
+    /// [`MAX_PHYSICS_SUBSTEPS`]. Off by default.
+    ///
+    /// This keeps very fast ships from tunneling through walls. Like
+    /// [`set_dt`](Self::set_dt) it changes the outcome of the simulation.
+    pub fn set_adaptive_substeps(&mut self, enabled: bool) {
+        self.adaptive_substeps = enabled;
+    }
+
+    fn physics_substeps(&self) -> u32 {
+        if !self.adaptive_substeps {
+            return 1;
+        }
+        let dt = self.integration_parameters.dt;
+        self.ships
+            .iter()
+            .map(|&handle| {
+                let ship = self.ship(handle);
+                (ship.velocity().norm() * dt / (0.5 * ship.radius())).ceil() as u32
+            })
+            .max()
+            .unwrap_or(1)
+            .clamp(1, MAX_PHYSICS_SUBSTEPS)
+    }
+
+    /// Sets velocity-proportional drag on every body, including ones created
+    /// later. Zero (the default) means bodies coast forever.
+    pub fn set_linear_damping(&mut self, damping: f64) {
+        self.linear_damping = damping.max(0.0);
+        for (_, body) in self.bodies.iter_mut() {
+            body.set_linear_damping(self.linear_damping);
+        }
+    }
+
+    pub fn linear_damping(&self) -> f64 {
+        self.linear_damping
+    }
+
+    /// Like [`set_linear_damping`](Self::set_linear_damping) but for rotation.
+    pub fn set_angular_damping(&mut self, damping: f64) {
+        self.angular_damping = damping.max(0.0);
+        for (_, body) in self.bodies.iter_mut() {
+            body.set_angular_damping(self.angular_damping);
+        }
+    }
+
+    pub fn angular_damping(&self) -> f64 {
+        self.angular_damping
+    }
+
     pub fn status(&self) -> scenario::Status {
         self.scenario.as_ref().unwrap().status(self)
     }
@@ -153,6 +641,70 @@ impl Simulation {
         }
     }
 
+    /// Returns the handles of all ships matching `pred`, in iteration order.
+    pub fn find_ships(&self, pred: impl Fn(&ShipAccessor) -> bool) -> Vec<ShipHandle> {
+        self.ships
+            .iter()
+            .filter(|&&handle| pred(&self.ship(handle)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the closest live ship matching `filter` and its distance from
+    /// `from`, measured across the edges of a toroidal world. Ties go to the
+    /// ship found first in iteration order.
+    ///
+    /// Backed by a grid of ship positions that is rebuilt at most once per
+    /// step, so it's cheap to call for every ship. A ship teleported by hand
+    /// between steps is found at its old position until the next step.
+    pub fn nearest_ship(&self, from: Point2<f64>, filter: ShipFilter) -> Option<(ShipHandle, f64)> {
+        self.with_ship_grid(|grid| {
+            grid.nearest(self, from, |handle| {
+                let ship = self.ship(handle);
+                !ship.data().destroyed && filter.matches(&ship)
+            })
+        })
+    }
+
+    /// Returns the live ships whose hull is within `radius` of `center`, in
+    /// iteration order. Uses the same grid as
+    /// [`nearest_ship`](Self::nearest_ship).
+    pub fn ships_within(&self, center: Vector2<f64>, radius: f64) -> Vec<ShipHandle> {
+        self.with_ship_grid(|grid| grid.within(self, center, radius))
+    }
+
+    fn with_ship_grid<T>(&self, f: impl FnOnce(&ShipGrid) -> T) -> T {
+        let mut grid = self.ship_grid.borrow_mut();
+        f(grid.get_or_insert_with(|| ShipGrid::new(self)))
+    }
+
+    /// Discards the ship grid once ships have moved or been created.
+    pub(crate) fn invalidate_ship_grid(&mut self) {
+        *self.ship_grid.get_mut() = None;
+    }
+
+    /// Returns the number of live ships on `team`.
+    ///
+    /// This is kept up to date as ships are created and removed, so it's cheap
+    /// to call every frame.
+    pub fn ship_count(&self, team: i32) -> usize {
+        self.ship_counts.get(&team).copied().unwrap_or(0)
+    }
+
+    /// Returns the error that disabled each ship whose AI trapped or ran out
+    /// of instructions, or whose physics state became NaN or infinite.
+    ///
+    /// A disabled ship is no longer ticked by its AI but stays in the
+    /// simulation, drifting. Ships with an invalid position are removed
+    /// instead. Entries remain after the ship is gone.
+    pub fn ship_errors(&self) -> &BTreeMap<ShipHandle, String> {
+        &self.ship_errors
+    }
+
+    pub(crate) fn record_ship_error(&mut self, handle: ShipHandle, msg: String) {
+        self.ship_errors.insert(handle, msg);
+    }
+
     pub fn ship_mut(self: &mut Simulation, handle: ShipHandle) -> ShipAccessorMut {
         ShipAccessorMut {
             simulation: self,
@@ -160,44 +712,158 @@ impl Simulation {
         }
     }
 
+    /// Overwrites a ship's linear (m/s) and angular (radians/s) velocity.
+    pub fn set_ship_velocity(&mut self, handle: ShipHandle, linvel: Vector2<f64>, angvel: f64) {
+        let body = self
+            .bodies
+            .get_mut(RigidBodyHandle(handle.index()))
+            .unwrap();
+        body.set_linvel(linvel, true);
+        body.set_angvel(angvel, true);
+    }
+
+    /// Rotates a ship in place to face `heading` (in radians), unlike
+    /// turning with torque.
+    pub fn set_ship_heading(&mut self, handle: ShipHandle, heading: f64) {
+        use std::f64::consts::{PI, TAU};
+        let body = self
+            .bodies
+            .get_mut(RigidBodyHandle(handle.index()))
+            .unwrap();
+        let translation = *body.translation();
+        let angle = (heading + PI).rem_euclid(TAU) - PI;
+        body.set_position(Isometry::new(translation, angle), true);
+    }
+
+    /// Pins a ship in place while `frozen`. The ship keeps colliding and
+    /// taking damage but forces and impulses don't move it. Thawing restores
+    /// its original locked axes, with zero velocity.
+    pub fn set_ship_frozen(&mut self, handle: ShipHandle, frozen: bool) {
+        let body = self
+            .bodies
+            .get_mut(RigidBodyHandle(handle.index()))
+            .unwrap();
+        if frozen {
+            if self.frozen_ships.contains_key(&handle) {
+                return;
+            }
+            self.frozen_ships.insert(handle, body.locked_axes());
+            body.set_linvel(vector![0.0, 0.0], false);
+            body.set_angvel(0.0, false);
+            body.set_locked_axes(LockedAxes::all(), true);
+        } else if let Some(locked_axes) = self.frozen_ships.remove(&handle) {
+            body.set_locked_axes(locked_axes, true);
+        }
+    }
+
+    pub fn is_ship_frozen(&self, handle: ShipHandle) -> bool {
+        self.frozen_ships.contains_key(&handle)
+    }
+
+    /// Sets [`ShipData::think_interval`] for every current ship of `team`.
+    pub fn set_team_think_interval(&mut self, team: i32, interval: u32) {
+        for handle in self.find_ships(|ship| ship.data().team == team) {
+            self.ship_mut(handle).data_mut().think_interval = interval;
+        }
+    }
+
+    /// Keeps NaN and infinite values out of the physics solver, where they
+    /// would spread to every body the ship touches.
+    ///
+    /// An invalid velocity is zeroed and the ship's AI disabled. A ship with
+    /// an invalid position can't be recovered and is removed.
+    fn sanitize_ships(&mut self) {
+        let handles: Vec<ShipHandle> = self.ships.iter().cloned().collect();
+        for handle in handles {
+            let body = self.ship(handle).body();
+            let position = body.position();
+            let position_valid = position.translation.vector.iter().all(|x| x.is_finite())
+                && position.rotation.angle().is_finite();
+            let velocity_valid =
+                body.linvel().iter().all(|x| x.is_finite()) && body.angvel().is_finite();
+            if position_valid && velocity_valid {
+                continue;
+            }
+            let msg = if position_valid {
+                "Ship velocity became invalid and was disabled"
+            } else {
+                "Ship position became invalid and was removed"
+            };
+            log::warn!("{msg}: {:?}", handle);
+            self.record_ship_error(handle, msg.to_string());
+            if position_valid {
+                let team = self.ship(handle).data().team;
+                if let Some(ctrl) = self.ship_controller(handle, team) {
+                    ctrl.borrow_mut().remove_ship(handle);
+                }
+                let body = self
+                    .bodies
+                    .get_mut(RigidBodyHandle(handle.index()))
+                    .unwrap();
+                body.set_linvel(vector![0.0, 0.0], false);
+                body.set_angvel(0.0, false);
+                body.reset_forces(false);
+                body.reset_torques(false);
+            } else {
+                self.ship_mut(handle).remove();
+            }
+        }
+    }
+
     #[allow(clippy::let_unit_value)]
     pub fn step(self: &mut Simulation) {
         self.events.clear();
+        self.bullet_pool.recycle_pending();
         self.timing = Default::default();
 
         let new_ships = std::mem::take(&mut self.new_ships);
         for (team, handle) in new_ships.iter() {
-            if let Some(team_ctrl) = self.get_team_controller(*team) {
-                if let Err(e) = team_ctrl.borrow_mut().add_ship(*handle, self) {
+            if let Some(ctrl) = self.ship_controller(*handle, *team) {
+                if let Err(e) = ctrl.borrow_mut().add_ship(*handle, self) {
                     log::warn!("Ship creation error: {:?}", e);
                     self.events.errors.push(e);
                 }
             }
         }
 
+        self.sanitize_ships();
+
+        let ram_velocities = collision::ram_velocities(self);
         let physics_timer = Timer::new();
         let gravity = vector![0.0, 0.0];
         let physics_hooks = ();
-        self.physics_pipeline.step(
-            &gravity,
-            &self.integration_parameters,
-            &mut self.island_manager,
-            &mut self.broad_phase,
-            &mut self.narrow_phase,
-            &mut self.bodies,
-            &mut self.colliders,
-            &mut self.impulse_joints,
-            &mut self.multibody_joints,
-            &mut self.ccd_solver,
-            None,
-            &physics_hooks,
-            &self.event_collector,
-        );
+        let substeps = self.physics_substeps();
+        let mut integration_parameters = self.integration_parameters;
+        integration_parameters.dt /= substeps as f64;
+        for _ in 0..substeps {
+            self.physics_pipeline.step(
+                &gravity,
+                &integration_parameters,
+                &mut self.island_manager,
+                &mut self.broad_phase,
+                &mut self.narrow_phase,
+                &mut self.bodies,
+                &mut self.colliders,
+                &mut self.impulse_joints,
+                &mut self.multibody_joints,
+                &mut self.ccd_solver,
+                None,
+                &physics_hooks,
+                &self.event_collector,
+            );
+        }
+        if self.boundary == scenario::Boundary::Torus {
+            self.wrap_positions();
+        }
+        self.invalidate_ship_grid();
         self.timing.physics = physics_timer.elapsed();
 
         let collision_timer = Timer::new();
-        let collision_events: Vec<_> = self.contact_recv.try_iter().collect();
+        let mut collision_events: Vec<_> = self.contact_recv.try_iter().collect();
+        collision::sort_collision_events(&mut collision_events);
         collision::handle_collisions(self, &collision_events);
+        collision::apply_ram_damage(self, &ram_velocities);
+        repair::tick(self);
         self.timing.collision += collision_timer.elapsed();
 
         let radar_timer = Timer::new();
@@ -215,16 +881,39 @@ impl Simulation {
             .map(|(k, v)| (*k, Rc::clone(v)))
             .collect();
         teams.sort_by_key(|(k, _)| *k);
+        let mut controllers: Vec<_> = teams
+            .into_iter()
+            .map(|(_, v)| v)
+            .chain(self.ship_controllers.values().cloned())
+            .collect();
 
-        for (_, team_controller) in teams.iter() {
-            team_controller.borrow_mut().tick(self);
+        // Start with the controller that ran out of budget last step.
+        let resume = self.script_resume % controllers.len().max(1);
+        controllers.rotate_left(resume);
+        self.script_resume = 0;
+        self.script_budget_start = Some(Instant::now());
+        self.scripts_run = 0;
+        let mut deferred = None;
+        for (i, controller) in controllers.iter().enumerate() {
+            if controller.borrow_mut().tick(self) && deferred.is_none() {
+                deferred = Some((resume + i) % controllers.len());
+            }
         }
+        self.script_resume = deferred.unwrap_or(0);
+        self.script_budget_start = None;
         self.timing.vm += vm_timer.elapsed();
 
-        let ship_timer = Timer::new();
+        let debug_timer = Timer::new();
         let handle_snapshot: Vec<ShipHandle> = self.ships.iter().cloned().collect();
+        for &handle in handle_snapshot.iter() {
+            if self.is_debug_ship(handle) {
+                debug::emit_ship(self, handle);
+            }
+        }
+        self.timing.debug += debug_timer.elapsed();
+
+        let ship_timer = Timer::new();
         for handle in handle_snapshot {
-            debug::emit_ship(self, handle);
             self.ship_mut(handle).tick();
         }
         self.timing.ship += ship_timer.elapsed();
@@ -237,6 +926,7 @@ impl Simulation {
         let mut scenario = std::mem::take(&mut self.scenario);
         scenario.as_mut().unwrap().tick(self);
         self.scenario = scenario;
+        self.invalidate_ship_grid();
         self.timing.scenario += scenario_timer.elapsed();
 
         self.tick += 1;
@@ -255,14 +945,147 @@ impl Simulation {
         }
     }
 
+    /// Like [`upload_code`](Self::upload_code) but reuses an AI compiled
+    /// ahead of time, so a batch of simulations only compiles it once.
+    ///
+    /// Ships that haven't been ticked yet, including those spawned by the
+    /// scenario, are controlled by the new AI.
+    pub fn upload_compiled_ai(&mut self, team: i32, ai: &vm::CompiledAi) {
+        match vm::TeamController::create_from_ai(ai) {
+            Ok(team_ctrl) => {
+                self.team_controllers
+                    .insert(team, Rc::new(RefCell::new(team_ctrl)));
+                if self.scenario.is_some() {
+                    self.update_environment(team, BTreeMap::new());
+                }
+            }
+            Err(e) => {
+                log::warn!("Creating team controller failed: {:?}", e);
+                self.events.errors.push(e);
+            }
+        }
+    }
+
     pub fn events(&self) -> &SimEvents {
         &self.events
     }
 
+    /// Returns the ships destroyed during the last step, for rendering.
+    pub fn take_explosions(&mut self) -> Vec<ExplosionEffect> {
+        std::mem::take(&mut self.events.explosions)
+    }
+
+    /// Selects between the spatial index and a linear scan when finding radar
+    /// contacts. Both give identical results; this exists for benchmarking.
+    pub fn set_radar_spatial_index(&mut self, enabled: bool) {
+        self.radar_spatial_index = enabled;
+    }
+
+    /// Makes asteroids block radar, so ships behind them can't be detected.
+    /// Off by default to keep existing scenarios unchanged.
+    pub fn set_radar_occlusion(&mut self, enabled: bool) {
+        self.radar_occlusion = enabled;
+    }
+
+    /// Hides the class of weak radar contacts, see
+    /// [`CLASSIFY_RSSI_FACTOR`](crate::radar::CLASSIFY_RSSI_FACTOR). Off by
+    /// default to keep existing scenarios unchanged.
+    pub fn set_radar_classification(&mut self, enabled: bool) {
+        self.radar_classification = enabled;
+    }
+
+    /// Adds a debug line per ship showing the thrust applied by its engine,
+    /// drawn in the team's color. Off by default.
+    pub fn set_thrust_debug(&mut self, enabled: bool) {
+        self.thrust_debug = enabled;
+    }
+
+    /// Limits the simulator's own debug visuals, such as radar beams and
+    /// thrust lines, to one ship. `None` draws them for every ship.
+    pub fn set_debug_ship(&mut self, handle: Option<ShipHandle>) {
+        self.debug_ship = handle;
+    }
+
+    pub(crate) fn is_debug_ship(&self, handle: ShipHandle) -> bool {
+        self.debug_ship
+            .map_or(true, |debug_ship| debug_ship == handle)
+    }
+
+    /// Lets bullets from different teams collide and destroy each other, so
+    /// guns can shoot down incoming fire. Off by default.
+    pub fn set_bullet_collisions(&mut self, enabled: bool) {
+        self.bullet_collisions = enabled;
+        let bullets: Vec<BulletHandle> = self.bullets.iter().cloned().collect();
+        for handle in bullets {
+            let groups =
+                collision::bullet_interaction_groups(bullet::data(self, handle).team, enabled);
+            let colliders = bullet::body(self, handle).colliders().to_vec();
+            for collider in colliders {
+                self.colliders
+                    .get_mut(collider)
+                    .unwrap()
+                    .set_collision_groups(groups);
+            }
+        }
+    }
+
+    /// Sets a hard limit on radar detection range (in meters), applied on top
+    /// of the power-based range. `None` removes the limit.
+    pub fn set_max_radar_range(&mut self, range: Option<f64>) {
+        self.max_radar_range = range;
+    }
+
+    pub fn max_radar_range(&self) -> Option<f64> {
+        self.max_radar_range
+    }
+
+    /// Reuses the rigid bodies of destroyed bullets instead of freeing them.
+    ///
+    /// This avoids allocation churn in gun-heavy matches. Bullets behave the
+    /// same either way, but recycled bullets keep their old handles, so
+    /// simulations with different settings assign handles differently.
+    pub fn set_bullet_pooling(&mut self, enabled: bool) {
+        self.bullet_pool.enabled = enabled;
+    }
+
+    /// Starts recording [`SimEvent`]s. Events accumulate across steps until
+    /// drained.
+    pub fn enable_event_log(&mut self) {
+        if self.event_log.is_none() {
+            self.event_log = Some(Vec::new());
+        }
+    }
+
+    /// Returns the events recorded since the last call, in the order they
+    /// happened.
+    pub fn drain_events(&mut self) -> Vec<SimEvent> {
+        self.event_log
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn log_event(&mut self, event: SimEvent) {
+        if let Some(event_log) = self.event_log.as_mut() {
+            event_log.push(event);
+        }
+    }
+
     pub fn timing(&self) -> &Timing {
         &self.timing
     }
 
+    /// Like [`step`](Self::step), also returning how long the whole step
+    /// took alongside the per-phase breakdown from [`timing`](Self::timing).
+    pub fn step_timed(&mut self) -> StepTiming {
+        let timer = Timer::new();
+        self.step();
+        StepTiming {
+            total: timer.elapsed(),
+            phases: self.timing.clone(),
+        }
+    }
+
     pub fn emit_debug_lines(&mut self, ship: ShipHandle, lines: Vec<Line>) {
         self.events.debug_lines.push((ship.into(), lines));
     }
@@ -287,6 +1110,28 @@ impl Simulation {
             .extend(texts.iter().cloned());
     }
 
+    /// Records watch values set by a ship's AI with `debug_set`.
+    pub fn emit_debug_values(&mut self, ship: ShipHandle, values: &[DebugValue]) {
+        let map = self.events.debug_values.entry(ship.into()).or_default();
+        for v in values {
+            let key = String::from_utf8_lossy(&v.key[..v.length as usize]).into_owned();
+            map.insert(key, v.value);
+        }
+    }
+
+    /// Returns the watch values the ship's AI set during the last tick.
+    pub fn ship_debug(&self, ship: ShipHandle) -> Option<&BTreeMap<String, f64>> {
+        self.events.debug_values.get(&ship.into())
+    }
+
+    /// Commands a ship directly from Rust, bypassing the VM.
+    ///
+    /// Takes effect during the next call to `step`. If the ship's team also has
+    /// an AI, the AI's commands for the same tick take precedence.
+    pub fn apply_action(&mut self, ship: ShipHandle, action: &ShipAction) {
+        self.ship_mut(ship).apply_action(action);
+    }
+
     pub fn write_target(&mut self, ship: ShipHandle, p: Vector2<f64>, v: Vector2<f64>) {
         self.ship_mut(ship).data_mut().target = Some(Box::new(Target {
             position: p,
@@ -317,6 +1162,51 @@ impl Simulation {
         s.finish()
     }
 
+    /// Fingerprint of the exact simulation state, for checking that two runs
+    /// are identical tick by tick.
+    ///
+    /// Unlike [`hash`](Self::hash), which is rounded and kept stable for golden
+    /// tests, this covers the full bits of every ship and bullet transform and
+    /// velocity, ship health, and the RNG state.
+    pub fn state_hash(&self) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let mut s = DefaultHasher::new();
+        s.write_u32(self.tick);
+        for handle in self.ships.iter() {
+            let ship = self.ship(*handle);
+            let body = ship.body();
+            s.write_u64(u64::from(*handle));
+            for v in [
+                body.translation().x,
+                body.translation().y,
+                body.rotation().angle(),
+                body.linvel().x,
+                body.linvel().y,
+                body.angvel(),
+                ship.data().health,
+            ] {
+                s.write_u64(v.to_bits());
+            }
+        }
+        for handle in self.bullets.iter() {
+            let body = bullet::body(self, *handle);
+            for v in [
+                body.translation().x,
+                body.translation().y,
+                body.linvel().x,
+                body.linvel().y,
+            ] {
+                s.write_u64(v.to_bits());
+            }
+        }
+        let rng = self.rng_state();
+        rng.seed.hash(&mut s);
+        s.write_u64(rng.stream);
+        s.write_u128(rng.word_pos);
+        s.finish()
+    }
+
     pub fn snapshot(&self, nonce: u32) -> Snapshot {
         let mut snapshot = Snapshot {
             nonce,
@@ -329,11 +1219,14 @@ impl Simulation {
             debug_lines: self.events.debug_lines.clone(),
             debug_text: self.events.debug_text.clone(),
             drawn_text: self.events.drawn_text.clone(),
+            debug_values: self.events.debug_values.clone(),
             particles: self.events.particles.clone(),
             errors: self.events.errors.clone(),
             cheats: self.cheats,
             timing: self.timing.clone(),
             world_size: self.world_size,
+            team_scores: self.team_scores(),
+            sudden_death: self.sudden_death_active(),
         };
 
         for &handle in self.ships.iter() {
@@ -355,8 +1248,10 @@ impl Simulation {
                 team,
                 class,
                 health,
+                shield_up: ship.shield_up(),
                 fuel,
                 active_abilities: ship.active_abilities(),
+                lock_target: ship.lock_target().map(|handle| handle.into()),
             });
         }
 
@@ -374,25 +1269,145 @@ impl Simulation {
         snapshot
     }
 
+    pub fn render_state(&self) -> RenderState {
+        RenderState {
+            ships: self
+                .ships
+                .iter()
+                .map(|&handle| {
+                    let ship = self.ship(handle);
+                    ShipRenderState {
+                        id: handle.into(),
+                        position: ship.position().vector.into(),
+                        heading: ship.heading(),
+                        radius: ship.radius(),
+                        team: ship.data().team,
+                        lock_target: ship.lock_target().map(|handle| handle.into()),
+                    }
+                })
+                .collect(),
+            bullets: self
+                .bullets
+                .iter()
+                .map(|&handle| {
+                    let body = self.bodies.get(handle.into()).unwrap();
+                    BulletRenderState {
+                        position: body.position().translation.vector.into(),
+                        velocity: *body.linvel(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the debris fragments currently in the arena.
+    pub fn debris(&self) -> &[debris::Debris] {
+        &self.debris
+    }
+
+    /// Returns the mines currently deployed.
+    pub fn mines(&self) -> &[mine::Mine] {
+        &self.mines
+    }
+
+    /// Returns each bullet's position and team, e.g. for a minimap. Cheaper
+    /// than [`render_state`](Self::render_state) when nothing else is needed.
+    pub fn bullet_positions(&self) -> Vec<(Point2<f64>, i32)> {
+        self.bullets
+            .iter()
+            .map(|&handle| {
+                let body = self.bodies.get(handle.into()).unwrap();
+                let team = self.bullet_data.get(handle.index()).unwrap().team;
+                ((*body.translation()).into(), team)
+            })
+            .collect()
+    }
+
     pub fn get_team_controller(&mut self, team: i32) -> Option<Rc<RefCell<Box<TeamController>>>> {
         self.team_controllers.get_mut(&team).map(|x| x.clone())
     }
 
+    /// Returns the controller running a ship's AI: its own if it was created
+    /// with [`ship::create_with_ai`](crate::ship::create_with_ai), otherwise
+    /// its team's.
+    pub(crate) fn ship_controller(
+        &mut self,
+        handle: ShipHandle,
+        team: i32,
+    ) -> Option<Rc<RefCell<Box<TeamController>>>> {
+        match self.ship_controllers.get(&handle) {
+            Some(ctrl) => Some(ctrl.clone()),
+            None => self.get_team_controller(team),
+        }
+    }
+
+    /// Gives a ship that hasn't been ticked yet its own AI instead of its
+    /// team's.
+    pub(crate) fn set_ship_ai(&mut self, handle: ShipHandle, ai: &vm::CompiledAi) {
+        match vm::TeamController::create_from_ai(ai) {
+            Ok(ctrl) => {
+                self.ship_controllers
+                    .insert(handle, Rc::new(RefCell::new(ctrl)));
+                if self.scenario.is_some() {
+                    let team = self.ship(handle).data().team;
+                    self.update_environment(team, BTreeMap::new());
+                }
+            }
+            Err(e) => {
+                log::warn!("Creating ship controller failed: {:?}", e);
+                self.events.errors.push(e);
+            }
+        }
+    }
+
     pub fn update_environment(&mut self, team: i32, mut environment: BTreeMap<String, String>) {
         environment.insert(
             "SCENARIO_NAME".to_string(),
             self.scenario.as_ref().unwrap().name(),
         );
         environment.insert("WORLD_SIZE".to_string(), format!("{}", self.world_size));
+        environment.insert("TEAM_COUNT".to_string(), format!("{}", self.team_count));
         if let Some(team_ctrl) = self.get_team_controller(team) {
             team_ctrl
                 .borrow_mut()
                 .update_environment(&environment)
                 .unwrap();
         }
+        for (&handle, ctrl) in self.ship_controllers.iter() {
+            if self.ship(handle).data().team == team {
+                ctrl.borrow_mut().update_environment(&environment).unwrap();
+            }
+        }
     }
 }
 
+/// Simulation state saved by [`Simulation::checkpoint`].
+#[derive(Clone)]
+pub struct Checkpoint {
+    tick: u32,
+    ships: IndexSet<ShipHandle>,
+    ship_data: Coarena<ShipData>,
+    new_ships: Vec<(i32, ShipHandle)>,
+    bullets: IndexSet<BulletHandle>,
+    bullet_data: Coarena<BulletData>,
+    bodies: RigidBodySet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    colliders: ColliderSet,
+    island_manager: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    rng: ChaCha8Rng,
+    bullet_pool: bullet::BulletPool,
+    repair_zones: Vec<repair::RepairZone>,
+    debris: Vec<debris::Debris>,
+    mines: Vec<mine::Mine>,
+    ship_counts: HashMap<i32, usize>,
+    collision_cooldowns: BTreeMap<(ShipHandle, ShipHandle), u32>,
+    frozen_ships: BTreeMap<ShipHandle, LockedAxes>,
+}
+
 pub struct CollisionEventHandler {
     collision_event_sender: Sender<CollisionEvent>,
 }
@@ -436,31 +1451,106 @@ pub struct Particle {
     pub lifetime: f32,
 }
 
+/// Entry in the event log. See [`Simulation::enable_event_log`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimEvent {
+    ShipCreated {
+        tick: u32,
+        ship: ShipHandle,
+        team: i32,
+        class: ShipClass,
+        position: Vector2<f64>,
+    },
+    ShotFired {
+        tick: u32,
+        ship: ShipHandle,
+        gun: usize,
+        position: Vector2<f64>,
+    },
+    BulletHit {
+        tick: u32,
+        /// Destroyed by the hit. With bullet pooling the handle may later be
+        /// reused for a new bullet.
+        bullet: BulletHandle,
+        ship: ShipHandle,
+        position: Vector2<f64>,
+        /// Damage dealt before shields absorbed any of it.
+        damage: f64,
+    },
+    ShipCollision {
+        tick: u32,
+        ships: [ShipHandle; 2],
+        position: Vector2<f64>,
+    },
+    Explosion {
+        tick: u32,
+        /// The ship that exploded, or `None` for a mine.
+        ship: Option<ShipHandle>,
+        position: Vector2<f64>,
+    },
+    ShipDestroyed {
+        tick: u32,
+        ship: ShipHandle,
+        position: Vector2<f64>,
+    },
+    /// The ship's AI was skipped because the step's script budget ran out.
+    ScriptDeferred { tick: u32, ship: ShipHandle },
+}
+
+/// A ship destroyed by damage.
+#[derive(Debug, Clone, Copy)]
+pub struct Kill {
+    pub victim: ShipHandle,
+    pub victim_team: i32,
+    /// Ship that dealt the damage, if known.
+    pub source: Option<ShipHandle>,
+    /// False if the source was destroyed before its damage landed. The handle
+    /// is kept for attribution but no longer refers to a live ship.
+    pub source_alive: bool,
+}
+
+/// Visual effect for a destroyed ship, see [`Simulation::take_explosions`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ExplosionEffect {
+    pub position: Vector2<f64>,
+    pub radius: f64,
+    pub tick: u32,
+}
+
 pub struct SimEvents {
     pub errors: Vec<vm::Error>,
+    pub kills: Vec<Kill>,
+    pub explosions: Vec<ExplosionEffect>,
     pub particles: Vec<Particle>,
     pub debug_lines: Vec<(u64, Vec<Line>)>,
     pub debug_text: BTreeMap<u64, String>,
     pub drawn_text: BTreeMap<u64, Vec<Text>>,
+    pub debug_values: BTreeMap<u64, BTreeMap<String, f64>>,
 }
 
 impl SimEvents {
     pub fn new() -> Self {
         Self {
             errors: vec![],
+            kills: vec![],
+            explosions: vec![],
             particles: vec![],
             debug_lines: Vec::new(),
             debug_text: BTreeMap::new(),
             drawn_text: BTreeMap::new(),
+            debug_values: BTreeMap::new(),
         }
     }
 
     pub fn clear(&mut self) {
         self.errors.clear();
+        self.kills.clear();
+        self.explosions.clear();
         self.particles.clear();
         self.debug_lines.clear();
         self.debug_text.clear();
         self.drawn_text.clear();
+        self.debug_values.clear();
     }
 }
 
@@ -470,6 +1560,51 @@ impl Default for SimEvents {
     }
 }
 
+/// Which teams a [`ShipFilter`] accepts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TeamFilter {
+    #[default]
+    Any,
+    Same(i32),
+    Enemy(i32),
+}
+
+/// Criteria for [`Simulation::nearest_ship`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShipFilter {
+    pub team: TeamFilter,
+    pub class: Option<ShipClass>,
+    /// Ship to skip, usually the one doing the search.
+    pub exclude: Option<ShipHandle>,
+}
+
+impl ShipFilter {
+    pub fn matches(&self, ship: &ShipAccessor) -> bool {
+        let data = ship.data();
+        let team_matches = match self.team {
+            TeamFilter::Any => true,
+            TeamFilter::Same(team) => data.team == team,
+            TeamFilter::Enemy(team) => ship.simulation.are_hostile(data.team, team),
+        };
+        team_matches
+            && self.class.map_or(true, |class| data.class == class)
+            && self.exclude != Some(ship.handle)
+    }
+}
+
+/// Shortest equivalent of `dp` in a toroidal world of the given size.
+pub(crate) fn wrap_displacement(dp: Vector2<f64>, world_size: f64) -> Vector2<f64> {
+    dp - (dp / world_size).map(f64::round) * world_size
+}
+
+/// Wall-clock durations (in seconds) of one step, see
+/// [`Simulation::step_timed`].
+#[derive(Clone, Debug, Default)]
+pub struct StepTiming {
+    pub total: f64,
+    pub phases: Timing,
+}
+
 pub struct Timer {
     start: Instant,
 }
@@ -491,3 +1626,692 @@ impl Default for Timer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::color;
+    use crate::debug::Line;
+    use crate::ship::{self, ColliderShape, ShipClass, ShipData};
+    use crate::simulation::{Code, ShipFilter, Simulation, TeamFilter, PHYSICS_TICK_LENGTH};
+    use nalgebra::vector;
+    use oort_api::DebugValue;
+    use rand::Rng;
+    use test_log::test;
+
+    #[test]
+    fn test_rng_state() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.step();
+        let state = sim.rng_state();
+        let first: Vec<u64> = (0..10).map(|_| sim.rng.gen()).collect();
+        assert_ne!(sim.rng_state(), state);
+
+        sim.set_rng_state(&state);
+        assert_eq!(sim.rng_state(), state);
+        let second: Vec<u64> = (0..10).map(|_| sim.rng.gen()).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_checkpoint_restore() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        for i in 0..10 {
+            let x = i as f64 * 25.0;
+            ship::create(
+                &mut sim,
+                vector![x, 0.0],
+                vector![-x, (i % 3) as f64 * 10.0],
+                0.0,
+                ship::fighter(i % 2),
+            );
+        }
+        let positions = |sim: &Simulation| -> Vec<(u64, [u64; 2])> {
+            sim.ships
+                .iter()
+                .map(|&handle| {
+                    let p = sim.ship(handle).position().vector;
+                    (handle.into(), [p.x.to_bits(), p.y.to_bits()])
+                })
+                .collect()
+        };
+
+        for _ in 0..50 {
+            sim.step();
+        }
+        let checkpoint = sim.checkpoint();
+        for _ in 0..50 {
+            sim.step();
+        }
+        let expected = positions(&sim);
+
+        sim.restore(&checkpoint);
+        assert_eq!(sim.tick(), 50);
+        for _ in 0..50 {
+            sim.step();
+        }
+        assert_eq!(sim.tick(), 100);
+        assert_eq!(positions(&sim), expected);
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip_hash() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ships = [
+            ship::create(
+                &mut sim,
+                vector![-500.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            ),
+            ship::create(
+                &mut sim,
+                vector![500.0, 0.0],
+                vector![0.0, 0.0],
+                std::f64::consts::PI,
+                ship::fighter(1),
+            ),
+        ];
+        // Both ships fire every tick so bullets, hits and damage are covered.
+        let run = |sim: &mut Simulation, ticks: usize| -> Vec<u64> {
+            (0..ticks)
+                .map(|_| {
+                    for handle in ships {
+                        if sim.ships.contains(handle) {
+                            sim.ship_mut(handle).fire(0);
+                        }
+                    }
+                    sim.step();
+                    sim.hash()
+                })
+                .collect()
+        };
+
+        run(&mut sim, 30);
+        let checkpoint = sim.checkpoint();
+        let expected = run(&mut sim, 60);
+        assert!(!sim.bullets.is_empty());
+
+        sim.restore(&checkpoint);
+        assert_eq!(run(&mut sim, 60), expected);
+    }
+
+    #[test]
+    fn test_kinetic_energy_conserved() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let data = || ship::fighter(0).with_collider(ColliderShape::Circle { radius: 10.0 });
+        let ship0 = ship::create(
+            &mut sim,
+            vector![-100.0, 0.0],
+            vector![100.0, 0.0],
+            0.0,
+            data(),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![100.0, 0.0],
+            vector![-100.0, 0.0],
+            0.0,
+            data(),
+        );
+        for (_, collider) in sim.colliders.iter_mut() {
+            collider.set_restitution(1.0);
+        }
+
+        let initial = sim.total_kinetic_energy();
+        let mass = sim.ship(ship0).body().mass();
+        assert!((initial - 2.0 * 0.5 * mass * 100.0 * 100.0).abs() < 1e-6 * initial);
+        for _ in 0..120 {
+            sim.step();
+            let energy = sim.total_kinetic_energy();
+            assert!(
+                (energy - initial).abs() < 0.01 * initial,
+                "tick={} energy={} initial={}",
+                sim.tick(),
+                energy,
+                initial
+            );
+        }
+
+        // The ships bounced off each other.
+        assert!(sim.ship(ship0).velocity().x < 0.0);
+        assert!(sim.ship(ship1).velocity().x > 0.0);
+    }
+
+    #[test]
+    fn test_find_ships() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let mut handles = vec![];
+        for (i, team) in [0, 0, 1, 1, 1].iter().enumerate() {
+            let handle = ship::create(
+                &mut sim,
+                vector![i as f64 * 100.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(*team),
+            );
+            handles.push(handle);
+        }
+        sim.ship_mut(handles[1]).data_mut().health = 10.0;
+        sim.ship_mut(handles[4]).data_mut().health = 10.0;
+
+        assert_eq!(sim.find_ships(|ship| ship.data().team == 0).len(), 2);
+        assert_eq!(sim.find_ships(|ship| ship.data().team == 1).len(), 3);
+        assert_eq!(
+            sim.find_ships(|ship| ship.data().health < 50.0),
+            vec![handles[1], handles[4]]
+        );
+        assert!(sim.find_ships(|ship| ship.data().team == 2).is_empty());
+    }
+
+    #[test]
+    fn test_nearest_ship() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let create = |sim: &mut Simulation, x: f64, data: ShipData| {
+            ship::create(sim, vector![x, 0.0], vector![0.0, 0.0], 0.0, data)
+        };
+        let ship0 = create(&mut sim, 0.0, ship::fighter(0));
+        let friend = create(&mut sim, 100.0, ship::fighter(0));
+        let enemy_missile = create(&mut sim, -200.0, ship::missile(1));
+        let enemy_fighter = create(&mut sim, 300.0, ship::fighter(1));
+        let from = sim.ship(ship0).position().vector.into();
+
+        assert_eq!(
+            sim.nearest_ship(from, Default::default()),
+            Some((ship0, 0.0))
+        );
+        let exclude = Some(ship0);
+        assert_eq!(
+            sim.nearest_ship(
+                from,
+                ShipFilter {
+                    exclude,
+                    ..Default::default()
+                }
+            ),
+            Some((friend, 100.0))
+        );
+        assert_eq!(
+            sim.nearest_ship(
+                from,
+                ShipFilter {
+                    team: TeamFilter::Enemy(0),
+                    ..Default::default()
+                }
+            ),
+            Some((enemy_missile, 200.0))
+        );
+        assert_eq!(
+            sim.nearest_ship(
+                from,
+                ShipFilter {
+                    team: TeamFilter::Enemy(0),
+                    class: Some(ShipClass::Fighter),
+                    exclude,
+                }
+            ),
+            Some((enemy_fighter, 300.0))
+        );
+        assert_eq!(
+            sim.nearest_ship(
+                from,
+                ShipFilter {
+                    team: TeamFilter::Same(1),
+                    class: Some(ShipClass::Cruiser),
+                    exclude,
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_scenario_environment() {
+        let sim = Simulation::new(
+            "fighter_duel",
+            0,
+            &[Code::Builtin("test".to_string()), Code::None],
+        );
+        let controller = sim.team_controllers[&0].clone();
+        let environment = controller.borrow().environment();
+        let lines: Vec<&str> = environment.lines().collect();
+        assert!(lines.contains(&"SCENARIO_NAME=fighter_duel"), "{lines:?}");
+        assert!(lines.contains(&"TEAM_COUNT=2"), "{lines:?}");
+    }
+
+    #[test]
+    fn test_nearest_ship_matches_linear_scan() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let mut rng = crate::rng::new_rng(1);
+        let half = sim.world_size() / 2.0;
+        for i in 0..200 {
+            let position = vector![rng.gen_range(-half..half), rng.gen_range(-half..half)];
+            ship::create(
+                &mut sim,
+                position,
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(i % 2),
+            );
+        }
+        for _ in 0..50 {
+            let from = vector![rng.gen_range(-half..half), rng.gen_range(-half..half)];
+            let filter = ShipFilter {
+                team: TeamFilter::Enemy(0),
+                ..Default::default()
+            };
+            let expected = sim
+                .ships
+                .iter()
+                .map(|&handle| sim.ship(handle))
+                .filter(|ship| filter.matches(ship))
+                .map(|ship| (ship.handle, (ship.position().vector - from).norm()))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b));
+            assert_eq!(sim.nearest_ship(from.into(), filter), expected);
+        }
+    }
+
+    #[test]
+    fn test_ships_within_matches_linear_scan() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let mut rng = crate::rng::new_rng(1);
+        let half = sim.world_size() / 2.0;
+        for i in 0..200 {
+            let position = vector![rng.gen_range(-half..half), rng.gen_range(-half..half)];
+            // Mix sizes so large hulls reach into neighbouring cells.
+            let data = if i % 10 == 0 {
+                ship::cruiser(i % 2)
+            } else {
+                ship::fighter(i % 2)
+            };
+            ship::create(&mut sim, position, vector![0.0, 0.0], 0.0, data);
+        }
+        for _ in 0..50 {
+            let center = vector![rng.gen_range(-half..half), rng.gen_range(-half..half)];
+            let radius = rng.gen_range(0.0..5000.0);
+            let expected: Vec<ship::ShipHandle> = sim
+                .ships
+                .iter()
+                .map(|&handle| sim.ship(handle))
+                .filter(|ship| (ship.position().vector - center).norm() - ship.radius() <= radius)
+                .map(|ship| ship.handle)
+                .collect();
+            assert_eq!(sim.ships_within(center, radius), expected);
+        }
+    }
+
+    #[test]
+    fn test_step_timed() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        for i in 0..10 {
+            ship::create(
+                &mut sim,
+                vector![i as f64 * 100.0, 0.0],
+                vector![0.0, 10.0],
+                0.0,
+                ship::fighter(i % 2),
+            );
+        }
+        for _ in 0..10 {
+            let timing = sim.step_timed();
+            let phases = &timing.phases;
+            for x in [
+                phases.physics,
+                phases.collision,
+                phases.radar,
+                phases.radio,
+                phases.vm,
+                phases.debug,
+                phases.ship,
+                phases.bullet,
+                phases.scenario,
+            ] {
+                assert!(x >= 0.0);
+            }
+            // Only bookkeeping between phases is untimed.
+            assert!(phases.total() <= timing.total);
+            assert!(timing.total - phases.total() < 0.01 + 0.1 * timing.total);
+        }
+    }
+
+    #[test]
+    fn test_debug_values() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let value = |key: &str, value: f64| {
+            let mut v = DebugValue {
+                value,
+                length: key.len() as u8,
+                ..Default::default()
+            };
+            v.key[..key.len()].copy_from_slice(key.as_bytes());
+            v
+        };
+        sim.emit_debug_values(handle, &[value("a", 1.0), value("b", 2.0)]);
+        let values = sim.ship_debug(handle).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["a"], 1.0);
+        assert_eq!(values["b"], 2.0);
+        assert_eq!(sim.snapshot(0).debug_values[&u64::from(handle)], *values);
+
+        sim.step();
+        assert!(sim.ship_debug(handle).is_none());
+    }
+
+    #[test]
+    fn test_set_ship_velocity() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        sim.step();
+        assert_eq!(sim.ship(handle).position().vector, vector![0.0, 0.0]);
+
+        sim.set_ship_velocity(handle, vector![60.0, -30.0], 0.0);
+        sim.step();
+        let ship = sim.ship(handle);
+        assert_eq!(ship.velocity(), vector![60.0, -30.0]);
+        let expected = vector![60.0, -30.0] * PHYSICS_TICK_LENGTH;
+        assert!((ship.position().vector - expected).magnitude() < 1e-9);
+
+        // Rotation is about the centre of mass, which moves the origin, so
+        // check it separately.
+        sim.set_ship_velocity(handle, vector![0.0, 0.0], 1.0);
+        sim.step();
+        let ship = sim.ship(handle);
+        assert_eq!(ship.angular_velocity(), 1.0);
+        assert!((ship.heading() - PHYSICS_TICK_LENGTH).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_set_ship_heading() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        sim.set_ship_heading(handle, 5.0 * std::f64::consts::PI / 2.0);
+        assert!((sim.ship(handle).heading() - std::f64::consts::PI / 2.0).abs() < 1e-9);
+
+        // The force is applied at the end of the first step and integrated
+        // in the second.
+        sim.ship_mut(handle).accelerate(vector![1.0, 0.0]);
+        sim.step();
+        sim.step();
+        let velocity = sim.ship(handle).velocity();
+        assert!(velocity.x.abs() < 1e-9);
+        assert!((velocity.y - PHYSICS_TICK_LENGTH).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_closest_point_of_approach() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let mut create =
+            |position, velocity| ship::create(&mut sim, position, velocity, 0.0, ship::fighter(0));
+        let a = create(vector![0.0, 0.0], vector![100.0, 0.0]);
+        let b = create(vector![1000.0, -1000.0], vector![0.0, 110.0]);
+        let c = create(vector![-1000.0, 0.0], vector![-100.0, 0.0]);
+
+        // Near miss, closest after about ten seconds.
+        let (time, distance) = sim.closest_point_of_approach(a, b);
+        assert!(time > 9.0 && time < 10.0, "{time}");
+        assert!(distance < 100.0, "{distance}");
+        assert_eq!(sim.closest_point_of_approach(b, a), (time, distance));
+
+        // Diverging ships are closest now.
+        let (time, distance) = sim.closest_point_of_approach(a, c);
+        assert_eq!(time, 0.0);
+        assert!((distance - 1000.0).abs() < 1e-9, "{distance}");
+    }
+
+    #[test]
+    fn test_thrust_debug() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            std::f64::consts::PI / 2.0,
+            ship::fighter(0),
+        );
+        let thrust_lines = |sim: &Simulation| -> Vec<Line> {
+            sim.events()
+                .debug_lines
+                .iter()
+                .flat_map(|(_, lines)| lines.iter())
+                .filter(|line| line.color == color::team(0))
+                .cloned()
+                .collect()
+        };
+
+        sim.ship_mut(handle).accelerate(vector![20.0, 0.0]);
+        sim.step();
+        sim.ship_mut(handle).accelerate(vector![20.0, 0.0]);
+        sim.step();
+        assert!(thrust_lines(&sim).is_empty());
+
+        sim.set_thrust_debug(true);
+        sim.ship_mut(handle).accelerate(vector![20.0, 0.0]);
+        sim.step();
+        let lines = thrust_lines(&sim);
+        assert_eq!(lines.len(), 1);
+        let thrust = lines[0].b - lines[0].a;
+        assert!((lines[0].a.coords - sim.ship(handle).position().vector).norm() < 1e-9);
+        assert!((thrust - vector![0.0, 20.0]).norm() < 1e-9, "{thrust}");
+    }
+
+    #[test]
+    fn test_set_ship_frozen() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![100.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        sim.set_ship_frozen(handle, true);
+        assert!(sim.is_ship_frozen(handle));
+        sim.ship_mut(handle)
+            .body()
+            .apply_impulse(vector![0.0, 1e6], true);
+        sim.ship_mut(handle).torque(1.0);
+        for _ in 0..10 {
+            sim.step();
+        }
+        assert_eq!(sim.ship(handle).position().vector, vector![0.0, 0.0]);
+        assert_eq!(sim.ship(handle).heading(), 0.0);
+
+        sim.set_ship_frozen(handle, false);
+        assert!(!sim.is_ship_frozen(handle));
+        sim.ship_mut(handle)
+            .body()
+            .apply_impulse(vector![0.0, 1e6], true);
+        sim.step();
+        assert!(sim.ship(handle).velocity().y > 0.0);
+        assert!(sim.ship(handle).position().y > 0.0);
+
+        sim.set_ship_frozen(handle, true);
+        sim.ship_mut(handle).explode();
+        sim.step();
+        assert!(!sim.ships.contains(handle));
+        assert!(!sim.is_ship_frozen(handle));
+    }
+
+    #[test]
+    fn test_sanitize_ships() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let create = |sim: &mut Simulation, x: f64| {
+            ship::create(
+                sim,
+                vector![x, 0.0],
+                vector![0.0, 10.0],
+                0.0,
+                ship::fighter(0),
+            )
+        };
+        let bad_velocity = create(&mut sim, -100.0);
+        let bad_position = create(&mut sim, 0.0);
+        let healthy = create(&mut sim, 100.0);
+        sim.ship_mut(bad_velocity)
+            .body()
+            .set_linvel(vector![f64::NAN, 0.0], true);
+        sim.ship_mut(bad_position)
+            .body()
+            .set_translation(vector![f64::INFINITY, 0.0], true);
+
+        for _ in 0..10 {
+            sim.step();
+        }
+
+        assert_eq!(sim.ship(bad_velocity).velocity(), vector![0.0, 0.0]);
+        assert_eq!(
+            sim.ship(bad_velocity).position().vector,
+            vector![-100.0, 0.0]
+        );
+        assert!(!sim.ships.contains(bad_position));
+        assert_eq!(sim.ship_errors().len(), 2);
+        assert!(!sim.ship_errors().contains_key(&healthy));
+        let expected = vector![100.0, 10.0 * 10.0 * PHYSICS_TICK_LENGTH];
+        assert!((sim.ship(healthy).position().vector - expected).norm() < 1e-9);
+        assert!((sim.ship(healthy).velocity() - vector![0.0, 10.0]).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_ship_count() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let create = |sim: &mut Simulation, team: i32| {
+            let position = vector![sim.ships.len() as f64, sim.tick() as f64] * 100.0;
+            ship::create(sim, position, vector![0.0, 0.0], 0.0, ship::fighter(team))
+        };
+        let check = |sim: &Simulation| {
+            for team in 0..3 {
+                assert_eq!(
+                    sim.ship_count(team),
+                    sim.find_ships(|ship| ship.data().team == team).len(),
+                    "team={team}"
+                );
+            }
+        };
+
+        assert_eq!(sim.ship_count(0), 0);
+        let a = create(&mut sim, 0);
+        let b = create(&mut sim, 0);
+        let c = create(&mut sim, 1);
+        check(&sim);
+        assert_eq!(sim.ship_count(0), 2);
+        assert_eq!(sim.ship_count(1), 1);
+
+        sim.ship_mut(a).explode();
+        sim.step();
+        check(&sim);
+        assert_eq!(sim.ship_count(0), 1);
+
+        create(&mut sim, 1);
+        sim.ship_mut(c).data_mut().health = 0.0;
+        sim.ship_mut(c).data_mut().destroyed = true;
+        sim.step();
+        check(&sim);
+        assert_eq!(sim.ship_count(1), 1);
+
+        sim.ship_mut(b).explode();
+        create(&mut sim, 0);
+        create(&mut sim, 2);
+        sim.step();
+        check(&sim);
+        assert_eq!(sim.ship_count(0), 1);
+        assert_eq!(sim.ship_count(2), 1);
+    }
+
+    #[test]
+    fn test_dt() {
+        let displacement = |dt: Option<f64>| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            if let Some(dt) = dt {
+                sim.set_dt(dt);
+            }
+            let handle = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![100.0, 0.0],
+                0.0,
+                ship::target(0),
+            );
+            sim.step();
+            let start = sim.ship(handle).position().vector;
+            sim.step();
+            (sim.ship(handle).position().vector - start).x
+        };
+
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        assert_eq!(sim.dt(), PHYSICS_TICK_LENGTH);
+        sim.set_dt(PHYSICS_TICK_LENGTH / 2.0);
+        assert_eq!(sim.dt(), PHYSICS_TICK_LENGTH / 2.0);
+        sim.step();
+        assert_eq!(sim.time(), PHYSICS_TICK_LENGTH / 2.0);
+
+        let full = displacement(None);
+        let half = displacement(Some(PHYSICS_TICK_LENGTH / 2.0));
+        assert!(
+            (full - 100.0 * PHYSICS_TICK_LENGTH).abs() < 1e-6,
+            "full={full}"
+        );
+        assert!((half - full / 2.0).abs() < 1e-6, "full={full} half={half}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_dt() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.set_dt(0.0);
+    }
+
+    #[test]
+    fn test_damping() {
+        let final_speed = |damping: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let before = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![100.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            sim.set_linear_damping(damping);
+            sim.set_angular_damping(damping);
+            assert_eq!(sim.linear_damping(), damping);
+            assert_eq!(sim.angular_damping(), damping);
+            let after = ship::create(
+                &mut sim,
+                vector![0.0, 1000.0],
+                vector![100.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            for _ in 0..600 {
+                sim.step();
+            }
+            let speed = sim.ship(before).velocity().magnitude();
+            assert!((speed - sim.ship(after).velocity().magnitude()).abs() < 1e-9);
+            speed
+        };
+
+        assert!((final_speed(0.0) - 100.0).abs() < 1e-9);
+        let damped = final_speed(0.5);
+        assert!(damped < 10.0, "damped={damped}");
+        assert!(damped > 0.0);
+    }
+}
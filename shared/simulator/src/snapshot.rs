@@ -22,8 +22,14 @@ pub struct Snapshot {
     pub debug_lines: Vec<(u64, Vec<Line>)>,
     pub debug_text: BTreeMap<u64, String>,
     pub drawn_text: BTreeMap<u64, Vec<Text>>,
+    #[serde(default)]
+    pub debug_values: BTreeMap<u64, BTreeMap<String, f64>>,
     pub timing: Timing,
     pub world_size: f64,
+    #[serde(default)]
+    pub team_scores: BTreeMap<i32, f64>,
+    #[serde(default)]
+    pub sudden_death: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -37,8 +43,12 @@ pub struct ShipSnapshot {
     pub team: i32,
     pub class: ShipClass,
     pub health: f64,
+    pub shield_up: bool,
     pub fuel: Option<f64>,
     pub active_abilities: Vec<Ability>,
+    /// Id of the ship this ship's radar is locked on to.
+    #[serde(default)]
+    pub lock_target: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -49,6 +59,33 @@ pub struct BulletSnapshot {
     pub ttl: f32,
 }
 
+/// Minimal per-frame state needed to draw ships and bullets.
+///
+/// Returned by `Simulation::render_state`. Much cheaper than a full [`Snapshot`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RenderState {
+    pub ships: Vec<ShipRenderState>,
+    pub bullets: Vec<BulletRenderState>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShipRenderState {
+    pub id: u64,
+    pub position: Point2<f64>,
+    pub heading: f64,
+    pub radius: f64,
+    pub team: i32,
+    /// Id of the ship this ship's radar is locked on to.
+    #[serde(default)]
+    pub lock_target: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BulletRenderState {
+    pub position: Point2<f64>,
+    pub velocity: Vector2<f64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Timing {
     pub physics: f64,
@@ -56,6 +93,9 @@ pub struct Timing {
     pub radar: f64,
     pub radio: f64,
     pub vm: f64,
+    /// Generating per-ship debug lines.
+    #[serde(default)]
+    pub debug: f64,
     pub ship: f64,
     pub bullet: f64,
     pub scenario: f64,
@@ -68,6 +108,7 @@ impl Timing {
             + self.radar
             + self.radio
             + self.vm
+            + self.debug
             + self.ship
             + self.bullet
             + self.scenario
@@ -84,6 +125,7 @@ impl std::ops::Add for Timing {
             radar: self.radar + other.radar,
             radio: self.radio + other.radio,
             vm: self.vm + other.vm,
+            debug: self.debug + other.debug,
             ship: self.ship + other.ship,
             bullet: self.bullet + other.bullet,
             scenario: self.scenario + other.scenario,
@@ -107,6 +149,7 @@ impl std::ops::Mul<f64> for Timing {
             radar: self.radar * other,
             radio: self.radio * other,
             vm: self.vm * other,
+            debug: self.debug * other,
             ship: self.ship * other,
             bullet: self.bullet * other,
             scenario: self.scenario * other,
@@ -3,10 +3,11 @@ mod limiter;
 
 use crate::color;
 use crate::debug;
+use crate::radar::ScanResult;
 use crate::ship::{ShipClass, ShipHandle};
-use crate::simulation::{Code, Simulation};
-use nalgebra::point;
-use oort_api::{ActiveAbilities, Class, EcmMode, Line, SystemState, Text};
+use crate::simulation::{Code, SimEvent, Simulation};
+use nalgebra::{point, vector};
+use oort_api::{ActiveAbilities, Class, DebugValue, EcmMode, Line, SystemState, Text};
 use serde::{Deserialize, Serialize};
 use std::cell::{Ref, RefCell, RefMut};
 use std::collections::BTreeMap;
@@ -21,6 +22,7 @@ pub type Environment = BTreeMap<String, String>;
 const GAS_PER_TICK: i32 = 1_000_000;
 const MAX_DEBUG_LINES: u32 = 1024;
 const MAX_DRAWN_TEXT: u32 = 128;
+const MAX_DEBUG_VALUES: u32 = 128;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Error {
@@ -60,14 +62,21 @@ pub struct TeamController {
     vm: WasmVm,
     states: HashMap<ShipHandle, LocalSystemState>,
     next_id: u32,
+    /// First ship deferred by the script budget, which runs first next tick.
+    resume_from: Option<ShipHandle>,
 }
 
 impl TeamController {
     pub fn create(code: &Code) -> Result<Box<TeamController>, Error> {
+        Self::create_from_ai(&CompiledAi::new(code)?)
+    }
+
+    pub fn create_from_ai(ai: &CompiledAi) -> Result<Box<TeamController>, Error> {
         Ok(Box::new(TeamController {
-            vm: WasmVm::create(code)?,
+            vm: WasmVm::instantiate(ai)?,
             states: HashMap::new(),
             next_id: 1,
+            resume_from: None,
         }))
     }
 
@@ -81,10 +90,11 @@ impl TeamController {
         state.set(SystemState::Id, self.next_id as f64);
         self.next_id += 1;
         if let Some(radar) = sim.ship(handle).data().radar.as_ref() {
-            state.set(SystemState::RadarHeading, radar.heading);
+            state.set(SystemState::RadarHeading, radar.target_heading);
             state.set(SystemState::RadarWidth, radar.width);
             state.set(SystemState::RadarMinDistance, radar.min_distance);
             state.set(SystemState::RadarMaxDistance, radar.max_distance);
+            state.set(SystemState::RadarMinRssi, radar.min_rssi);
         }
 
         self.states.insert(handle, state);
@@ -109,16 +119,42 @@ impl TeamController {
         }
     }
 
-    pub fn tick(&mut self, sim: &mut Simulation) {
+    /// Runs every ship's AI, returning whether any were deferred by the
+    /// script budget.
+    pub fn tick(&mut self, sim: &mut Simulation) -> bool {
         let mut handles: Vec<_> = self.states.keys().cloned().collect();
         handles.sort_by_key(|x| x.0);
+        if let Some(start) = self.resume_from.take() {
+            let split = handles.partition_point(|x| x.0 < start.0);
+            handles.rotate_left(split);
+        }
 
         for handle in handles {
+            let state = self.states.get_mut(&handle).unwrap();
+            if state.idle_ticks > 0 {
+                state.idle_ticks -= 1;
+                sim.ship_mut(handle).accelerate(state.held_acceleration);
+                sim.ship_mut(handle).torque(state.held_torque);
+                continue;
+            }
+            if sim.script_budget_exceeded() {
+                sim.ship_mut(handle).accelerate(state.held_acceleration);
+                sim.ship_mut(handle).torque(state.held_torque);
+                let tick = sim.tick();
+                sim.log_event(SimEvent::ScriptDeferred { tick, ship: handle });
+                self.resume_from.get_or_insert(handle);
+                continue;
+            }
+            state.idle_ticks = sim.ship(handle).data().think_interval.max(1) - 1;
+            sim.scripts_run += 1;
             if let Err(e) = self.tick_ship(sim, handle) {
                 log::warn!("{}", e.msg);
-                sim.ship_mut(handle).explode();
+                // Stop running this ship's AI but leave the ship in play.
+                self.states.remove(&handle);
+                sim.record_ship_error(handle, e.msg);
             }
         }
+        self.resume_from.is_some()
     }
 
     fn tick_ship(&mut self, sim: &mut Simulation, handle: ShipHandle) -> Result<(), Error> {
@@ -133,6 +169,23 @@ impl TeamController {
 
             generate_system_state(sim, handle, state);
 
+            let tracks = vm
+                .radar_tracks_ptr
+                .map(|_| generate_radar_tracks(sim, handle))
+                .unwrap_or_default();
+            state.set(
+                SystemState::RadarTrackCount,
+                (tracks.len() / oort_api::RADAR_TRACK_SIZE) as f64,
+            );
+            let contacts = vm
+                .radar_contacts_ptr
+                .map(|_| generate_radar_contacts(sim, handle))
+                .unwrap_or_default();
+            state.set(
+                SystemState::RadarContactCount,
+                (contacts.len() / oort_api::RADAR_CONTACT_SIZE) as f64,
+            );
+
             let store = vm.store();
             let memory_view = vm.memory.view(store.deref());
             let slice = vm
@@ -140,6 +193,58 @@ impl TeamController {
                 .slice(&memory_view, SystemState::Size as u32)
                 .expect("system state write");
             slice.write_slice(&state.state).expect("system state write");
+
+            if let Some(ptr) = vm.radar_tracks_ptr {
+                let slice = ptr
+                    .slice(&memory_view, tracks.len() as u32)
+                    .expect("radar tracks write");
+                slice.write_slice(&tracks).expect("radar tracks write");
+            }
+
+            if let Some(ptr) = vm.radar_contacts_ptr {
+                let slice = ptr
+                    .slice(&memory_view, contacts.len() as u32)
+                    .expect("radar contacts write");
+                slice.write_slice(&contacts).expect("radar contacts write");
+            }
+
+            if let Some(ptr) = vm.radar_closest_contact_ptr {
+                let contact = generate_radar_closest_contact(sim, handle);
+                let slice = ptr
+                    .slice(&memory_view, contact.len() as u32)
+                    .expect("radar closest contact write");
+                slice
+                    .write_slice(&contact)
+                    .expect("radar closest contact write");
+            }
+
+            if let Some(ptr) = vm.gun_muzzle_velocities_ptr {
+                let velocities: [u64; oort_api::MAX_GUNS] =
+                    std::array::from_fn(|i| sim.ship(handle).gun_muzzle_velocity(i).to_bits());
+                let slice = ptr
+                    .slice(&memory_view, velocities.len() as u32)
+                    .expect("gun muzzle velocities write");
+                slice
+                    .write_slice(&velocities)
+                    .expect("gun muzzle velocities write");
+            }
+
+            if let Some(ptr) = vm.gun_bullet_ttls_ptr {
+                let ttls: [u64; oort_api::MAX_GUNS] =
+                    std::array::from_fn(|i| sim.ship(handle).gun_bullet_ttl(i).to_bits());
+                let slice = ptr
+                    .slice(&memory_view, ttls.len() as u32)
+                    .expect("gun bullet ttls write");
+                slice.write_slice(&ttls).expect("gun bullet ttls write");
+            }
+
+            if let Some(ptr) = vm.friendly_contact_ptr {
+                let contact = generate_friendly_contact(sim, handle);
+                let slice = ptr
+                    .slice(&memory_view, contact.len() as u32)
+                    .expect("friendly contact write");
+                slice.write_slice(&contact).expect("friendly contact write");
+            }
         }
 
         let (index, _) = handle.0.into_raw_parts();
@@ -153,7 +258,7 @@ impl TeamController {
                     let gas: i32 = ret[0].i32().unwrap();
                     if gas <= 0 {
                         return Err(Error {
-                            msg: "Ship exceeded maximum number of instructions and was destroyed"
+                            msg: "Ship exceeded maximum number of instructions and was disabled"
                                 .to_string(),
                         });
                     }
@@ -215,6 +320,20 @@ impl TeamController {
                     }
                 }
             }
+
+            if state.get(SystemState::DebugValuesLength) > 0.0 {
+                let offset = state.get(SystemState::DebugValuesPointer) as u32;
+                let length = state.get(SystemState::DebugValuesLength) as u32;
+                if length <= MAX_DEBUG_VALUES {
+                    if let Some(values) =
+                        WasmVm::read_vec::<DebugValue>(&memory_view, offset, length)
+                    {
+                        if validate_debug_values(&values) {
+                            sim.emit_debug_values(handle, &values);
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -223,26 +342,25 @@ impl TeamController {
     pub fn update_environment(&mut self, environment: &Environment) -> Result<(), Error> {
         self.vm.update_environment(environment)
     }
+
+    /// Returns the environment string as the AI sees it.
+    #[cfg(test)]
+    pub(crate) fn environment(&self) -> String {
+        self.vm.read_environment()
+    }
 }
 
+/// An AI compiled once and instantiated cheaply in any number of simulations,
+/// e.g. for every matchup of a tournament.
 #[derive(Clone)]
-pub struct WasmVm {
-    store: Rc<RefCell<wasmer::Store>>,
-    memory: wasmer::Memory,
-    system_state_ptr: WasmPtr<u64>,
-    environment_ptr: WasmPtr<u8>,
-    tick_ship: wasmer::Function,
-    delete_ship: wasmer::Function,
-    reset_gas: wasmer::Function,
-    get_gas: wasmer::Function,
+pub struct CompiledAi {
+    engine: wasmer::Engine,
+    module: Module,
 }
 
-impl WasmVm {
-    pub fn create(code: &Code) -> Result<WasmVm, Error> {
-        #[cfg(feature = "js")]
-        let mut store = Store::default();
-        #[cfg(feature = "sys")]
-        let mut store = Store::new(wasmer_compiler_singlepass::Singlepass::new());
+impl CompiledAi {
+    pub fn new(code: &Code) -> Result<CompiledAi, Error> {
+        let store = new_store();
         let module = match code {
             Code::Wasm(wasm) => {
                 let wasm = limiter::rewrite(wasm)?;
@@ -252,10 +370,61 @@ impl WasmVm {
             Code::Precompiled(bytes) => {
                 translate_error(unsafe { Module::deserialize(&store, bytes.clone()) })?
             }
+            Code::Builtin(name) => {
+                return match builtin::load_compiled(name) {
+                    Ok(code) => CompiledAi::new(&code),
+                    Err(e) => Err(Error { msg: e }),
+                }
+            }
             _ => unreachable!(),
         };
+        Ok(CompiledAi {
+            engine: store.engine().clone(),
+            module,
+        })
+    }
+}
+
+#[cfg(feature = "js")]
+fn new_store() -> Store {
+    Store::default()
+}
+
+#[cfg(feature = "sys")]
+fn new_store() -> Store {
+    Store::new(wasmer_compiler_singlepass::Singlepass::new())
+}
+
+#[derive(Clone)]
+pub struct WasmVm {
+    store: Rc<RefCell<wasmer::Store>>,
+    memory: wasmer::Memory,
+    system_state_ptr: WasmPtr<u64>,
+    environment_ptr: WasmPtr<u8>,
+    radar_tracks_ptr: Option<WasmPtr<u64>>,
+    radar_closest_contact_ptr: Option<WasmPtr<u64>>,
+    radar_contacts_ptr: Option<WasmPtr<u64>>,
+    friendly_contact_ptr: Option<WasmPtr<u64>>,
+    gun_muzzle_velocities_ptr: Option<WasmPtr<u64>>,
+    gun_bullet_ttls_ptr: Option<WasmPtr<u64>>,
+    tick_ship: wasmer::Function,
+    delete_ship: wasmer::Function,
+    reset_gas: wasmer::Function,
+    get_gas: wasmer::Function,
+}
+
+impl WasmVm {
+    pub fn create(code: &Code) -> Result<WasmVm, Error> {
+        Self::instantiate(&CompiledAi::new(code)?)
+    }
+
+    /// Creates a fresh instance of an already compiled AI.
+    pub fn instantiate(ai: &CompiledAi) -> Result<WasmVm, Error> {
+        // The module can only be instantiated in a store using the engine it
+        // was compiled with.
+        let mut store = Store::new(ai.engine.clone());
         let import_object = imports! {};
-        let instance = Instance::new(&mut store, &module, &import_object)?;
+        let instance = Instance::new(&mut store, &ai.module, &import_object)?;
 
         let memory = translate_error(instance.exports.get_memory("memory"))?.clone();
         let system_state_offset: i32 =
@@ -269,6 +438,43 @@ impl WasmVm {
             .i32()
             .unwrap();
         let environment_ptr: WasmPtr<u8> = WasmPtr::new(environment_offset as u32);
+        // Optional, older AIs don't export the track buffer.
+        let radar_tracks_ptr: Option<WasmPtr<u64>> = instance
+            .exports
+            .get_global("RADAR_TRACKS")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| WasmPtr::new(offset as u32));
+        let radar_closest_contact_ptr: Option<WasmPtr<u64>> = instance
+            .exports
+            .get_global("RADAR_CLOSEST_CONTACT")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| WasmPtr::new(offset as u32));
+        let radar_contacts_ptr: Option<WasmPtr<u64>> = instance
+            .exports
+            .get_global("RADAR_CONTACTS")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| WasmPtr::new(offset as u32));
+        let friendly_contact_ptr: Option<WasmPtr<u64>> = instance
+            .exports
+            .get_global("FRIENDLY_CONTACT")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| WasmPtr::new(offset as u32));
+        let gun_muzzle_velocities_ptr: Option<WasmPtr<u64>> = instance
+            .exports
+            .get_global("GUN_MUZZLE_VELOCITIES")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| WasmPtr::new(offset as u32));
+        let gun_bullet_ttls_ptr: Option<WasmPtr<u64>> = instance
+            .exports
+            .get_global("GUN_BULLET_TTLS")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| WasmPtr::new(offset as u32));
 
         let initialize =
             translate_error(instance.exports.get_function("export_initialize"))?.clone();
@@ -286,6 +492,12 @@ impl WasmVm {
             memory,
             system_state_ptr,
             environment_ptr,
+            radar_tracks_ptr,
+            radar_closest_contact_ptr,
+            radar_contacts_ptr,
+            friendly_contact_ptr,
+            gun_muzzle_velocities_ptr,
+            gun_bullet_ttls_ptr,
             tick_ship,
             delete_ship,
             reset_gas,
@@ -348,16 +560,38 @@ impl WasmVm {
             .unwrap();
         Ok(())
     }
+
+    #[cfg(test)]
+    fn read_environment(&self) -> String {
+        let store = self.store();
+        let view = self.memory.view(store.deref());
+        let bytes = self
+            .environment_ptr
+            .slice(&view, oort_api::MAX_ENVIRONMENT_SIZE as u32)
+            .and_then(|slice| slice.read_to_vec())
+            .unwrap();
+        String::from_utf8_lossy(&bytes)
+            .trim_end_matches('\0')
+            .to_string()
+    }
 }
 
 struct LocalSystemState {
     pub state: [u64; SystemState::Size as usize],
+    /// Ticks left before the ship's AI runs again, see
+    /// [`ShipData::think_interval`](crate::ship::ShipData::think_interval).
+    idle_ticks: u32,
+    held_acceleration: Vec2,
+    held_torque: f64,
 }
 
 impl LocalSystemState {
     fn new() -> Self {
         Self {
             state: [0; SystemState::Size as usize],
+            idle_ticks: 0,
+            held_acceleration: Vec2::zeros(),
+            held_torque: 0.0,
         }
     }
 
@@ -383,6 +617,92 @@ impl LocalSystemState {
     }
 }
 
+fn generate_radar_tracks(sim: &Simulation, handle: ShipHandle) -> Vec<u64> {
+    let mut result = vec![];
+    if let Some(radar) = sim.ship(handle).data().radar.as_ref() {
+        for track in radar.tracks().iter().take(oort_api::MAX_RADAR_TRACKS) {
+            result.extend(
+                [
+                    track.id as f64,
+                    track.class.map_or(Class::Unknown, translate_class) as u32 as f64,
+                    track.position.x,
+                    track.position.y,
+                    track.velocity.x,
+                    track.velocity.y,
+                    track.confidence,
+                    track.ticks_since_seen as f64,
+                ]
+                .map(f64::to_bits),
+            );
+        }
+    }
+    result
+}
+
+fn generate_radar_closest_contact(
+    sim: &Simulation,
+    handle: ShipHandle,
+) -> [u64; oort_api::RADAR_CONTACT_SIZE] {
+    let contact = sim
+        .ship(handle)
+        .data()
+        .radar
+        .as_ref()
+        .and_then(|radar| radar.closest_contact());
+    match contact {
+        Some(contact) => scan_result_fields(&contact),
+        None => [0.0; oort_api::RADAR_CONTACT_SIZE],
+    }
+    .map(f64::to_bits)
+}
+
+fn generate_radar_contacts(sim: &Simulation, handle: ShipHandle) -> Vec<u64> {
+    let mut result = vec![];
+    if let Some(radar) = sim.ship(handle).data().radar.as_ref() {
+        for contact in radar.scan_all().iter().take(oort_api::MAX_RADAR_CONTACTS) {
+            result.extend(scan_result_fields(contact).map(f64::to_bits));
+        }
+    }
+    result
+}
+
+fn scan_result_fields(contact: &ScanResult) -> [f64; oort_api::RADAR_CONTACT_SIZE] {
+    [
+        1.0,
+        contact.class.map_or(Class::Unknown, translate_class) as u32 as f64,
+        contact.position.x,
+        contact.position.y,
+        contact.velocity.x,
+        contact.velocity.y,
+        contact.rssi,
+        contact.snr,
+        contact.closing_speed,
+    ]
+}
+
+fn generate_friendly_contact(
+    sim: &Simulation,
+    handle: ShipHandle,
+) -> [u64; oort_api::FRIENDLY_CONTACT_SIZE] {
+    match sim.ship(handle).nearest_friendly() {
+        Some(friendly) => {
+            let ship = sim.ship(friendly);
+            let position = ship.position().vector;
+            let velocity = ship.velocity();
+            [
+                1.0,
+                translate_class(ship.data().class) as u32 as f64,
+                position.x,
+                position.y,
+                velocity.x,
+                velocity.y,
+            ]
+        }
+        None => [0.0; oort_api::FRIENDLY_CONTACT_SIZE],
+    }
+    .map(f64::to_bits)
+}
+
 fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut LocalSystemState) {
     state.set(
         SystemState::Class,
@@ -403,11 +723,49 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         sim.ship(handle).angular_velocity(),
     );
 
+    state.set(
+        SystemState::ProximityFuze,
+        sim.ship(handle).data().proximity_fuze.unwrap_or(0.0),
+    );
+    state.set(
+        SystemState::RadarJammerBearing,
+        sim.ship(handle)
+            .radar()
+            .and_then(|radar| radar.jammer_bearing())
+            .unwrap_or(f64::NAN),
+    );
     if let Some(radar) = sim.ship_mut(handle).data_mut().radar.as_mut() {
-        state.set(SystemState::RadarHeading, radar.get_heading());
+        state.set(SystemState::RadarHeading, radar.get_target_heading());
+        state.set(SystemState::RadarAppliedHeading, radar.get_heading());
+        state.set(
+            SystemState::RadarSlewRate,
+            radar.get_slew_rate().unwrap_or(f64::INFINITY),
+        );
         state.set(SystemState::RadarWidth, radar.get_width());
         state.set(SystemState::RadarMinDistance, radar.get_min_distance());
         state.set(SystemState::RadarMaxDistance, radar.get_max_distance());
+        state.set(SystemState::RadarMinRssi, radar.get_min_rssi());
+        state.set(
+            SystemState::RadarBearingOnlyRssi,
+            radar.get_bearing_only_rssi().unwrap_or(0.0),
+        );
+        state.set(
+            SystemState::RadarTrackContact,
+            radar.tracked_contact().is_some() as u32 as f64,
+        );
+        state.set(
+            SystemState::RadarEnergy,
+            radar.get_energy().unwrap_or(f64::INFINITY),
+        );
+        state.set(
+            SystemState::RadarPingRange,
+            radar.ping_range().unwrap_or(f64::INFINITY),
+        );
+        if let Some(beam) = radar.last_beam() {
+            state.set(SystemState::RadarBeamHeading, beam.heading);
+            state.set(SystemState::RadarBeamWidth, beam.width);
+            state.set(SystemState::RadarBeamRange, beam.range);
+        }
 
         if let Some(contact) = radar.scan() {
             state.set(SystemState::RadarContactFound, 1.0);
@@ -417,10 +775,23 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
             state.set(SystemState::RadarContactVelocityY, contact.velocity.y);
             state.set(
                 SystemState::RadarContactClass,
-                translate_class(contact.class) as u32 as f64,
+                contact.class.map_or(Class::Unknown, translate_class) as u32 as f64,
             );
             state.set(SystemState::RadarContactRssi, contact.rssi);
             state.set(SystemState::RadarContactSnr, contact.snr);
+            state.set(
+                SystemState::RadarContactBearingOnly,
+                contact.bearing_only as u32 as f64,
+            );
+            state.set(SystemState::RadarContactClosingSpeed, contact.closing_speed);
+            state.set(
+                SystemState::RadarContactAccelerationX,
+                contact.acceleration.x,
+            );
+            state.set(
+                SystemState::RadarContactAccelerationY,
+                contact.acceleration.y,
+            );
         } else {
             state.set(SystemState::RadarContactFound, 0.0);
         }
@@ -456,8 +827,26 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
             data.max_angular_acceleration,
         );
         state.set(SystemState::Health, data.health);
+        state.set(SystemState::Shield, data.shield);
+        state.set(
+            SystemState::Burning,
+            if data.is_burning() { 1.0 } else { 0.0 },
+        );
         state.set(SystemState::Fuel, data.fuel.unwrap_or(f64::INFINITY));
+        state.set(SystemState::ThermalSignature, data.thermal_signature);
+        state.set(
+            SystemState::BeamEnergy,
+            data.beam.as_ref().map_or(0.0, |beam| beam.energy),
+        );
     }
+    state.set(
+        SystemState::ImpulseReady,
+        if sim.ship(handle).impulse_ready() {
+            1.0
+        } else {
+            0.0
+        },
+    );
 
     for (i, radio) in sim.ship(handle).data().radios.iter().enumerate() {
         let idxs = oort_api::prelude::radio_internal::radio_indices(i);
@@ -487,17 +876,82 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
     {
         state.set(*idx, sim.ship(handle).get_reload_ticks(i) as f64)
     }
+
+    for (i, idx) in [
+        SystemState::Ammo0,
+        SystemState::Ammo1,
+        SystemState::Ammo2,
+        SystemState::Ammo3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let ammo = match sim.ship(handle).data().guns.get(i) {
+            Some(gun) => gun.ammo.map_or(f64::INFINITY, |ammo| ammo as f64),
+            None => 0.0,
+        };
+        state.set(*idx, ammo);
+    }
+
+    for (i, idx) in [
+        SystemState::JamTicks0,
+        SystemState::JamTicks1,
+        SystemState::JamTicks2,
+        SystemState::JamTicks3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        state.set(*idx, sim.ship(handle).weapon_cooldown_remaining(i) as f64);
+    }
+
+    for (i, idx) in [
+        SystemState::Heat0,
+        SystemState::Heat1,
+        SystemState::Heat2,
+        SystemState::Heat3,
+    ]
+    .iter()
+    .enumerate()
+    {
+        let heat = sim
+            .ship(handle)
+            .data()
+            .guns
+            .get(i)
+            .map_or(0.0, |gun| gun.heat);
+        state.set(*idx, heat);
+    }
 }
 
 fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut LocalSystemState) {
-    sim.ship_mut(handle).accelerate(Vec2::new(
+    state.held_acceleration = Vec2::new(
         state.get(SystemState::AccelerateX),
         state.get(SystemState::AccelerateY),
-    ));
+    );
+    sim.ship_mut(handle).accelerate(state.held_acceleration);
     state.set(SystemState::AccelerateX, 0.0);
     state.set(SystemState::AccelerateY, 0.0);
 
-    sim.ship_mut(handle).torque(state.get(SystemState::Torque));
+    let impulse = vector![
+        state.get(SystemState::ImpulseX),
+        state.get(SystemState::ImpulseY)
+    ];
+    if impulse != vector![0.0, 0.0] {
+        sim.ship_mut(handle).impulse(impulse);
+    }
+    state.set(SystemState::ImpulseX, 0.0);
+    state.set(SystemState::ImpulseY, 0.0);
+
+    let beam_range = state.get(SystemState::BeamRange);
+    if beam_range > 0.0 {
+        sim.ship_mut(handle)
+            .fire_beam(state.get(SystemState::BeamHeading), beam_range);
+    }
+    state.set(SystemState::BeamRange, 0.0);
+
+    state.held_torque = state.get(SystemState::Torque);
+    sim.ship_mut(handle).torque(state.held_torque);
     state.set(SystemState::Torque, 0.0);
 
     for (i, (aim, fire)) in [
@@ -521,8 +975,21 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         radar.set_width(state.get(SystemState::RadarWidth));
         radar.set_min_distance(state.get(SystemState::RadarMinDistance));
         radar.set_max_distance(state.get(SystemState::RadarMaxDistance));
+        radar.set_min_rssi(state.get(SystemState::RadarMinRssi));
+        let bearing_only_rssi = state.get(SystemState::RadarBearingOnlyRssi);
+        radar.set_bearing_only_rssi((bearing_only_rssi > 0.0).then_some(bearing_only_rssi));
         radar.set_ecm_mode(translate_ecm_mode(state.get(SystemState::RadarEcmMode)));
+        let lock = state.get(SystemState::RadarTrackContact) > 0.0;
+        if lock && radar.tracked_contact().is_none() {
+            radar.track_contact();
+        } else if !lock {
+            radar.untrack_contact();
+        }
+        if state.get(SystemState::RadarPing) > 0.0 {
+            radar.request_ping();
+        }
     }
+    state.set(SystemState::RadarPing, 0.0);
 
     let active_abilities = ActiveAbilities(state.get_u64(SystemState::ActivateAbility));
     for &ability in oort_api::ABILITIES {
@@ -537,6 +1004,10 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         }
     }
 
+    let proximity_fuze = state.get(SystemState::ProximityFuze);
+    sim.ship_mut(handle).data_mut().proximity_fuze =
+        (proximity_fuze > 0.0).then_some(proximity_fuze);
+
     if state.get(SystemState::Explode) > 0.0 {
         sim.ship_mut(handle).explode();
         state.set(SystemState::Explode, 0.0);
@@ -624,6 +1095,12 @@ fn validate_texts(texts: &[Text]) -> bool {
         .all(|t| validate_floats(&[t.x, t.y]) && t.length as usize <= t.text.len())
 }
 
+fn validate_debug_values(values: &[DebugValue]) -> bool {
+    values
+        .iter()
+        .all(|v| v.value.is_finite() && v.length as usize <= v.key.len())
+}
+
 #[cfg(feature = "precompile")]
 pub fn precompile(code: &[u8]) -> Result<Code, Error> {
     let code = limiter::rewrite(code)?;
@@ -95,7 +95,7 @@ fn test_decoy() {
             .scan()
             .unwrap()
             .class,
-        ShipClass::Cruiser
+        Some(ShipClass::Cruiser)
     );
 }
 
@@ -127,3 +127,59 @@ fn test_shield() {
     assert_ne!(sim.ship(ship0).data().health, frigate(0).health);
     assert_eq!(sim.ship(ship1).data().health, cruiser(1).health);
 }
+
+#[test]
+fn test_impulse() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    assert!(sim.ship(ship0).impulse_ready());
+
+    // The velocity change is immediate and clamped.
+    assert!(sim.ship_mut(ship0).impulse(vector![0.0, 500.0]));
+    assert!((sim.ship(ship0).velocity() - vector![0.0, 100.0]).norm() < 1e-6);
+    assert!(!sim.ship(ship0).impulse_ready());
+
+    // A second impulse during the cooldown is ignored.
+    sim.step();
+    assert!(!sim.ship_mut(ship0).impulse(vector![50.0, 0.0]));
+    assert!((sim.ship(ship0).velocity() - vector![0.0, 100.0]).norm() < 1e-6);
+
+    for _ in 1..ship::IMPULSE_COOLDOWN_TICKS {
+        assert!(!sim.ship(ship0).impulse_ready());
+        sim.step();
+    }
+    assert!(sim.ship(ship0).impulse_ready());
+    assert!(sim.ship_mut(ship0).impulse(vector![50.0, 0.0]));
+    assert!((sim.ship(ship0).velocity() - vector![50.0, 100.0]).norm() < 1e-6);
+
+    // Ships without an impulse drive can't use it.
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        frigate(0),
+    );
+    assert!(!sim.ship(ship1).impulse_ready());
+    assert!(!sim.ship_mut(ship1).impulse(vector![50.0, 0.0]));
+
+    // Without fuel the impulse fails and doesn't start the cooldown.
+    let ship2 = ship::create(
+        &mut sim,
+        vector![2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.ship_mut(ship2).data_mut().fuel = Some(0.0);
+    assert!(!sim.ship(ship2).impulse_ready());
+    assert!(!sim.ship_mut(ship2).impulse(vector![50.0, 0.0]));
+    assert_eq!(sim.ship(ship2).velocity(), vector![0.0, 0.0]);
+    assert_eq!(sim.ship(ship2).data().impulse_cooldown_ticks_remaining, 0);
+}
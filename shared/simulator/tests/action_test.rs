@@ -0,0 +1,91 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, ShipAction};
+use oort_simulator::simulation::{self, Code};
+use test_log::test;
+
+#[test]
+fn test_thrust_action() {
+    let mut sim0 = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut sim1 = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim0,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim1,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    let action = ShipAction {
+        acceleration: vector![50.0, 10.0],
+        torque: 1.0,
+        ..Default::default()
+    };
+    for _ in 0..60 {
+        sim0.apply_action(ship0, &action);
+        sim0.step();
+
+        sim1.ship_mut(ship1).accelerate(vector![50.0, 10.0]);
+        sim1.ship_mut(ship1).torque(1.0);
+        sim1.step();
+    }
+
+    assert!(sim0.ship(ship0).position().x > 0.0);
+    assert_eq!(sim0.ship(ship0).position(), sim1.ship(ship1).position());
+    assert_eq!(sim0.ship(ship0).velocity(), sim1.ship(ship1).velocity());
+    assert_eq!(sim0.ship(ship0).heading(), sim1.ship(ship1).heading());
+}
+
+#[test]
+fn test_fire_action() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    let action = ShipAction {
+        fire: [true, false, false, false],
+        ..Default::default()
+    };
+    sim.apply_action(ship0, &action);
+    assert_eq!(sim.bullets.len(), 1);
+}
+
+#[test]
+fn test_commanded_force() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        std::f64::consts::FRAC_PI_2,
+        fighter(0),
+    );
+    let data = fighter(0);
+    let mass = sim.ship(ship0).body().mass();
+
+    // Forward thrust beyond the limit is clamped and rotated into the world frame.
+    sim.ship_mut(ship0).accelerate(vector![1000.0, 0.0]);
+    sim.ship_mut(ship0).torque(-100.0);
+    let force = sim.ship(ship0).commanded_force();
+    let expected = vector![0.0, data.max_forward_acceleration * mass];
+    assert!((force - expected).magnitude() < 1e-6 * expected.magnitude());
+    let torque = sim.ship(ship0).commanded_torque();
+    assert!(torque < 0.0);
+    sim.ship_mut(ship0).torque(-data.max_angular_acceleration);
+    assert_eq!(sim.ship(ship0).commanded_torque(), torque);
+
+    sim.step();
+    assert_eq!(sim.ship(ship0).commanded_force(), vector![0.0, 0.0]);
+    assert_eq!(sim.ship(ship0).commanded_torque(), 0.0);
+}
@@ -1,6 +1,6 @@
 use nalgebra::vector;
 use oort_simulator::ship::{self, fighter, ShipHandle};
-use oort_simulator::simulation::{self, Code};
+use oort_simulator::simulation::{self, Code, SimEvent};
 use std::collections::BTreeMap;
 use test_log::test;
 
@@ -99,3 +99,103 @@ fn test_id() {
     check(ship_handles[1], 2);
     check(ship_handles[2], 1);
 }
+
+#[test]
+fn test_debug_values() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "debug_values".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![30.0, 40.0],
+        0.0,
+        fighter(0),
+    );
+    sim.step();
+    let values = sim.ship_debug(ship0).expect("Missing debug values");
+    assert_eq!(values.len(), 2);
+    assert_eq!(values["answer"], 42.0);
+    assert!((values["speed"] - 50.0).abs() < 1e-9);
+
+    // The script only sets them on its first tick.
+    sim.step();
+    assert!(sim.ship_debug(ship0).is_none());
+}
+
+#[test]
+fn test_think_interval() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.set_team_think_interval(0, 5);
+    assert_eq!(sim.ship(ship0).data().think_interval, 5);
+
+    // The test AI emits debug text every time it runs.
+    let mut thought = vec![];
+    for _ in 0..12 {
+        sim.step();
+        if sim.events().debug_text.contains_key(&ship0.into()) {
+            thought.push(sim.tick() - 1);
+        }
+    }
+    assert_eq!(thought, vec![0, 5, 10]);
+}
+
+#[test]
+fn test_script_budget() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let ships: Vec<_> = (0..3)
+        .map(|i| {
+            ship::create(
+                &mut sim,
+                vector![i as f64 * 100.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                fighter(0),
+            )
+        })
+        .collect();
+    sim.enable_event_log();
+    let ran = |sim: &simulation::Simulation| {
+        ships
+            .iter()
+            .cloned()
+            .filter(|&handle| sim.events().debug_text.contains_key(&handle.into()))
+            .collect::<Vec<_>>()
+    };
+    let deferred = |sim: &mut simulation::Simulation| {
+        sim.drain_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                SimEvent::ScriptDeferred { ship, .. } => Some(ship),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // An exhausted budget still runs one script per step, starting with the
+    // first ship deferred last step, so every ship takes its turn.
+    sim.set_script_budget(Some(0.0));
+    for i in 0..ships.len() {
+        sim.step();
+        assert_eq!(ran(&sim), vec![ships[i]]);
+        let mut expected: Vec<_> = ships.iter().cloned().filter(|&x| x != ships[i]).collect();
+        expected.rotate_left(i);
+        assert_eq!(deferred(&mut sim), expected);
+    }
+
+    sim.set_script_budget(Some(10.0));
+    sim.step();
+    assert_eq!(ran(&sim), ships);
+    assert!(sim.drain_events().is_empty());
+}
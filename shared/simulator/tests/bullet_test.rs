@@ -113,3 +113,325 @@ fn test_penetration() {
     assert_ne!(bullet::data(&sim, bullet).mass, initial_bullet_mass);
     assert_ne!(*bullet::body(&sim, bullet).linvel(), initial_velocity);
 }
+
+#[test]
+fn test_bullet_pooling() {
+    let run = |pooling: bool| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.set_bullet_pooling(pooling);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![-100.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![100.0, 0.0],
+            vector![0.0, 0.0],
+            0.1,
+            ship::ShipData {
+                health: 1e6,
+                ..fighter(1)
+            },
+        );
+
+        let mut handles = std::collections::BTreeSet::new();
+        let mut trace = vec![];
+        for _ in 0..300 {
+            sim.ship_mut(ship0).fire_gun(0);
+            handles.extend(sim.bullets.iter().copied());
+            sim.step();
+            trace.push((
+                sim.ship(ship1).data().health,
+                sim.ship(ship1).position().vector,
+                sim.bullets
+                    .iter()
+                    .map(|&b| *bullet::body(&sim, b).translation())
+                    .collect::<Vec<_>>(),
+            ));
+        }
+        (trace, handles.len())
+    };
+
+    let (fresh, fresh_handles) = run(false);
+    let (pooled, pooled_handles) = run(true);
+    assert!(fresh.last().unwrap().0 < 1e6);
+    assert_eq!(fresh, pooled);
+    assert!(pooled_handles < fresh_handles);
+}
+
+#[test]
+fn test_hardpoints() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = fighter(0);
+    data.guns.truncate(1);
+    data.guns[0].inaccuracy = 0.0;
+    data.guns[0].hardpoints = vec![
+        ship::Hardpoint {
+            offset: vector![20.0, 10.0],
+            angle: -0.01,
+        },
+        ship::Hardpoint {
+            offset: vector![20.0, -10.0],
+            angle: 0.01,
+        },
+    ];
+    assert_eq!(data.guns[0].hardpoint_count(), 2);
+    assert_eq!(fighter(0).guns[0].hardpoint_count(), 1);
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+
+    sim.ship_mut(ship0).fire_gun(0);
+    assert_eq!(sim.bullets.len(), 2);
+    let mut bullets: Vec<_> = sim
+        .bullets
+        .iter()
+        .map(|&b| {
+            let body = bullet::body(&sim, b);
+            (*body.translation(), *body.linvel())
+        })
+        .collect();
+    bullets.sort_by(|a, b| a.0.y.total_cmp(&b.0.y));
+    let [(p0, v0), (p1, v1)] = [bullets[0], bullets[1]];
+    assert!((p1 - p0).magnitude() > 15.0);
+    assert!((p0.y + 10.0).abs() < 1.0, "p0={p0:?}");
+    assert!((p1.y - 10.0).abs() < 1.0, "p1={p1:?}");
+    // Muzzles angle in towards each other.
+    assert!(v0.y > 0.0 && v1.y < 0.0);
+}
+
+#[test]
+fn test_overheat_jam() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut data = fighter(0);
+    data.guns[0] = ship::Gun {
+        magazine_size: 1000,
+        reload_ticks: 1,
+        heat_per_shot: 0.1,
+        cooling_rate: 0.6,
+        jam_ticks: 60,
+        ..data.guns[0].clone()
+    };
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
+
+    // Sustained fire heats the gun faster than it cools.
+    let mut shots = 0;
+    while !sim.ship(ship0).is_jammed(0) {
+        sim.ship_mut(ship0).fire_gun(0);
+        sim.step();
+        shots += 1;
+        assert!(shots < 100, "gun never jammed");
+    }
+    assert_eq!(sim.bullets.len(), shots);
+    assert_eq!(sim.ship(ship0).weapon_cooldown_remaining(0), 59);
+    assert_eq!(sim.ship(ship0).get_reload_ticks(0), 59);
+
+    // Firing is blocked for the whole cooldown, even once heat has dropped.
+    while sim.ship(ship0).weapon_cooldown_remaining(0) > 0 {
+        sim.ship_mut(ship0).fire_gun(0);
+        sim.step();
+    }
+    assert_eq!(sim.bullets.len(), shots);
+    assert!(sim.ship(ship0).data().guns[0].heat < 0.5);
+
+    sim.ship_mut(ship0).fire_gun(0);
+    assert_eq!(sim.bullets.len(), shots + 1);
+}
+
+#[test]
+fn test_guided_bullet() {
+    let hits = |guided: bool| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let target = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(1),
+        );
+        let initial_health = sim.ship(target).data().health;
+        bullet::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![500.0, 250.0],
+            bullet::BulletData {
+                mass: 0.1,
+                team: 0,
+                ttl: 5.0,
+                guidance: guided.then_some(bullet::BulletGuidance {
+                    target,
+                    turn_rate: 2.0,
+                }),
+                ..Default::default()
+            },
+        );
+        for _ in 0..300 {
+            sim.step();
+        }
+        sim.ship(target).data().health < initial_health
+    };
+
+    assert!(!hits(false));
+    assert!(hits(true));
+}
+
+#[test]
+fn test_bullet_expires() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    bullet::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![1000.0, 0.0],
+        bullet::BulletData {
+            mass: 0.1,
+            team: 0,
+            ttl: 1.0,
+            ..Default::default()
+        },
+    );
+    for _ in 0..55 {
+        sim.step();
+    }
+    assert_eq!(sim.bullets.len(), 1);
+    for _ in 0..10 {
+        sim.step();
+    }
+    assert_eq!(sim.bullets.len(), 0);
+}
+
+#[test]
+fn test_muzzle_velocity() {
+    let bullet_speed = |stats: ship::ShipStats| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter_with(0, stats),
+        );
+        assert_eq!(sim.ship(ship0).gun_muzzle_velocity(0), stats.bullet_speed);
+        assert_eq!(sim.ship(ship0).gun_muzzle_velocity(1), 0.0);
+        assert_eq!(
+            sim.ship(ship0).gun_bullet_ttl(0),
+            sim.ship(ship0).data().guns[0].ttl as f64
+        );
+        assert_eq!(sim.ship(ship0).gun_bullet_ttl(1), 0.0);
+        sim.ship_mut(ship0).fire_gun(0);
+        let &bullet = sim.bullets.iter().next().unwrap();
+        bullet::body(&sim, bullet).linvel().norm()
+    };
+
+    let default = bullet_speed(ship::ShipStats::fighter());
+    let fast = bullet_speed(ship::ShipStats {
+        bullet_speed: 2000.0,
+        ..ship::ShipStats::fighter()
+    });
+    assert!((default - ship::ShipStats::fighter().bullet_speed).abs() < 1e-9);
+    assert!((fast - 2000.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_bullet_radius() {
+    let hits = |radius: f64| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let target = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            target(1).with_collider(ship::ColliderShape::Circle { radius: 10.0 }),
+        );
+        bullet::create(
+            &mut sim,
+            vector![-100.0, 13.0],
+            vector![200.0, 0.0],
+            bullet::BulletData {
+                mass: 1.0,
+                team: 0,
+                ttl: 5.0,
+                radius,
+                ..Default::default()
+            },
+        );
+        for _ in 0..120 {
+            sim.step();
+        }
+        !sim.ships.contains(target)
+    };
+
+    assert!(!hits(bullet::DEFAULT_RADIUS));
+    assert!(hits(5.0));
+}
+
+#[test]
+fn test_time_to_impact() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let target = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1).with_collider(ship::ColliderShape::Circle { radius: 10.0 }),
+    );
+    let create = |sim: &mut simulation::Simulation, x: f64, y: f64| {
+        bullet::create(
+            sim,
+            vector![x, y],
+            vector![1000.0, 0.0],
+            bullet::BulletData {
+                mass: 1.0,
+                team: 0,
+                ttl: 5.0,
+                ..Default::default()
+            },
+        )
+    };
+    let radius = sim.ship(target).radius() + bullet::DEFAULT_RADIUS;
+
+    let hit = create(&mut sim, -1000.0, 0.0);
+    let t = bullet::time_to_impact(&sim, hit, target).expect("impact");
+    assert!((t - (1000.0 - radius) / 1000.0).abs() < 1e-9, "{t}");
+
+    let miss = create(&mut sim, -1000.0, radius + 1.0);
+    assert_eq!(bullet::time_to_impact(&sim, miss, target), None);
+
+    // A bullet that has passed the ship won't come back.
+    let receding = create(&mut sim, 100.0, 0.0);
+    assert_eq!(bullet::time_to_impact(&sim, receding, target), None);
+}
+
+#[test]
+fn test_aim_lead_moving() {
+    use oort_api::prelude::{aim_lead_moving, vec2, Vec2, Vec2Extras};
+
+    let hits = |own_velocity_for_aim: Vec2| -> bool {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let own_velocity = vector![0.0, 300.0];
+        let target_position = vector![1000.0, 0.0];
+        let target_velocity = vector![0.0, -100.0];
+        let heading = aim_lead_moving(
+            vec2(target_position.x, target_position.y),
+            vec2(target_velocity.x, target_velocity.y),
+            own_velocity_for_aim,
+            fighter(0).guns[0].speed,
+        )
+        .angle();
+        let mut data = fighter(0);
+        data.guns[0].offset = vector![0.0, 0.0];
+        data.guns[0].inaccuracy = 0.0;
+        let ship0 = ship::create(&mut sim, vector![0.0, 0.0], own_velocity, heading, data);
+        let ship1 = ship::create(&mut sim, target_position, target_velocity, 0.0, target(1));
+        let initial_health = sim.ship(ship1).data().health;
+        sim.ship_mut(ship0).fire_gun(0);
+        for _ in 0..120 {
+            sim.step();
+        }
+        !sim.ships.contains(ship1) || sim.ship(ship1).data().health < initial_health
+    };
+
+    // Leading as if stationary misses by the shooter's own drift.
+    assert!(hits(vec2(0.0, 300.0)));
+    assert!(!hits(vec2(0.0, 0.0)));
+}
@@ -1,7 +1,7 @@
-use nalgebra::vector;
+use nalgebra::{point, vector};
 use oort_simulator::ship::{fighter, missile};
 use oort_simulator::simulation::{self, Code};
-use oort_simulator::{bullet, collision, ship};
+use oort_simulator::{bullet, collision, scenario, ship};
 use rand::Rng;
 use test_log::test;
 
@@ -9,8 +9,9 @@ const BULLET_COLOR: u32 = 0xffffffff;
 
 #[test]
 fn test_world_edge() {
-    let mut rng = rand::thread_rng();
     let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    // Placement follows the simulation seed so a failure can be reproduced.
+    let mut rng = oort_simulator::rng::new_rng(sim.seed());
     collision::add_walls(&mut sim);
 
     for _ in 0..100 {
@@ -43,6 +44,80 @@ fn test_world_edge() {
     }
 }
 
+#[test]
+fn test_adaptive_substeps() {
+    let run = |substeps: bool| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+        sim.set_adaptive_substeps(substeps);
+        scenario::add_polygon_wall(&mut sim, &[point![540.0, -1000.0], point![540.0, 1000.0]]);
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![5000.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        // CCD would catch the wall by itself.
+        sim.ship_mut(handle).body().enable_ccd(false);
+        for _ in 0..12 {
+            sim.step();
+        }
+        sim.ships
+            .contains(handle)
+            .then(|| sim.ship(handle).position().x)
+    };
+
+    // The ship moves 83 m per tick, far more than its radius, and passes
+    // through the wall unless each tick is split. Hitting the wall destroys
+    // it.
+    let x = run(false).expect("ship hit the wall without substeps");
+    assert!(x > 540.0, "{x}");
+    assert_eq!(run(true), None);
+}
+
+#[test]
+fn test_polygon_wall() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    // Degenerate walls are ignored.
+    scenario::add_polygon_wall(&mut sim, &[]);
+    scenario::add_polygon_wall(&mut sim, &[point![0.0, 0.0]]);
+    scenario::add_polygon_wall(
+        &mut sim,
+        &[
+            point![400.0, -200.0],
+            point![600.0, -200.0],
+            point![600.0, 200.0],
+            point![400.0, 200.0],
+        ],
+    );
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![200.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    bullet::create(
+        &mut sim,
+        vector![0.0, 100.0],
+        vector![1000.0, 0.0],
+        bullet::BulletData {
+            mass: 0.1,
+            team: 0,
+            ttl: 10.0,
+            ..Default::default()
+        },
+    );
+
+    // Like the world edge, the wall destroys the ship rather than letting it
+    // through.
+    for _ in 0..300 {
+        sim.step();
+    }
+    assert!(!sim.ships.contains(ship0));
+    assert!(sim.bullets.is_empty());
+}
+
 #[test]
 fn test_head_on_collision() {
     let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
@@ -73,6 +148,41 @@ fn test_head_on_collision() {
     assert!(sim.ship(ship1).velocity().x > 0.0);
 }
 
+#[test]
+fn test_collision_filter() {
+    let run = |mask0: u8, mask1: u8| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![-100.0, 0.0],
+            vector![100.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![100.0, 0.0],
+            vector![-100.0, 0.0],
+            0.0,
+            fighter(0),
+        );
+        sim.set_collision_filter(ship0, mask0);
+        sim.set_collision_filter(ship1, mask1);
+        for _ in 0..180 {
+            sim.step();
+        }
+        (sim.ship(ship0).position().x, sim.ship(ship1).position().x)
+    };
+
+    // Collide and bounce back.
+    let (x0, x1) = run(collision::DEFAULT_SHIP_COLLISION_MASK, 0b11);
+    assert!(x0 < 0.0 && x1 > 0.0, "x0={x0} x1={x1}");
+
+    // Pass through each other.
+    let (x0, x1) = run(0b01, 0b10);
+    assert!(x0 > 100.0 && x1 < -100.0, "x0={x0} x1={x1}");
+}
+
 #[test]
 fn test_fighter_bullet_collision_same_team() {
     let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
@@ -93,6 +203,9 @@ fn test_fighter_bullet_collision_same_team() {
             team: 0,
             color: BULLET_COLOR,
             ttl: 5.0,
+            source: None,
+            damage_type: bullet::DamageType::Kinetic,
+            ..Default::default()
         },
     );
 
@@ -124,6 +237,9 @@ fn test_fighter_bullet_collision_different_team() {
             team: 1,
             color: BULLET_COLOR,
             ttl: 5.0,
+            source: None,
+            damage_type: bullet::DamageType::Kinetic,
+            ..Default::default()
         },
     );
 
@@ -155,6 +271,9 @@ fn test_missile_bullet_collision_same_team() {
             team: 0,
             color: BULLET_COLOR,
             ttl: 5.0,
+            source: None,
+            damage_type: bullet::DamageType::Kinetic,
+            ..Default::default()
         },
     );
 
@@ -186,6 +305,9 @@ fn test_missile_bullet_collision_different_team() {
             team: 1,
             color: BULLET_COLOR,
             ttl: 5.0,
+            source: None,
+            damage_type: bullet::DamageType::Kinetic,
+            ..Default::default()
         },
     );
 
@@ -273,6 +395,9 @@ fn test_bullet_continuous_collision_detection() {
                 team: 1,
                 color: BULLET_COLOR,
                 ttl: 1.5,
+                source: None,
+                damage_type: bullet::DamageType::Kinetic,
+                ..Default::default()
             },
         );
 
@@ -304,3 +429,217 @@ fn test_ship_wall_collision() {
 
     assert!(!sim.ship(ship0).exists());
 }
+
+#[test]
+fn test_solver_iterations() {
+    let run = |velocity_iterations, position_iterations| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+        sim.set_solver_iterations(velocity_iterations, position_iterations);
+        assert_eq!(
+            sim.solver_iterations(),
+            (velocity_iterations, position_iterations)
+        );
+        // Overlapping stack of ships pushed into each other.
+        let handles: Vec<_> = (0..5)
+            .map(|i| {
+                ship::create(
+                    &mut sim,
+                    vector![i as f64 * 12.0, 0.0],
+                    vector![-10.0 * i as f64, 0.0],
+                    0.0,
+                    fighter(0),
+                )
+            })
+            .collect();
+        for _ in 0..60 {
+            sim.step();
+        }
+        handles
+            .iter()
+            .map(|&handle| sim.ship(handle).position().vector)
+            .collect::<Vec<_>>()
+    };
+
+    let low = run(1, 0);
+    let high = run(16, 4);
+    for p in low.iter().chain(high.iter()) {
+        assert!(p.x.is_finite() && p.y.is_finite());
+    }
+    assert_ne!(low, high);
+}
+
+#[test]
+fn test_circular_boundary() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    let radius = 2000.0;
+    collision::add_circular_boundary(&mut sim, radius);
+    assert_eq!(sim.arena_radius(), Some(radius));
+
+    let ships: Vec<_> = (0..8)
+        .map(|i| {
+            let angle = i as f64 * std::f64::consts::TAU / 8.0;
+            let direction = vector![angle.cos(), angle.sin()];
+            ship::create(
+                &mut sim,
+                direction * 100.0,
+                direction * 2000.0,
+                angle,
+                fighter(0),
+            )
+        })
+        .collect();
+    bullet::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 5000.0],
+        bullet::BulletData {
+            mass: 0.1,
+            team: 0,
+            color: BULLET_COLOR,
+            ttl: 5.0,
+            source: None,
+            damage_type: bullet::DamageType::Kinetic,
+            ..Default::default()
+        },
+    );
+
+    for _ in 0..120 {
+        sim.step();
+        for &handle in ships.iter() {
+            if sim.ships.contains(handle) {
+                assert!(sim.ship(handle).position().vector.norm() <= radius);
+            }
+        }
+    }
+
+    assert!(ships.iter().all(|&handle| !sim.ships.contains(handle)));
+    assert!(sim.bullets.is_empty());
+}
+
+fn bullet_collision_sim(enabled: bool) -> (Box<simulation::Simulation>, [bullet::BulletHandle; 2]) {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    sim.set_bullet_collisions(enabled);
+    let mut create = |position, velocity, team| {
+        bullet::create(
+            &mut sim,
+            position,
+            velocity,
+            bullet::BulletData {
+                mass: 0.1,
+                team,
+                color: BULLET_COLOR,
+                ttl: 5.0,
+                ..Default::default()
+            },
+        )
+    };
+    let b0 = create(vector![-20.0, 0.0], vector![50.0, 0.0], 0);
+    let b1 = create(vector![20.0, 0.0], vector![-50.0, 0.0], 1);
+    (sim, [b0, b1])
+}
+
+#[test]
+fn test_bullet_bullet_collision() {
+    let (mut sim, bullets) = bullet_collision_sim(true);
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(!sim.bullets.contains(bullets[0]));
+    assert!(!sim.bullets.contains(bullets[1]));
+}
+
+#[test]
+fn test_bullet_bullet_collision_disabled() {
+    let (mut sim, bullets) = bullet_collision_sim(false);
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(sim.bullets.contains(bullets[0]));
+    assert!(sim.bullets.contains(bullets[1]));
+    assert!(bullet::body(&sim, bullets[0]).translation().x > 20.0);
+}
+
+#[test]
+fn test_ricochet() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    let edge = sim.world_size() / 2.0;
+    let blt = bullet::create(
+        &mut sim,
+        vector![edge - 20.0, edge - 60.0],
+        vector![100.0, 100.0],
+        bullet::BulletData {
+            mass: 0.1,
+            team: 0,
+            color: BULLET_COLOR,
+            ttl: 5.0,
+            max_bounces: 1,
+            ..Default::default()
+        },
+    );
+
+    // Bounces off the right wall.
+    let mut ticks = 0;
+    while bullet::body(&sim, blt).linvel().x > 0.0 {
+        assert!(ticks < 60);
+        sim.step();
+        ticks += 1;
+    }
+    let velocity = *bullet::body(&sim, blt).linvel();
+    assert!((velocity - vector![-100.0, 100.0]).norm() < 1e-3);
+    assert_eq!(bullet::data(&sim, blt).bounces, 1);
+    assert!(bullet::body(&sim, blt).translation().x < edge);
+
+    // Destroyed by the top wall.
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(!sim.bullets.contains(blt));
+}
+
+#[test]
+fn test_simultaneous_hits_deterministic() {
+    // The heavy bullet destroys the ship on its own and the light one doesn't,
+    // so which bullets survive depends on the order the hits are resolved in.
+    let run = |seed: u32| {
+        let mut sim = simulation::Simulation::new("test", seed, &[Code::None, Code::None]);
+        ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(1),
+        );
+        for (x, mass) in [(-100.0, 10.0), (100.0, 0.1)] {
+            bullet::create(
+                &mut sim,
+                vector![x, 0.0],
+                vector![-10.0 * x, 0.0],
+                bullet::BulletData {
+                    mass,
+                    team: 0,
+                    color: BULLET_COLOR,
+                    ttl: 5.0,
+                    ..Default::default()
+                },
+            );
+        }
+        for _ in 0..30 {
+            sim.step();
+        }
+        let mut masses: Vec<f32> = sim
+            .bullets
+            .iter()
+            .map(|&handle| bullet::data(&sim, handle).mass)
+            .collect();
+        masses.sort_by(f32::total_cmp);
+        (masses, sim.hash())
+    };
+
+    let (expected, _) = run(0);
+    assert!(!expected.is_empty());
+    for seed in 0..20 {
+        let (masses, hash) = run(seed);
+        assert_eq!(masses, expected, "seed={seed}");
+        assert_eq!(hash, run(seed).1, "seed={seed}");
+    }
+}
@@ -0,0 +1,63 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter};
+use oort_simulator::simulation::{self, Code};
+use oort_simulator::vm::CompiledAi;
+use test_log::test;
+
+fn run(setup: impl Fn(&mut simulation::Simulation)) -> u64 {
+    let mut sim = simulation::Simulation::new("fighter_duel", 0, &[Code::None, Code::None]);
+    setup(&mut sim);
+    for _ in 0..300 {
+        sim.step();
+    }
+    assert!(sim.events().errors.is_empty());
+    sim.hash()
+}
+
+#[test]
+fn test_compiled_ai_reuse() {
+    let ai = CompiledAi::new(&Code::Builtin("reference".to_string())).unwrap();
+    let upload = |sim: &mut simulation::Simulation| {
+        sim.upload_compiled_ai(0, &ai);
+        sim.upload_compiled_ai(1, &ai);
+    };
+    let first = run(upload);
+    let second = run(upload);
+    assert_eq!(first, second);
+
+    let code = Code::Builtin("reference".to_string());
+    let uncompiled = run(|sim| {
+        sim.upload_code(0, &code);
+        sim.upload_code(1, &code);
+    });
+    assert_eq!(first, uncompiled);
+}
+
+#[test]
+fn test_per_ship_ai() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::Builtin("empty".to_string())]);
+    let ai = CompiledAi::new(&Code::Builtin("test".to_string())).unwrap();
+    let scripted = ship::create_with_ai(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+        &ai,
+    );
+    let default = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    // The test AI reports an unknown testcase every tick, the empty AI is
+    // silent.
+    sim.step();
+    assert!(sim.events().errors.is_empty());
+    let debug_text = &sim.events().debug_text;
+    assert!(debug_text[&scripted.into()].contains("Unknown testcase"));
+    assert!(!debug_text.contains_key(&default.into()));
+}
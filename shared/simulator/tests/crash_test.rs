@@ -52,7 +52,33 @@ fn test_infinite_loop() {
         assert_eq!(captured_logs[0].level, log::Level::Warn);
         assert_eq!(
             captured_logs[0].body,
-            "Ship exceeded maximum number of instructions and was destroyed"
+            "Ship exceeded maximum number of instructions and was disabled"
         );
     });
 }
+
+#[test]
+#[serial]
+fn test_panic_disables_ship() {
+    let mut sim =
+        simulation::Simulation::new("test", 0, &[Code::Builtin("test".to_string()), Code::None]);
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "panic".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    for _ in 0..60 {
+        sim.step();
+    }
+
+    assert!(!sim.ship(ship0).data().destroyed);
+    assert!(sim.ship(ship0).position().x > 90.0);
+    let error = sim.ship_errors().get(&ship0).expect("error recorded");
+    assert!(error.starts_with("Ship runtime error"), "{error}");
+}
@@ -1,7 +1,7 @@
 use nalgebra::vector;
-use oort_simulator::ship;
-use oort_simulator::ship::{cruiser, fighter, frigate, target};
+use oort_simulator::ship::{cruiser, fighter, frigate, missile, target};
 use oort_simulator::simulation::{self, Code};
+use oort_simulator::{bullet, collision, scenario, ship};
 use test_log::test;
 
 fn high_health_target(team: i32) -> ship::ShipData {
@@ -63,3 +63,726 @@ fn test_dps() {
     approx::assert_abs_diff_eq!(find_gun_dps(frigate(0), 2), 73.5, epsilon = 1.0);
     approx::assert_abs_diff_eq!(find_gun_dps(cruiser(0), 0), 75.7, epsilon = 1.0);
 }
+
+fn find_kill(destroy_shooter: bool) -> (ship::ShipHandle, simulation::Kill) {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut shooter = fighter(0);
+    shooter.guns[0].inaccuracy = 0.0;
+    let offset = shooter.guns[0].offset;
+
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        shooter.clone(),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![0.0, 200.0],
+        vector![0.0, 0.0],
+        0.0,
+        shooter,
+    );
+    let ship2 = ship::create(
+        &mut sim,
+        vector![100.0, 200.0] + offset,
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+
+    sim.ship_mut(ship1).fire_gun(0);
+    if destroy_shooter {
+        sim.ship_mut(ship1).data_mut().destroyed = true;
+    }
+    for _ in 0..30 {
+        sim.step();
+        if let Some(kill) = sim.events().kills.first() {
+            assert_eq!(kill.victim, ship2);
+            return (ship1, *kill);
+        }
+    }
+    panic!("target not destroyed");
+}
+
+#[test]
+fn test_kill_attribution() {
+    let (shooter, kill) = find_kill(false);
+    assert_eq!(kill.source, Some(shooter));
+    assert_eq!(kill.victim_team, 1);
+    assert!(kill.source_alive);
+
+    let (shooter, kill) = find_kill(true);
+    assert_eq!(kill.source, Some(shooter));
+    assert!(!kill.source_alive);
+}
+
+#[test]
+fn test_damage_resistance() {
+    let damage_taken = |resistance: ship::DamageResistance, damage_type: bullet::DamageType| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let data = ship::ShipData {
+            resistance,
+            ..high_health_target(1)
+        };
+        let ship0 = ship::create(&mut sim, vector![100.0, 0.0], vector![0.0, 0.0], 0.0, data);
+        bullet::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![1000.0, 0.0],
+            bullet::BulletData {
+                mass: 1.0,
+                team: 0,
+                ttl: 5.0,
+                damage_type,
+                ..Default::default()
+            },
+        );
+        let initial_health = sim.ship(ship0).data().health;
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert!(sim.bullets.is_empty());
+        initial_health - sim.ship(ship0).data().health
+    };
+
+    let kinetic_resistant = ship::DamageResistance {
+        kinetic: 0.25,
+        energy: 1.0,
+    };
+    let energy_resistant = ship::DamageResistance {
+        kinetic: 1.0,
+        energy: 0.25,
+    };
+    let full = damage_taken(Default::default(), bullet::DamageType::Kinetic);
+    assert!(full > 0.0);
+    approx::assert_relative_eq!(
+        damage_taken(kinetic_resistant, bullet::DamageType::Kinetic),
+        full * 0.25,
+        max_relative = 1e-6
+    );
+    approx::assert_relative_eq!(
+        damage_taken(energy_resistant, bullet::DamageType::Kinetic),
+        full,
+        max_relative = 1e-6
+    );
+    approx::assert_relative_eq!(
+        damage_taken(kinetic_resistant, bullet::DamageType::Energy),
+        full,
+        max_relative = 1e-6
+    );
+    approx::assert_relative_eq!(
+        damage_taken(energy_resistant, bullet::DamageType::Energy),
+        full * 0.25,
+        max_relative = 1e-6
+    );
+}
+
+#[test]
+fn test_damage_falloff() {
+    let damage_taken = |range: f64, falloff: bullet::DamageFalloff| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![range, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            high_health_target(1),
+        );
+        bullet::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![1000.0, 0.0],
+            bullet::BulletData {
+                mass: 1.0,
+                team: 0,
+                ttl: 5.0,
+                falloff,
+                ..Default::default()
+            },
+        );
+        let initial_health = sim.ship(ship0).data().health;
+        for _ in 0..180 {
+            sim.step();
+        }
+        assert!(sim.bullets.is_empty());
+        initial_health - sim.ship(ship0).data().health
+    };
+
+    approx::assert_relative_eq!(
+        damage_taken(100.0, bullet::DamageFalloff::None),
+        damage_taken(1000.0, bullet::DamageFalloff::None),
+        max_relative = 1e-6
+    );
+
+    for falloff in [
+        bullet::DamageFalloff::Linear { range: 2000.0 },
+        bullet::DamageFalloff::Quadratic { range: 2000.0 },
+    ] {
+        let close = damage_taken(100.0, falloff);
+        let far = damage_taken(1000.0, falloff);
+        assert!(close > far, "falloff={falloff:?} close={close} far={far}");
+        assert!(far > 0.0);
+    }
+    assert!(
+        damage_taken(1000.0, bullet::DamageFalloff::Quadratic { range: 2000.0 })
+            > damage_taken(1000.0, bullet::DamageFalloff::Linear { range: 2000.0 })
+    );
+    assert_eq!(
+        damage_taken(1000.0, bullet::DamageFalloff::Linear { range: 500.0 }),
+        0.0
+    );
+}
+
+#[test]
+fn test_bullet_kinds() {
+    // Returns the damage taken by each target and the explosions logged.
+    let run = |kind: bullet::BulletKind, targets: &[(f64, f64)]| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.enable_event_log();
+        let handles: Vec<ship::ShipHandle> = targets
+            .iter()
+            .map(|&(x, y)| {
+                ship::create(
+                    &mut sim,
+                    vector![x, y],
+                    vector![0.0, 0.0],
+                    0.0,
+                    high_health_target(1),
+                )
+            })
+            .collect();
+        bullet::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![1000.0, 0.0],
+            bullet::BulletData {
+                mass: 1.0,
+                team: 0,
+                ttl: 5.0,
+                kind,
+                ..Default::default()
+            },
+        );
+        for _ in 0..60 {
+            sim.step();
+        }
+        let damage = handles
+            .iter()
+            .map(|&handle| 1e6 - sim.ship(handle).data().health)
+            .collect::<Vec<f64>>();
+        let explosions = sim
+            .drain_events()
+            .into_iter()
+            .filter(|event| matches!(event, simulation::SimEvent::Explosion { ship: None, .. }))
+            .count();
+        (damage, explosions)
+    };
+    let damage_taken = |kind, targets: &[(f64, f64)]| run(kind, targets).0;
+
+    let in_line = [(100.0, 0.0), (300.0, 0.0)];
+    let kinetic = damage_taken(bullet::BulletKind::Kinetic, &in_line);
+    assert!(kinetic[0] > 0.0);
+    assert_eq!(kinetic[1], 0.0);
+    let piercing = damage_taken(bullet::BulletKind::Piercing, &in_line);
+    assert!(piercing[0] > 0.0, "{piercing:?}");
+    approx::assert_relative_eq!(piercing[0], piercing[1], max_relative = 1e-6);
+
+    // The last target's centre is out of the blast, but its hull isn't.
+    let radius = high_health_target(1)
+        .collider
+        .radius(ship::ShipClass::Target);
+    let side_by_side = [
+        (100.0, 0.0),
+        (100.0, 100.0),
+        (100.0, 1000.0),
+        (100.0, 200.0 + radius / 2.0),
+    ];
+    let kinetic = damage_taken(bullet::BulletKind::Kinetic, &side_by_side);
+    assert_eq!(&kinetic[1..], &[0.0, 0.0, 0.0]);
+    let (explosive, explosions) = run(
+        bullet::BulletKind::Explosive { radius: 200.0 },
+        &side_by_side,
+    );
+    assert!(explosive[0] > 0.0, "{explosive:?}");
+    approx::assert_relative_eq!(explosive[0], explosive[1], max_relative = 1e-6);
+    assert_eq!(explosive[2], 0.0);
+    assert!(explosive[3] > 0.0, "{explosive:?}");
+    assert_eq!(explosions, 1);
+}
+
+#[test]
+fn test_burning() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        high_health_target(1),
+    );
+    bullet::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![1000.0, 0.0],
+        bullet::BulletData {
+            mass: 1.0,
+            team: 0,
+            ttl: 5.0,
+            status_effect: Some(ship::StatusEffect::Burning {
+                dps: 60.0,
+                ticks_left: 30,
+                source: None,
+            }),
+            ..Default::default()
+        },
+    );
+    assert!(!sim.ship(ship0).data().is_burning());
+    while !sim.bullets.is_empty() {
+        sim.step();
+        assert!(sim.tick() < 60);
+    }
+    assert!(sim.ship(ship0).data().is_burning());
+
+    let mut ticks = 0;
+    while sim.ship(ship0).data().is_burning() {
+        let health = sim.ship(ship0).data().health;
+        sim.step();
+        approx::assert_relative_eq!(
+            health - sim.ship(ship0).data().health,
+            1.0,
+            max_relative = 1e-9
+        );
+        ticks += 1;
+        assert!(ticks <= 30);
+    }
+    assert!(ticks >= 29);
+
+    let health = sim.ship(ship0).data().health;
+    sim.step();
+    assert_eq!(sim.ship(ship0).data().health, health);
+}
+
+#[test]
+fn test_burning_kill_attribution() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let shooter = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let victim = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+    sim.ship_mut(victim)
+        .data_mut()
+        .apply_status_effect(ship::StatusEffect::Burning {
+            dps: 1e6,
+            ticks_left: 30,
+            source: Some(shooter),
+        });
+    sim.step();
+
+    let kills = &sim.events().kills;
+    assert_eq!(kills.len(), 1);
+    assert_eq!(kills[0].victim, victim);
+    assert_eq!(kills[0].source, Some(shooter));
+}
+
+#[test]
+fn test_damage_multiplier() {
+    let setup = |multiplier: f64, health: f64| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        assert_eq!(sim.damage_multiplier(), 1.0);
+        sim.set_damage_multiplier(multiplier);
+        let data = ship::ShipData {
+            health,
+            ..target(1)
+        };
+        let ship0 = ship::create(&mut sim, vector![100.0, 0.0], vector![0.0, 0.0], 0.0, data);
+        (sim, ship0)
+    };
+    let shoot = |sim: &mut simulation::Simulation| {
+        bullet::create(
+            sim,
+            vector![0.0, 0.0],
+            vector![1000.0, 0.0],
+            bullet::BulletData {
+                mass: 1.0,
+                team: 0,
+                ttl: 5.0,
+                ..Default::default()
+            },
+        );
+        for _ in 0..60 {
+            sim.step();
+        }
+    };
+
+    let (mut sim, ship0) = setup(1.0, 1e6);
+    shoot(&mut sim);
+    let damage = 1e6 - sim.ship(ship0).data().health;
+    assert!(damage > 0.0);
+
+    let shots_to_destroy = |multiplier: f64| {
+        let (mut sim, ship0) = setup(multiplier, damage * 3.5);
+        (1..=10)
+            .find(|_| {
+                shoot(&mut sim);
+                !sim.ships.contains(ship0)
+            })
+            .expect("target not destroyed")
+    };
+    assert_eq!(shots_to_destroy(1.0), 4);
+    assert_eq!(shots_to_destroy(2.0), 2);
+}
+
+#[test]
+fn test_debris() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let shooter = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let target = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        high_health_target(1),
+    );
+    let runner = ship::create(
+        &mut sim,
+        vector![520.0, 150.0],
+        vector![0.0, -500.0],
+        0.0,
+        fighter(2),
+    );
+    scenario::add_debris(&mut sim, vector![500.0, 0.0], 50.0, 100.0);
+    let initial_health = sim.ship(target).data().health;
+
+    // Cover soaks up the first shots.
+    for _ in 0..30 {
+        sim.ship_mut(shooter).fire_gun(0);
+        sim.step();
+    }
+    assert_eq!(sim.debris().len(), 1);
+    assert!(sim.debris()[0].health < 100.0);
+    assert_eq!(sim.ship(target).data().health, initial_health);
+
+    // The ship flying through it is unaffected.
+    assert!(sim.ships.contains(runner));
+    assert_eq!(sim.ship(runner).data().health, fighter(2).health);
+    assert!(sim.ship(runner).position().y < -50.0);
+    assert!((sim.ship(runner).velocity() - vector![0.0, -500.0]).norm() < 1e-6);
+
+    for _ in 0..600 {
+        sim.ship_mut(shooter).fire_gun(0);
+        sim.step();
+    }
+    assert!(sim.debris().is_empty());
+    assert!(sim.ship(target).data().health < initial_health);
+}
+
+#[test]
+fn test_sudden_death() {
+    let mut sim = simulation::Simulation::new("fighter_duel", 0, &[Code::None, Code::None]);
+    let ship0 = sim.find_ships(|ship| ship.data().team == 0)[0];
+    let health = |sim: &simulation::Simulation| sim.ship(ship0).data().health;
+    let initial_health = health(&sim);
+    sim.set_sudden_death_tick(Some(60));
+
+    for _ in 0..59 {
+        sim.step();
+    }
+    assert!(!sim.sudden_death_active());
+    assert!(!sim.snapshot(0).sudden_death);
+    assert_eq!(health(&sim), initial_health);
+
+    // Damage escalates once sudden death begins.
+    let mut losses = vec![];
+    for _ in 0..3 {
+        let before = health(&sim);
+        for _ in 0..60 {
+            sim.step();
+        }
+        losses.push(before - health(&sim));
+    }
+    assert!(sim.sudden_death_active());
+    assert!(sim.snapshot(0).sudden_death);
+    assert!(losses[0] > 0.0);
+    assert!(losses[1] > losses[0] && losses[2] > losses[1], "{losses:?}");
+
+    let mut ticks = 0;
+    while sim.status() == scenario::Status::Running {
+        assert!(ticks < 60 * 60, "match didn't end");
+        sim.step();
+        ticks += 1;
+    }
+}
+
+#[test]
+fn test_collision_damage_cooldown() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-10.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![10.0, 0.0],
+        vector![0.0, 0.0],
+        std::f64::consts::PI,
+        fighter(1),
+    );
+    // Each impact at 100 m/s deals 0.0002 * 0.5 * 100² = 1 damage.
+    sim.set_ram_damage(0.0002);
+    let initial_health = sim.ship(ship0).data().health;
+
+    // Both ships are driven into each other for two seconds.
+    let ticks = 120;
+    for _ in 0..ticks {
+        sim.ship_mut(ship0)
+            .body()
+            .set_linvel(vector![50.0, 0.0], true);
+        sim.ship_mut(ship1)
+            .body()
+            .set_linvel(vector![-50.0, 0.0], true);
+        sim.step();
+    }
+    let max_impacts = ticks / collision::COLLISION_DAMAGE_COOLDOWN_TICKS;
+    for handle in [ship0, ship1] {
+        let loss = initial_health - sim.ship(handle).data().health;
+        assert!((2.0..=max_impacts as f64).contains(&loss), "{loss}");
+    }
+}
+
+#[test]
+fn test_ram_damage() {
+    let run = |speed: f64| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![-50.0, 0.0],
+            vector![speed, 0.0],
+            0.0,
+            fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![50.0, 0.0],
+            vector![-speed, 0.0],
+            0.0,
+            fighter(0),
+        );
+        sim.set_ram_damage(0.001);
+        for _ in 0..1200 {
+            sim.step();
+        }
+        [ship0, ship1].map(|handle| fighter(0).health - sim.ship(handle).data().health)
+    };
+
+    // Teammates hit each other at 200 m/s, for 0.001 * 0.5 * 200² damage.
+    for loss in run(100.0) {
+        assert!((loss - 20.0).abs() < 1.0, "{loss}");
+    }
+    assert_eq!(run(2.0), [0.0, 0.0]);
+}
+
+#[test]
+fn test_proximity_fuze() {
+    let run = |fuze: Option<f64>| {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        let target = ship::create(
+            &mut sim,
+            vector![300.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            fighter(1),
+        );
+        let handle = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![200.0, 0.0],
+            0.0,
+            ship::ShipData {
+                proximity_fuze: fuze,
+                ..missile(0)
+            },
+        );
+        for _ in 0..70 {
+            sim.step();
+        }
+        (
+            sim.ships.contains(handle),
+            fighter(1).health - sim.ship(target).data().health,
+        )
+    };
+
+    // Detonates about 100 m short of the target after one second.
+    let (alive, damage) = run(Some(100.0));
+    assert!(!alive);
+    assert!(damage > 0.0, "{damage}");
+    assert_eq!(run(None), (true, 0.0));
+}
+
+#[test]
+fn test_mine() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    scenario::add_mine(&mut sim, vector![500.0, 0.0], 0, 50.0, 100.0);
+    let friendly = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![500.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    // The owner's ships pass over it.
+    for _ in 0..120 {
+        sim.step();
+    }
+    assert!(sim.ship(friendly).position().x > 600.0);
+    assert_eq!(sim.ship(friendly).data().health, fighter(0).health);
+    assert_eq!(sim.mines().len(), 1);
+
+    let enemy = ship::create(
+        &mut sim,
+        vector![500.0, 1000.0],
+        vector![0.0, -500.0],
+        0.0,
+        fighter(1),
+    );
+    sim.enable_event_log();
+    for _ in 0..180 {
+        sim.step();
+    }
+    let explosions: Vec<_> = sim
+        .drain_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            simulation::SimEvent::Explosion { ship, position, .. } => Some((ship, position)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(explosions, vec![(None, vector![500.0, 0.0])]);
+    assert!(sim.mines().is_empty());
+    assert_eq!(sim.ship(enemy).data().health, fighter(1).health - 50.0);
+    assert_eq!(sim.ship(friendly).data().health, fighter(0).health);
+}
+
+#[test]
+fn test_beam() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let shooter = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::ShipData {
+            beam: Some(Default::default()),
+            ..fighter(0)
+        },
+    );
+    let target = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        high_health_target(1),
+    );
+    let initial_health = sim.ship(target).data().health;
+    let beam = ship::Beam::default();
+    let fire = |sim: &mut simulation::Simulation, ticks: usize| {
+        for _ in 0..ticks {
+            sim.ship_mut(shooter).fire_beam(0.0, 1500.0);
+            sim.step();
+        }
+    };
+
+    // Sustained fire deals damage every tick and drains energy.
+    fire(&mut sim, 60);
+    let damage = initial_health - sim.ship(target).data().health;
+    assert!((damage - beam.damage_per_second).abs() < 1e-6, "{damage}");
+    let energy = sim.ship(shooter).data().beam.as_ref().unwrap().energy;
+    assert!((energy - (beam.max_energy - beam.energy_per_second)).abs() < 1e-6);
+
+    // Out of the ray, or out of range, nothing is hit.
+    let health = sim.ship(target).data().health;
+    sim.ship_mut(target)
+        .body()
+        .set_translation(vector![1000.0, 200.0], true);
+    fire(&mut sim, 30);
+    sim.ship_mut(target)
+        .body()
+        .set_translation(vector![1600.0, 0.0], true);
+    fire(&mut sim, 30);
+    assert_eq!(sim.ship(target).data().health, health);
+}
+
+#[test]
+fn test_allied_fire() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None, Code::None]);
+    sim.set_allied(0, 1, true);
+    scenario::add_mine(&mut sim, vector![500.0, 0.0], 0, 50.0, 100.0);
+    let shooter = ship::create(
+        &mut sim,
+        vector![0.0, 1000.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::ShipData {
+            beam: Some(Default::default()),
+            ..fighter(0)
+        },
+    );
+    let ally = ship::create(
+        &mut sim,
+        vector![1000.0, 1000.0],
+        vector![0.0, 0.0],
+        0.0,
+        high_health_target(1),
+    );
+    let passing_ally = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![500.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    // Allies neither trigger mines nor take beam damage.
+    for _ in 0..120 {
+        sim.ship_mut(shooter).fire_beam(0.0, 1500.0);
+        sim.step();
+    }
+    assert!(sim.ship(passing_ally).position().x > 600.0);
+    assert_eq!(sim.mines().len(), 1);
+    assert_eq!(sim.ship(passing_ally).data().health, fighter(1).health);
+    assert_eq!(sim.ship(ally).data().health, high_health_target(1).health);
+
+    // Only allied teams are left, so the match is won.
+    let status = scenario::check_victory_with_filter(&sim, 10000, |_| true);
+    assert_eq!(status, scenario::Status::Victory { team: 0 });
+    ship::create(
+        &mut sim,
+        vector![-2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(2),
+    );
+    let status = scenario::check_victory_with_filter(&sim, 10000, |_| true);
+    assert_eq!(status, scenario::Status::Running);
+}
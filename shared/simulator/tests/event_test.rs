@@ -0,0 +1,135 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, target, ShipClass};
+use oort_simulator::simulation::{self, Code, SimEvent};
+use test_log::test;
+
+#[test]
+fn test_event_log() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    sim.enable_event_log();
+
+    let mut shooter = fighter(0);
+    shooter.guns[0].inaccuracy = 0.0;
+    let offset = shooter.guns[0].offset;
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, shooter);
+    let ship1 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0] + offset,
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+
+    sim.ship_mut(ship0).fire_gun(0);
+    let mut events = sim.drain_events();
+    let mut bullets = vec![];
+    for _ in 0..30 {
+        sim.step();
+        events.extend(sim.drain_events());
+        for &bullet in sim.bullets.iter() {
+            if !bullets.contains(&bullet) {
+                bullets.push(bullet);
+            }
+        }
+    }
+    assert!(!sim.ships.contains(ship1));
+    assert!(sim.drain_events().is_empty());
+
+    let summary: Vec<_> = events
+        .iter()
+        .map(|event| match event {
+            SimEvent::ShipCreated { ship, class, .. } => ("created", *ship, Some(*class)),
+            SimEvent::ShotFired { ship, .. } => ("fired", *ship, None),
+            SimEvent::BulletHit { ship, .. } => ("hit", *ship, None),
+            SimEvent::ShipCollision { ships, .. } => ("collision", ships[0], None),
+            SimEvent::Explosion { ship, .. } => ("explosion", ship.unwrap(), None),
+            SimEvent::ShipDestroyed { ship, .. } => ("destroyed", *ship, None),
+            SimEvent::ScriptDeferred { ship, .. } => ("deferred", *ship, None),
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("created", ship0, Some(ShipClass::Fighter)),
+            ("created", ship1, Some(ShipClass::Target)),
+            ("fired", ship0, None),
+            ("hit", ship1, None),
+            ("destroyed", ship1, None),
+        ]
+    );
+
+    let ticks: Vec<u32> = events
+        .iter()
+        .map(|event| match event {
+            SimEvent::ShipCreated { tick, .. }
+            | SimEvent::ShotFired { tick, .. }
+            | SimEvent::BulletHit { tick, .. }
+            | SimEvent::ShipCollision { tick, .. }
+            | SimEvent::Explosion { tick, .. }
+            | SimEvent::ShipDestroyed { tick, .. }
+            | SimEvent::ScriptDeferred { tick, .. } => *tick,
+        })
+        .collect();
+    assert!(ticks.windows(2).all(|w| w[0] <= w[1]));
+    assert!(ticks[3] > 0);
+
+    let hits: Vec<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            SimEvent::BulletHit { bullet, ship, .. } => Some((*bullet, *ship)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(bullets.len(), 1);
+    assert_eq!(hits, vec![(bullets[0], ship1)]);
+
+    let damage: f64 = events
+        .iter()
+        .filter_map(|event| match event {
+            SimEvent::BulletHit { damage, .. } => Some(*damage),
+            _ => None,
+        })
+        .sum();
+    assert!(damage >= target(1).health, "{damage}");
+}
+
+#[test]
+fn test_event_log_disabled() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.ship_mut(ship0).fire_gun(0);
+    sim.step();
+    assert!(sim.drain_events().is_empty());
+}
+
+#[test]
+fn test_explosions() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![100.0, 200.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    sim.step();
+    assert!(sim.take_explosions().is_empty());
+
+    let position = sim.ship(ship0).position().vector;
+    let radius = sim.ship(ship0).radius();
+    sim.ship_mut(ship0).explode();
+    sim.step();
+    assert!(!sim.ships.contains(ship0));
+    let explosions = sim.take_explosions();
+    assert_eq!(explosions.len(), 1);
+    assert_eq!(explosions[0].position, position);
+    assert_eq!(explosions[0].radius, radius);
+    assert_eq!(explosions[0].tick, 1);
+    assert!(sim.take_explosions().is_empty());
+}
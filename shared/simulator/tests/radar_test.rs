@@ -0,0 +1,84 @@
+use nalgebra::{vector, Vector2};
+use oort_simulator::ship::{self, fighter, target};
+use oort_simulator::simulation::{self, Code};
+use test_log::test;
+
+fn scan_positions(seed: u32) -> Vec<Vector2<f64>> {
+    let mut sim = simulation::Simulation::new("test", seed, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![5000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+    sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+
+    (0..10)
+        .map(|_| {
+            sim.step();
+            sim.ship(ship0)
+                .radar()
+                .unwrap()
+                .scan()
+                .expect("contact")
+                .position
+        })
+        .collect()
+}
+
+#[test]
+fn test_noise_reproducible() {
+    let a = scan_positions(1);
+    let b = scan_positions(1);
+    for (pa, pb) in a.iter().zip(b.iter()) {
+        assert_eq!(pa.x.to_bits(), pb.x.to_bits());
+        assert_eq!(pa.y.to_bits(), pb.y.to_bits());
+    }
+
+    let c = scan_positions(2);
+    assert!(a.iter().zip(c.iter()).any(|(pa, pc)| pa != pc));
+
+    // Noise should actually perturb the contact.
+    assert!(a.iter().any(|p| *p != vector![5000.0, 0.0]));
+}
+
+#[test]
+fn test_nearest_friendly() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let friend = ship::create(
+        &mut sim,
+        vector![0.0, 1000.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![5000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+    sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+    sim.step();
+
+    assert_eq!(sim.ship(ship0).nearest_friendly(), Some(friend));
+    assert_eq!(sim.ship(friend).nearest_friendly(), Some(ship0));
+    let contact = sim.ship(ship0).radar().unwrap().scan().expect("contact");
+    assert!((contact.position - vector![5000.0, 0.0]).norm() < 500.0);
+}
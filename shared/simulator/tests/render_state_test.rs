@@ -0,0 +1,122 @@
+use nalgebra::{point, vector};
+use oort_simulator::ship::{self, fighter, target};
+use oort_simulator::simulation::{self, Code};
+use oort_simulator::{bullet, radar};
+use test_log::test;
+
+#[test]
+fn test_render_state() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![60.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+    sim.ship_mut(ship0).fire_gun(0);
+
+    let state = sim.render_state();
+    assert_eq!(state.ships.len(), 2);
+    assert_eq!(state.bullets.len(), 1);
+    let ship_state = |state: &oort_simulator::snapshot::RenderState| {
+        state
+            .ships
+            .iter()
+            .find(|s| s.id == u64::from(ship0))
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(ship_state(&state).position, point![0.0, 0.0]);
+    assert_eq!(ship_state(&state).team, 0);
+    assert_eq!(ship_state(&state).radius, sim.ship(ship0).radius());
+
+    sim.step();
+    let state = sim.render_state();
+    assert!(ship_state(&state).position.x > 0.0);
+    assert_eq!(
+        ship_state(&state).position.coords,
+        sim.ship(ship0).position().vector
+    );
+}
+
+#[test]
+fn test_bullet_positions() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    for (i, team) in [0, 0, 1, 0, 1].into_iter().enumerate() {
+        bullet::create(
+            &mut sim,
+            vector![i as f64 * 100.0, 0.0],
+            vector![0.0, 100.0],
+            bullet::BulletData {
+                team,
+                ttl: 5.0,
+                ..Default::default()
+            },
+        );
+    }
+
+    let mut positions = sim.bullet_positions();
+    assert_eq!(positions.len(), 5);
+    positions.sort_by(|a, b| a.0.x.total_cmp(&b.0.x));
+    let teams: Vec<i32> = positions.iter().map(|&(_, team)| team).collect();
+    assert_eq!(teams, vec![0, 0, 1, 0, 1]);
+    assert_eq!(positions[2].0, point![200.0, 0.0]);
+
+    sim.step();
+    let positions = sim.bullet_positions();
+    assert_eq!(positions.len(), 5);
+    assert!(positions.iter().all(|(position, _)| position.y > 0.0));
+}
+
+#[test]
+fn test_lock_target() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        target(1),
+    );
+    let lock_target = |sim: &simulation::Simulation| {
+        sim.render_state()
+            .ships
+            .iter()
+            .find(|s| s.id == u64::from(ship0))
+            .unwrap()
+            .lock_target
+    };
+
+    sim.ship_mut(ship0).radar_mut().unwrap().set_heading(0.0);
+    for _ in 0..radar::LOCK_PAINT_TICKS {
+        sim.step();
+    }
+    assert_eq!(sim.ship(ship0).lock_target(), Some(ship1));
+    assert_eq!(lock_target(&sim), Some(ship1.into()));
+    let snapshot = sim.snapshot(0);
+    let ship_snapshot = snapshot.ships.iter().find(|s| s.id == u64::from(ship0));
+    assert_eq!(ship_snapshot.unwrap().lock_target, Some(ship1.into()));
+
+    sim.ship_mut(ship0)
+        .radar_mut()
+        .unwrap()
+        .set_heading(std::f64::consts::PI);
+    sim.step();
+    assert_eq!(sim.ship(ship0).lock_target(), None);
+    assert_eq!(lock_target(&sim), None);
+}
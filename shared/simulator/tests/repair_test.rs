@@ -0,0 +1,84 @@
+use nalgebra::vector;
+use oort_simulator::repair;
+use oort_simulator::ship::{self, fighter};
+use oort_simulator::simulation::{self, Code};
+use test_log::test;
+
+#[test]
+fn test_repair_zone() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    repair::add_repair_zone(&mut sim, vector![0.0, 0.0], 200.0, 0);
+    let friend = ship::create(
+        &mut sim,
+        vector![50.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let enemy = ship::create(
+        &mut sim,
+        vector![-50.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let outside = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let max_health = sim.ship(friend).data().max_health;
+    for &handle in [friend, enemy, outside].iter() {
+        sim.ship_mut(handle).data_mut().health = max_health / 2.0;
+    }
+
+    for _ in 0..60 {
+        sim.step();
+    }
+
+    let health = |handle| sim.ship(handle).data().health;
+    assert!(health(friend) > max_health / 2.0);
+    assert!(health(friend) <= max_health);
+    assert_eq!(health(enemy), max_health / 2.0);
+    assert_eq!(health(outside), max_health / 2.0);
+
+    // Repairs stop at full health.
+    for _ in 0..1000 {
+        sim.step();
+    }
+    assert_eq!(sim.ship(friend).data().health, max_health);
+
+    // Leaving the zone stops repairs.
+    sim.ship_mut(friend).data_mut().health = max_health / 2.0;
+    sim.ship_mut(friend)
+        .body()
+        .set_translation(vector![5000.0, 0.0], true);
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert_eq!(sim.ship(friend).data().health, max_health / 2.0);
+}
+
+#[test]
+fn test_repair_zone_allies() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    sim.set_allied(0, 1, true);
+    repair::add_repair_zone(&mut sim, vector![0.0, 0.0], 200.0, 0);
+    let ally = ship::create(
+        &mut sim,
+        vector![50.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let max_health = sim.ship(ally).data().max_health;
+    sim.ship_mut(ally).data_mut().health = max_health / 2.0;
+
+    for _ in 0..60 {
+        sim.step();
+    }
+
+    assert!(sim.ship(ally).data().health > max_health / 2.0);
+}
@@ -0,0 +1,100 @@
+use nalgebra::vector;
+use oort_simulator::replay::{MatchRecorder, MatchReplay, REPLAY_VERSION};
+use oort_simulator::ship::{self, fighter, target};
+use oort_simulator::simulation::{self, Code};
+use test_log::test;
+
+#[test]
+fn test_replay_round_trip() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![100.0, 20.0],
+        0.0,
+        fighter(0),
+    );
+    ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, -50.0],
+        1.0,
+        target(1),
+    );
+    let mut recorder = MatchRecorder::new(Vec::new(), "test", 0).unwrap();
+    let mut expected = None;
+    for i in 0..100 {
+        recorder.step(&mut sim).unwrap();
+        if i == 50 {
+            expected = Some(sim.render_state());
+        }
+    }
+    assert_eq!(recorder.frames(), 100);
+    let bytes = recorder.finish().unwrap();
+
+    let replay = MatchReplay::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(replay.header.version, REPLAY_VERSION);
+    assert_eq!(replay.header.scenario, "test");
+    assert_eq!(replay.len(), 100);
+    assert!(replay.frame(100).is_none());
+
+    let expected = expected.unwrap();
+    let frame = replay.frame(50).unwrap();
+    assert_eq!(frame.ships.len(), 2);
+    assert_eq!(frame.ships.len(), expected.ships.len());
+    assert_eq!(frame.bullets.len(), expected.bullets.len());
+    for (a, b) in frame.ships.iter().zip(expected.ships.iter()) {
+        assert_eq!(a.id, b.id);
+        assert!(nalgebra::distance(&a.position, &b.position) < 1e-9);
+        assert!((a.heading - b.heading).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_replay_version_mismatch() {
+    let mut encoder = libflate::gzip::Encoder::new(Vec::new()).unwrap();
+    std::io::Write::write_all(
+        &mut encoder,
+        b"{\"version\":999,\"scenario\":\"test\",\"seed\":0}\n",
+    )
+    .unwrap();
+    let bytes = encoder.finish().into_result().unwrap();
+    assert!(MatchReplay::from_reader(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn test_state_hash() {
+    let make_sim = || {
+        let mut sim = simulation::Simulation::new("test", 3, &[Code::None, Code::None]);
+        ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![100.0, 20.0],
+            0.0,
+            fighter(0),
+        );
+        let handle = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, -50.0],
+            1.0,
+            target(1),
+        );
+        (sim, handle)
+    };
+    let (mut sim_a, _) = make_sim();
+    let (mut sim_b, target_b) = make_sim();
+    for _ in 0..100 {
+        assert_eq!(sim_a.state_hash(), sim_b.state_hash());
+        sim_a.step();
+        sim_b.step();
+    }
+    assert_eq!(sim_a.state_hash(), sim_b.state_hash());
+
+    let position = sim_b.ship(target_b).position().vector;
+    sim_b
+        .ship_mut(target_b)
+        .body()
+        .set_translation(position + vector![1e-6, 0.0], true);
+    assert_ne!(sim_a.state_hash(), sim_b.state_hash());
+}
@@ -0,0 +1,320 @@
+use nalgebra::vector;
+use oort_simulator::bullet;
+use oort_simulator::scenario::{self, Boundary, Loadout, ScenarioDef, ShipDef};
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::{self, Code, ShipFilter};
+use test_log::test;
+
+fn asteroid_layout(seed: u32) -> Vec<(f64, f64, f64)> {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    let handles = scenario::add_asteroid_field(&mut sim, 50, seed);
+    handles
+        .iter()
+        .map(|&handle| {
+            let ship = sim.ship(handle);
+            let position = ship.position();
+            (position.x, position.y, ship.radius())
+        })
+        .collect()
+}
+
+#[test]
+fn test_asteroid_field_reproducible() {
+    let layout = asteroid_layout(1234);
+    assert_eq!(layout.len(), 50);
+    assert_eq!(layout, asteroid_layout(1234));
+    assert_ne!(layout, asteroid_layout(1235));
+}
+
+fn torus_sim(ships: Vec<ShipDef>) -> Box<simulation::Simulation> {
+    ScenarioDef {
+        name: "torus".to_string(),
+        world_size: 10000.0,
+        boundary: Boundary::Torus,
+        victory: Default::default(),
+        max_ticks: 1000,
+        walls: Default::default(),
+        ships,
+        loadouts: Default::default(),
+    }
+    .build(0, &[Code::None, Code::None])
+    .unwrap()
+}
+
+fn fighter_def(team: i32, x: f64, vx: f64) -> ShipDef {
+    ShipDef {
+        team,
+        class: ShipClass::Fighter,
+        position: vector![x, 0.0],
+        velocity: vector![vx, 0.0],
+        heading: 0.0,
+    }
+}
+
+#[test]
+fn test_torus_wraps_ships() {
+    let mut sim = torus_sim(vec![fighter_def(0, 4990.0, 600.0)]);
+    let handle = *sim.ships.iter().next().unwrap();
+    for _ in 0..10 {
+        sim.step();
+    }
+    let position = sim.ship(handle).position().vector;
+    assert!(
+        (position - vector![-4910.0, 0.0]).norm() < 1.0,
+        "{position}"
+    );
+}
+
+#[test]
+fn test_open_boundary_bullets() {
+    // Far outside the area the bullet collision grid covers.
+    let x = simulation::MAX_WORLD_SIZE;
+    let mut sim = ScenarioDef {
+        name: "open".to_string(),
+        world_size: 10000.0,
+        boundary: Boundary::Open,
+        victory: Default::default(),
+        max_ticks: 1000,
+        walls: Default::default(),
+        ships: vec![fighter_def(0, 0.0, 0.0), fighter_def(1, x, 0.0)],
+        loadouts: Default::default(),
+    }
+    .build(0, &[Code::None, Code::None])
+    .unwrap();
+    let target = sim.find_ships(|ship| ship.data().team == 1)[0];
+    let health = sim.ship(target).data().health;
+    bullet::create(
+        &mut sim,
+        vector![x - 500.0, 0.0],
+        vector![1000.0, 0.0],
+        bullet::BulletData {
+            mass: 1.0,
+            team: 0,
+            ttl: 5.0,
+            ..Default::default()
+        },
+    );
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!(sim.bullets.is_empty());
+    assert!(sim.ship(target).data().health < health);
+}
+
+#[test]
+fn test_torus_radar() {
+    let mut sim = torus_sim(vec![
+        fighter_def(0, 4500.0, 0.0),
+        fighter_def(1, -4500.0, 0.0),
+    ]);
+    let handle = sim.find_ships(|ship| ship.data().team == 0)[0];
+    let other = sim.find_ships(|ship| ship.data().team == 1)[0];
+    assert_eq!(
+        sim.shortest_displacement(
+            sim.ship(handle).position().vector,
+            sim.ship(other).position().vector
+        ),
+        vector![1000.0, 0.0]
+    );
+    let from = sim.ship(handle).position().vector.into();
+    let (nearest, distance) = sim
+        .nearest_ship(
+            from,
+            ShipFilter {
+                exclude: Some(handle),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(nearest, other);
+    assert!((distance - 1000.0).abs() < 1e-9, "{distance}");
+
+    // Looking across the edge finds the contact 1 km away, not 9 km.
+    sim.ship_mut(handle).radar_mut().unwrap().set_heading(0.0);
+    sim.step();
+    let contact = sim.ship(handle).radar().unwrap().scan().expect("contact");
+    assert!(
+        (contact.position - vector![5500.0, 0.0]).norm() < 100.0,
+        "{}",
+        contact.position
+    );
+}
+
+#[test]
+fn test_torus_radar_spatial_index() {
+    let ships: Vec<ShipDef> = (0..20)
+        .map(|i| ShipDef {
+            position: vector![
+                (i * 3719 % 10000) as f64 - 5000.0,
+                (i * 7121 % 10000) as f64 - 5000.0
+            ],
+            ..fighter_def(i % 2, 0.0, 0.0)
+        })
+        .collect();
+    let scan = |spatial_index: bool| {
+        let mut sim = torus_sim(ships.clone());
+        sim.set_radar_spatial_index(spatial_index);
+        let handles = sim.find_ships(|ship| ship.data().team == 0);
+        for &handle in handles.iter() {
+            sim.ship_mut(handle)
+                .radar_mut()
+                .unwrap()
+                .set_width(std::f64::consts::TAU);
+        }
+        sim.step();
+        handles
+            .iter()
+            .map(|&handle| {
+                let contacts = sim.ship(handle).radar().unwrap().scan_all().to_vec();
+                // Each ship is seen at most once, through its nearest copy.
+                assert!(contacts.len() <= 10);
+                contacts
+                    .iter()
+                    .map(|contact| (contact.position, contact.rssi))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let contacts = scan(true);
+    assert!(contacts.iter().any(|c| !c.is_empty()));
+    assert_eq!(contacts, scan(false));
+}
+
+#[test]
+fn test_king_of_the_hill() {
+    let mut sim = simulation::Simulation::new("king_of_the_hill", 0, &[Code::None, Code::None]);
+    let ship0 = sim.find_ships(|ship| ship.data().team == 0)[0];
+    let ship1 = sim.find_ships(|ship| ship.data().team == 1)[0];
+    let score = |sim: &simulation::Simulation, team| sim.team_scores()[&team];
+
+    sim.ship_mut(ship0)
+        .body()
+        .set_translation(vector![100.0, 0.0], true);
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!((score(&sim, 0) - 1.0).abs() < 1e-9, "{}", score(&sim, 0));
+    assert_eq!(score(&sim, 1), 0.0);
+    assert_eq!(sim.snapshot(0).team_scores, sim.team_scores());
+
+    // A contesting enemy halts scoring for both teams.
+    sim.ship_mut(ship1)
+        .body()
+        .set_translation(vector![-100.0, 0.0], true);
+    for _ in 0..60 {
+        sim.step();
+    }
+    assert!((score(&sim, 0) - 1.0).abs() < 1e-9, "{}", score(&sim, 0));
+    assert_eq!(score(&sim, 1), 0.0);
+    assert_eq!(sim.status(), scenario::Status::Running);
+}
+
+#[test]
+fn test_spawn_teams_symmetric() {
+    let layout = |seed: u32| -> Vec<Vec<(f64, f64, f64)>> {
+        let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+        scenario::spawn_teams_symmetric(&mut sim, 2, 3, seed)
+            .iter()
+            .map(|handles| {
+                handles
+                    .iter()
+                    .map(|&handle| {
+                        let ship = sim.ship(handle);
+                        let position = ship.position();
+                        (position.x, position.y, ship.heading())
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+
+    let teams = layout(1234);
+    assert_eq!(teams.len(), 2);
+    assert!(teams.iter().all(|ships| ships.len() == 3));
+    let mean = |ships: &Vec<(f64, f64, f64)>| {
+        ships
+            .iter()
+            .fold(vector![0.0, 0.0], |acc, &(x, y, _)| acc + vector![x, y])
+            / ships.len() as f64
+    };
+    let (mean0, mean1) = (mean(&teams[0]), mean(&teams[1]));
+    assert!((mean0 + mean1).norm() < 1e-6, "{mean0} {mean1}");
+    assert!(mean0.norm() > 1000.0);
+
+    assert_eq!(teams, layout(1234));
+    assert_ne!(teams, layout(1235));
+}
+
+#[test]
+fn test_team_loadout() {
+    let mut sim = ScenarioDef {
+        name: "loadout".to_string(),
+        world_size: 100000.0,
+        boundary: Default::default(),
+        victory: Default::default(),
+        max_ticks: 1000,
+        walls: Default::default(),
+        ships: vec![
+            ShipDef {
+                position: vector![0.0, 1000.0],
+                ..fighter_def(0, 0.0, 0.0)
+            },
+            ShipDef {
+                position: vector![0.0, -1000.0],
+                ..fighter_def(1, 0.0, 0.0)
+            },
+        ],
+        loadouts: vec![Loadout {
+            team: 0,
+            health: Some(50.0),
+            fuel: Some(100.0),
+            ammo: Some(20),
+        }],
+    }
+    .build(0, &[Code::None, Code::None])
+    .unwrap();
+    let ship0 = sim.find_ships(|ship| ship.data().team == 0)[0];
+    let ship1 = sim.find_ships(|ship| ship.data().team == 1)[0];
+    assert_eq!(sim.ship(ship0).data().health, 50.0);
+    assert_eq!(sim.ship(ship0).data().fuel, Some(100.0));
+    assert_eq!(sim.ship(ship0).data().guns[0].ammo, Some(20));
+    assert_eq!(sim.ship(ship1).data().guns[0].ammo, None);
+
+    let mut shots = [0, 0];
+    for _ in 0..300 {
+        for (team, &handle) in [ship0, ship1].iter().enumerate() {
+            let before = sim.bullets.len();
+            sim.ship_mut(handle).fire_gun(0);
+            if sim.bullets.len() > before {
+                shots[team] += 1;
+            }
+        }
+        sim.step();
+    }
+    assert_eq!(shots[0], 20);
+    assert!(shots[1] > shots[0], "{shots:?}");
+    assert_eq!(sim.ship(ship0).data().guns[0].ammo, Some(0));
+}
+
+#[test]
+fn test_survival_waves() {
+    let mut sim = simulation::Simulation::new("survival", 0, &[Code::None, Code::None]);
+    let enemies = |sim: &simulation::Simulation| {
+        sim.find_ships(|ship| ship.data().team == 1 && ship.data().class == ShipClass::Fighter)
+            .len()
+    };
+    let wave = |sim: &simulation::Simulation| sim.team_scores()[&0];
+    assert_eq!(enemies(&sim), 2);
+    assert_eq!(wave(&sim), 1.0);
+
+    // Waves of 2, 4 and 6 fighters arrive 30 seconds apart.
+    for _ in 0..(2 * 30 * 60) {
+        sim.step();
+    }
+    assert_eq!(enemies(&sim), 6);
+    assert_eq!(wave(&sim), 2.0);
+    sim.step();
+    assert_eq!(enemies(&sim), 12);
+    assert_eq!(wave(&sim), 3.0);
+    assert_eq!(sim.status(), scenario::Status::Running);
+}
@@ -0,0 +1,59 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, target};
+use oort_simulator::simulation::{self, Code, PHYSICS_TICK_LENGTH};
+use test_log::test;
+
+fn shoot_target(shield: f64) -> (Box<simulation::Simulation>, ship::ShipHandle) {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mut shooter = fighter(0);
+    shooter.guns[0].inaccuracy = 0.0;
+    let offset = shooter.guns[0].offset;
+    let ship0 = ship::create(&mut sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, shooter);
+    let ship1 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0] + offset,
+        vector![0.0, 0.0],
+        0.0,
+        ship::ShipData {
+            shield,
+            max_shield: shield,
+            ..target(1)
+        },
+    );
+    sim.ship_mut(ship0).fire_gun(0);
+    for _ in 0..30 {
+        sim.step();
+    }
+    (sim, ship1)
+}
+
+#[test]
+fn test_shield_absorbs_damage() {
+    let (sim, ship1) = shoot_target(0.0);
+    assert!(!sim.ships.contains(ship1));
+
+    let (sim, ship1) = shoot_target(1000.0);
+    assert!(sim.ships.contains(ship1));
+    assert_eq!(sim.ship(ship1).data().health, target(1).health);
+    assert!(sim.ship(ship1).data().shield < 1000.0);
+    assert!(sim.ship(ship1).shield_up());
+}
+
+#[test]
+fn test_shield_recharges() {
+    let (mut sim, ship1) = shoot_target(1000.0);
+    let shield = sim.ship(ship1).data().shield;
+    assert!(shield < 1000.0);
+
+    // No recharge during the delay after a hit.
+    for _ in 0..(ship::SHIELD_RECHARGE_DELAY_TICKS - 60) {
+        sim.step();
+    }
+    assert_eq!(sim.ship(ship1).data().shield, shield);
+
+    let recharge_ticks = (ship::SHIELD_RECHARGE_TIME / PHYSICS_TICK_LENGTH) as u32;
+    for _ in 0..(recharge_ticks + 60) {
+        sim.step();
+    }
+    assert_eq!(sim.ship(ship1).data().shield, 1000.0);
+}
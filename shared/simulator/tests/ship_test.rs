@@ -0,0 +1,132 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, fighter_with, ShipStats};
+use oort_simulator::simulation::{self, Code, PHYSICS_TICK_LENGTH};
+use test_log::test;
+
+#[test]
+fn test_default_stats() {
+    let stats = ShipStats::fighter();
+    let data = fighter(0);
+    assert_eq!(
+        data.max_forward_acceleration,
+        stats.max_forward_acceleration
+    );
+    assert_eq!(data.guns[0].reload_ticks, stats.gun_reload_ticks);
+    assert_eq!(data.guns[0].speed, stats.bullet_speed);
+}
+
+#[test]
+fn test_buffed_fighter() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let normal = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let buffed = ship::create(
+        &mut sim,
+        vector![0.0, 100.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter_with(
+            0,
+            ShipStats {
+                max_forward_acceleration: 120.0,
+                ..ShipStats::fighter()
+            },
+        ),
+    );
+
+    for _ in 0..60 {
+        for handle in [normal, buffed] {
+            sim.ship_mut(handle).accelerate(vector![1000.0, 0.0]);
+        }
+        sim.step();
+    }
+
+    let normal_speed = sim.ship(normal).velocity().x;
+    let buffed_speed = sim.ship(buffed).velocity().x;
+    assert!(normal_speed > 50.0);
+    assert!(
+        buffed_speed > normal_speed * 1.9,
+        "{buffed_speed} {normal_speed}"
+    );
+    assert!(sim.ship(buffed).position().x > sim.ship(normal).position().x);
+}
+
+#[test]
+fn test_acceleration_limits() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    let handle = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let spinner = ship::create(
+        &mut sim,
+        vector![0.0, 1000.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let data = fighter(0);
+    let n = 60;
+    for _ in 0..n {
+        sim.ship_mut(handle).accelerate(vector![1e6, 0.0]);
+        sim.ship_mut(spinner).torque(1e6);
+        sim.step();
+    }
+    // Physics runs before ships tick, so the last command takes effect in the
+    // following step.
+    sim.step();
+    let elapsed = n as f64 * PHYSICS_TICK_LENGTH;
+    let expected_speed = data.max_forward_acceleration * elapsed;
+    let expected_angular_velocity = data.max_angular_acceleration * elapsed;
+    let velocity = sim.ship(handle).velocity();
+    let angular_velocity = sim.ship(spinner).angular_velocity();
+    assert!(
+        (velocity.x - expected_speed).abs() < expected_speed * 1e-3,
+        "{velocity} {expected_speed}"
+    );
+    assert!(
+        (angular_velocity - expected_angular_velocity).abs() < expected_angular_velocity * 1e-3,
+        "{angular_velocity} {expected_angular_velocity}"
+    );
+
+    // Commands only last for one tick.
+    sim.step();
+    assert_eq!(sim.ship(spinner).angular_velocity(), angular_velocity);
+    assert!((sim.ship(handle).velocity() - velocity).norm() < 1e-9);
+}
+
+#[test]
+fn test_heading_to() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None]);
+    let handle = ship::create(
+        &mut sim,
+        vector![100.0, 100.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship = sim.ship(handle);
+    assert_eq!(ship.heading_to(vector![200.0, 100.0]), 0.0);
+    approx::assert_abs_diff_eq!(
+        ship.heading_to(vector![100.0, 0.0]),
+        1.5 * std::f64::consts::PI,
+        epsilon = 1e-9
+    );
+
+    // Clockwise torque spins the ship with negative angular velocity.
+    for _ in 0..10 {
+        sim.ship_mut(handle).torque(-1.0);
+        sim.step();
+    }
+    assert!(sim.ship(handle).angular_velocity() < 0.0);
+    let heading = sim.ship(handle).heading();
+    assert!(heading > std::f64::consts::PI, "{heading}");
+}
@@ -1,13 +1,17 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser as _;
 use glob::glob;
 use libflate::gzip::{EncodeOptions, Encoder, HeaderBuilder};
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 use std::cell::RefCell;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tar::Header;
 
+// Bump to invalidate every cached entry.
+const CACHE_VERSION: &str = "1";
+
 thread_local! {
   static COMPILERS: std::cell::RefCell<oort_compiler::Compiler> = RefCell::new(oort_compiler::Compiler::new());
 }
@@ -24,9 +28,18 @@ fn main() -> Result<()> {
         input: String,
         #[clap(short, long, default_value = "shared/builtin_ai/builtin-ai.tar.gz")]
         output: String,
+        #[clap(long, default_value = "target/builtin-ai-cache")]
+        cache_dir: String,
+        /// Recompile every AI instead of reusing cached WASM.
+        #[clap(long)]
+        no_cache: bool,
     }
     let args = Arguments::parse();
 
+    let cache_dir = PathBuf::from(&args.cache_dir);
+    std::fs::create_dir_all(&cache_dir)?;
+    let marker = compiler_marker()?;
+
     let paths: Vec<_> = glob(&format!("{}/**/*.rs", args.input))?
         .map(|x| x.unwrap())
         .filter(|x| !["lib.rs", "mod.rs"].contains(&x.file_name().unwrap().to_str().unwrap()))
@@ -37,9 +50,36 @@ fn main() -> Result<()> {
         .map(
             |path| -> Result<(PathBuf, /*rust*/ String, /*wasm*/ Vec<u8>)> {
                 let source_code = std::fs::read_to_string(path).unwrap();
-                let wasm = COMPILERS
-                    .with(|compiler_cell| compiler_cell.borrow_mut().compile(&source_code))?;
+                let cache_path =
+                    cache_dir.join(format!("{}.wasm", cache_key(&marker, &source_code)));
+                if !args.no_cache {
+                    if let Ok(optimized_wasm) = std::fs::read(&cache_path) {
+                        println!("{} cached", path.display());
+                        return Ok((path.clone(), source_code, optimized_wasm));
+                    }
+                }
+                let wasm = match COMPILERS.with(|compiler_cell| {
+                    compiler_cell
+                        .borrow_mut()
+                        .compile_with_diagnostics(&source_code)
+                }) {
+                    Ok(wasm) => wasm,
+                    Err(diagnostics) => {
+                        for d in diagnostics.iter() {
+                            eprintln!(
+                                "{}:{}:{}: {}: {}",
+                                path.display(),
+                                d.line,
+                                d.col,
+                                d.severity,
+                                d.message
+                            );
+                        }
+                        bail!("{} failed to compile", path.display());
+                    }
+                };
                 let optimized_wasm = wasm_opt(&wasm)?;
+                write_cache(&cache_path, &optimized_wasm)?;
                 println!(
                     "{} source {}K wasm {}K optimized {}K",
                     path.display(),
@@ -52,6 +92,15 @@ fn main() -> Result<()> {
         )
         .collect();
 
+    // Report every failure rather than stopping at the first one.
+    let errors: Vec<_> = results.iter().filter_map(|r| r.as_ref().err()).collect();
+    if !errors.is_empty() {
+        for e in errors.iter() {
+            eprintln!("{e}");
+        }
+        bail!("{} of {} AIs failed to build", errors.len(), results.len());
+    }
+
     let writer = std::fs::File::create(args.output)?;
     let header = HeaderBuilder::new().modification_time(0).finish();
     let options = EncodeOptions::new().header(header);
@@ -98,3 +147,48 @@ fn wasm_opt(wasm: &[u8]) -> Result<Vec<u8>> {
     let output = child.wait_with_output()?;
     Ok(output.stdout)
 }
+
+/// Identifies everything besides an AI's source that affects its WASM: the
+/// toolchain, wasm-opt, the compiler itself, and the API and manifests it
+/// builds against.
+fn compiler_marker() -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(CACHE_VERSION);
+    for program in ["rustc", "wasm-opt"] {
+        let output = std::process::Command::new(program)
+            .arg("--version")
+            .output()?;
+        hasher.update(output.stdout);
+    }
+    let mut inputs: Vec<PathBuf> = glob("shared/api/src/**/*.rs")?
+        .map(|x| x.unwrap())
+        .collect();
+    inputs.extend(
+        [
+            "shared/compiler/src/lib.rs",
+            "Cargo.toml.user",
+            "Cargo.lock.user",
+        ]
+        .map(PathBuf::from),
+    );
+    inputs.sort();
+    for path in inputs {
+        hasher.update(std::fs::read(path)?);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn cache_key(marker: &str, source_code: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(marker);
+    hasher.update(source_code);
+    format!("{:x}", hasher.finalize())
+}
+
+// Written via a rename so an interrupted build can't leave a truncated entry.
+fn write_cache(path: &Path, wasm: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, wasm)?;
+    std::fs::rename(tmp_path, path)?;
+    Ok(())
+}
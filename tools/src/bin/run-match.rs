@@ -0,0 +1,112 @@
+use anyhow::Context;
+use clap::Parser;
+use oort_simulator::scenario;
+use oort_simulator::simulation::{Code, Simulation};
+use rayon::prelude::*;
+use serde::Serialize;
+
+/// Runs matches between two compiled AIs and prints the results as JSON.
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    scenario: String,
+    /// WASM file for team 0.
+    ai0: String,
+    /// WASM file for team 1.
+    ai1: String,
+
+    /// Seed of the first match. Later matches use the following seeds.
+    #[clap(short, long, default_value_t = 0)]
+    seed: u32,
+
+    /// Number of matches to run.
+    #[clap(short, long, default_value_t = 1)]
+    repeat: u32,
+
+    /// Ticks after which an undecided match counts as a draw.
+    #[clap(short, long, default_value_t = scenario::MAX_TICKS)]
+    max_ticks: u32,
+}
+
+#[derive(Serialize, Debug)]
+struct MatchResult {
+    seed: u32,
+    /// Winning team, or None for a draw.
+    winner: Option<i32>,
+    ticks: u32,
+}
+
+#[derive(Serialize, Debug)]
+struct Summary {
+    matches: u32,
+    wins: [u32; 2],
+    draws: u32,
+    win_rates: [f64; 2],
+}
+
+fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("run_match=info"))
+        .init();
+    let args = Arguments::parse();
+    if scenario::load_safe(&args.scenario).is_none() {
+        anyhow::bail!("Unknown scenario {:?}", args.scenario);
+    }
+    if args.repeat == 0 {
+        anyhow::bail!("--repeat must be at least 1");
+    }
+
+    let mut codes = vec![];
+    for path in [&args.ai0, &args.ai1] {
+        let wasm = std::fs::read(path).with_context(|| format!("Failed to read {path:?}"))?;
+        codes.push(Code::Wasm(wasm));
+    }
+
+    let seeds: Vec<u32> = (0..args.repeat)
+        .map(|i| args.seed.wrapping_add(i))
+        .collect();
+    let results: Vec<MatchResult> = seeds
+        .into_par_iter()
+        .map(|seed| run_match(&args.scenario, seed, &codes, args.max_ticks))
+        .collect();
+
+    if args.repeat == 1 {
+        println!("{}", serde_json::to_string(&results[0])?);
+        return Ok(());
+    }
+
+    for result in results.iter() {
+        println!("{}", serde_json::to_string(result)?);
+    }
+    let mut wins = [0; 2];
+    let mut draws = 0;
+    for result in results.iter() {
+        match result.winner {
+            Some(team @ 0..=1) => wins[team as usize] += 1,
+            _ => draws += 1,
+        }
+    }
+    let summary = Summary {
+        matches: args.repeat,
+        wins,
+        draws,
+        win_rates: wins.map(|w| w as f64 / args.repeat as f64),
+    };
+    println!("{}", serde_json::to_string(&summary)?);
+    Ok(())
+}
+
+fn run_match(scenario_name: &str, seed: u32, codes: &[Code], max_ticks: u32) -> MatchResult {
+    let mut sim = Simulation::new(scenario_name, seed, codes);
+    while sim.status() == scenario::Status::Running && sim.tick() < max_ticks {
+        sim.step();
+    }
+    let winner = match sim.status() {
+        scenario::Status::Victory { team } => Some(team),
+        _ => None,
+    };
+    MatchResult {
+        seed,
+        winner,
+        ticks: sim.tick(),
+    }
+}